@@ -0,0 +1,104 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use todo_tracker_fs::check::check_plan_sources;
+use todo_tracker_fs::config::{find_broken_project_configs, DeserializedId};
+use todo_tracker_fs::diagnostic::Diagnostic;
+use todo_tracker_fs::tracker::load_project_plan_sources_diagnosed;
+
+use crate::config::{Config, WorkingMode};
+use crate::{discovery_rules, inherit_workspace_defaults, projects_list_from_search_roots};
+
+/// Problems found in the files of the checked projects.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub projects_count: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Validates the project configs and plans without changing them: the unparsable configs, the broken plan lines,
+/// the issue ids colliding across the plan files, the duplicate issue names and the missing subprojects.
+pub fn check<PID>(
+    local_search_roots: impl IntoIterator<Item = impl AsRef<Path>>,
+    config: &Config,
+) -> anyhow::Result<CheckReport>
+where
+    PID: DeserializedId + Clone + Ord + Display + TryFrom<String>,
+    <PID as TryFrom<String>>::Error: Into<anyhow::Error>,
+{
+    let local_search_roots: Vec<PathBuf> = local_search_roots
+        .into_iter()
+        .map(|root| root.as_ref().to_path_buf())
+        .collect();
+    let search_roots = match config.working_mode {
+        WorkingMode::Local => local_search_roots.clone(),
+        WorkingMode::Global if config.search.projects.enabled => config.search.projects.dirs.clone(),
+        WorkingMode::Global => Vec::new(),
+    };
+    let rules = discovery_rules(config)?;
+    let get_project_config_placement = |path: &Path| config.source.find_project_config_placement(path, None);
+
+    let mut diagnostics: Vec<_> =
+        find_broken_project_configs::<PID>(&search_roots, &rules, get_project_config_placement)
+            .into_iter()
+            .map(|(placement, err)| {
+                Diagnostic::error(0, 0, format!("invalid project config: {err}")).with_path(placement.as_ref())
+            })
+            .collect();
+
+    let mut projects = projects_list_from_search_roots::<PID>(local_search_roots, config)?;
+    if config.working_mode.is_global() && config.list.projects.enabled {
+        for (id, project_config) in &config.project {
+            let path = project_config.path.clone().unwrap_or_default();
+            let project_id = PID::try_from(id.clone()).map_err(Into::into)?;
+            match project_config.load_fs_project_config(project_id, &config.source) {
+                Ok(Some(loaded_project_config)) => {
+                    let loaded_project_config = inherit_workspace_defaults(loaded_project_config, config);
+                    projects.insert(loaded_project_config.id.clone(), loaded_project_config);
+                },
+                Ok(None) => diagnostics
+                    .push(Diagnostic::error(0, 0, format!("project `{id}` is not found at its path")).with_path(path)),
+                Err(err) => diagnostics
+                    .push(Diagnostic::error(0, 0, format!("invalid config of `{id}` project: {err}")).with_path(path)),
+            }
+        }
+    }
+
+    for (id, project_config) in &projects {
+        let root_dir = project_config.root_dir.clone().unwrap_or_default();
+        let source = config.source.with_overrides(&project_config.source);
+        match load_project_plan_sources_diagnosed(
+            project_config,
+            &source.manifest_filename_regex,
+            &source.issues_filename_regex,
+            &source.list,
+        ) {
+            Ok((sources, plan_diagnostics)) => {
+                diagnostics.extend(plan_diagnostics);
+                diagnostics.extend(check_plan_sources(&sources));
+            },
+            Err(err) => diagnostics.push(Diagnostic::error(0, 0, err.to_string()).with_path(&root_dir)),
+        }
+
+        let config_path = source
+            .find_project_config_placement(&root_dir, project_config.name.as_deref())
+            .map_or(root_dir, |placement| placement.as_ref().to_path_buf());
+        for subproject_id in &project_config.subprojects {
+            if !projects.contains_key(subproject_id) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        0,
+                        0,
+                        format!("subproject `{subproject_id}` of `{id}` project does not exist"),
+                    )
+                    .with_path(&config_path),
+                );
+            }
+        }
+    }
+
+    Ok(CheckReport {
+        projects_count: projects.len(),
+        diagnostics,
+    })
+}
@@ -9,7 +9,9 @@ use indexmap::{IndexMap, IndexSet};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use todo_tracker_fs::Placement;
-use todo_tracker_fs::config::{DeserializedId, FsProjectConfig};
+use todo_tracker_fs::config::{ConfigFormat, DeserializedId, FsProjectConfig, ProjectDefaults, SourceOverrides};
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::discovery::{DiscoveryRules, GlobError};
 use todo_tracker_fs::file::find_by_name_part;
 
 use crate::issue::Order;
@@ -27,6 +29,24 @@ pub struct SearchProjectsConfig {
     pub enabled: bool,
 
     pub dirs: Vec<PathBuf>,
+
+    /// Maximum depth of the searched directories below the search root, unlimited by default
+    pub max_depth: Option<usize>,
+
+    /// Glob patterns of the skipped directories, matched against the directory name and its relative path,
+    /// `.git`, `node_modules` and `target` by default
+    pub exclude: Vec<String>,
+
+    /// Skip the directories listed in `.gitignore` and `.todoignore` files
+    #[serde(default = "SearchProjectsConfig::default_use_ignore_files")]
+    pub use_ignore_files: bool,
+
+    /// Remember the discovered projects between runs, reading again only the changed directories
+    #[serde(default = "SearchProjectsConfig::default_cache")]
+    pub cache: bool,
+
+    /// Discovery cache location, `~/.todo/cache/projects.json` by default
+    pub cache_file: Option<PathBuf>,
 }
 
 impl Default for SearchProjectsConfig {
@@ -34,6 +54,11 @@ impl Default for SearchProjectsConfig {
         Self {
             enabled: Self::default_enabled(),
             dirs: Default::default(),
+            max_depth: None,
+            exclude: [".git", "node_modules", "target"].map(String::from).to_vec(),
+            use_ignore_files: Self::default_use_ignore_files(),
+            cache: Self::default_cache(),
+            cache_file: None,
         }
     }
 }
@@ -42,6 +67,27 @@ impl SearchProjectsConfig {
     pub const fn default_enabled() -> bool {
         true
     }
+
+    pub const fn default_use_ignore_files() -> bool {
+        true
+    }
+
+    pub const fn default_cache() -> bool {
+        true
+    }
+
+    pub fn discovery_rules(&self) -> Result<DiscoveryRules, GlobError> {
+        DiscoveryRules::new()
+            .with_max_depth(self.max_depth)
+            .with_ignore_files(self.use_ignore_files)
+            .with_exclude(&self.exclude)
+    }
+
+    pub fn cache_file_path(&self) -> Option<PathBuf> {
+        self.cache_file.clone().or_else(|| {
+            home::home_dir().map(|home| home.join(".todo").join("cache").join(DEFAULT_DISCOVERY_CACHE_FILE_NAME))
+        })
+    }
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -116,10 +162,10 @@ impl ProjectConfig {
             return Ok(None);
         };
 
-        if let Some(projects_root_dir) = &config.projects_root_dir {
-            if project_path.is_relative() {
-                project_path = projects_root_dir.join(project_path)
-            }
+        if let Some(projects_root_dir) = &config.projects_root_dir
+            && project_path.is_relative()
+        {
+            project_path = projects_root_dir.join(project_path)
         }
 
         let Some(config_placement) = config.find_project_config_placement(&project_path, None) else {
@@ -156,7 +202,7 @@ pub enum TitleConsist {
     IdAndName,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DisplayProjectTitleConfig {
     pub consist: TitleConsist,
@@ -216,7 +262,7 @@ impl DisplayProjectTitleConfig {
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DisplayProjectConfig {
     pub title: DisplayProjectTitleConfig,
@@ -226,13 +272,33 @@ pub struct DisplayProjectConfig {
     pub separate_projects: bool,
 }
 
+impl DisplayProjectConfig {
+    /// Applies the `[display]` table of the project config, the nested tables are merged key by key.
+    pub fn with_overrides(&self, overrides: &toml::Table) -> Result<Self, toml::de::Error> {
+        let mut table = toml::Table::try_from(self).expect("display config must be serializable to TOML");
+        merge_toml_table(&mut table, overrides);
+        table.try_into()
+    }
+}
+
+fn merge_toml_table(table: &mut toml::Table, overrides: &toml::Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(overrides)) => merge_toml_table(table, overrides),
+            _ => {
+                table.insert(key.clone(), value.clone());
+            },
+        }
+    }
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DisplayConfig {
     pub project: DisplayProjectConfig,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct SourceConfig {
     #[serde(default = "SourceConfig::default_use_manifest_file_by_default")]
@@ -254,6 +320,18 @@ pub struct SourceConfig {
     pub project_config_file: PathBuf,
 
     pub projects_root_dir: Option<PathBuf>,
+
+    /// File of the archived issues relative to the project root.
+    #[serde(default = "SourceConfig::default_archive_file")]
+    pub archive_file: PathBuf,
+
+    /// File of the time entries relative to the project root.
+    #[serde(default = "SourceConfig::default_time_log_file")]
+    pub time_log_file: PathBuf,
+
+    /// Markdown list syntax of the plans.
+    #[serde(default)]
+    pub list: ListDialect,
 }
 
 impl Default for SourceConfig {
@@ -266,6 +344,9 @@ impl Default for SourceConfig {
             issues_filename_example: Self::default_issues_filename_example(),
             project_config_file: Self::default_project_config_file(),
             projects_root_dir: None,
+            archive_file: Self::default_archive_file(),
+            time_log_file: Self::default_time_log_file(),
+            list: ListDialect::default(),
         }
     }
 }
@@ -295,6 +376,35 @@ impl SourceConfig {
         "Project.toml".into()
     }
 
+    pub fn default_archive_file() -> PathBuf {
+        "ARCHIVE.md".into()
+    }
+
+    pub fn default_time_log_file() -> PathBuf {
+        "TIME.log".into()
+    }
+
+    /// The config with the source settings of the project applied.
+    pub fn with_overrides(&self, overrides: &SourceOverrides) -> Self {
+        let mut config = self.clone();
+        if let Some(regex) = &overrides.manifest_filename_regex {
+            config.manifest_filename_regex = regex.clone();
+        }
+        if let Some(example) = &overrides.manifest_filename_example {
+            config.manifest_filename_example = example.clone();
+        }
+        if let Some(regex) = &overrides.issues_filename_regex {
+            config.issues_filename_regex = regex.clone();
+        }
+        if let Some(example) = &overrides.issues_filename_example {
+            config.issues_filename_example = example.clone();
+        }
+        if let Some(list) = &overrides.list {
+            config.list = list.clone();
+        }
+        config
+    }
+
     pub fn manifest_example_project_name(&self) -> &str {
         self.manifest_filename_regex
             .captures(&self.manifest_filename_example)
@@ -348,13 +458,26 @@ impl SourceConfig {
             .join(self.project_issues_file_name(project_name.unwrap_or_default()))
     }
 
+    /// Finds the project config file in the directory. Besides the configured file, the files with the same name
+    /// and the extension of the other config format are looked for, like `Project.yaml` or `Project.json`.
+    pub fn find_project_config_file(&self, root_dir: impl AsRef<Path>) -> Option<PathBuf> {
+        let project_config_file = root_dir.as_ref().join(&self.project_config_file);
+        let other_format_files = ConfigFormat::ALL
+            .iter()
+            .flat_map(ConfigFormat::extensions)
+            .map(|extension| project_config_file.with_extension(extension));
+
+        std::iter::once(project_config_file.clone())
+            .chain(other_format_files)
+            .find(|path| path.exists())
+    }
+
     pub fn find_project_config_placement(
         &self,
         root_dir: impl AsRef<Path>,
         project_name: Option<&str>,
     ) -> Option<Placement<PathBuf>> {
-        let project_config_file = root_dir.as_ref().join(&self.project_config_file);
-        if project_config_file.exists() {
+        if let Some(project_config_file) = self.find_project_config_file(root_dir.as_ref()) {
             Some(Placement::WholeFile(project_config_file))
         } else {
             let project_manifest_file = self.make_manifest_file_path(root_dir, project_name);
@@ -400,6 +523,7 @@ impl WorkingMode {
 
 pub const DEFAULT_CONFIG_FILE_NAME: &str = "todo.toml";
 pub const ROOT_CONFIG_ENV_KEY: &str = "TODO_ROOT_CONFIG";
+pub const DEFAULT_DISCOVERY_CACHE_FILE_NAME: &str = "projects.json";
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default)]
@@ -417,6 +541,9 @@ pub struct Config {
     pub issue: IssueConfig,
 
     pub project: IndexMap<String, ProjectConfig>,
+
+    /// Defaults of the workspace projects, which are used unless a project or its ancestors set the value.
+    pub defaults: ProjectDefaults,
 }
 
 impl Config {
@@ -1,14 +1,25 @@
+use std::collections::HashSet;
 use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
 
+use jiff::Zoned;
+use serde::{Deserialize, Serialize};
 use todo_lib::id::HashedId;
-use todo_lib::issue::Issue;
-use todo_tracker_fs::issue::SaveIssue;
-use todo_tracker_fs::{tracker, Placement};
+use todo_lib::issue::{Issue, IssueStatus, SourceLocation};
+use todo_lib::plan::Step;
+use todo_tracker_fs::config::FsProjectConfig;
+use todo_tracker_fs::file::find_by_regex;
+use todo_tracker_fs::issue::{self as fs_issue, Movement, SaveIssue, SubtreeIssue};
+use todo_tracker_fs::plan::ItemLines;
+use todo_tracker_fs::tracker::PlanSource;
+use todo_tracker_fs::{recurrence, tracker, Placement};
 
 use crate::config::SourceConfig;
 use crate::project::ProjectData;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Order {
     First,
     Last,
@@ -16,19 +27,24 @@ pub enum Order {
     // After(u64),
 }
 
+/// Adds the issue to the project, the section is a named ```` ```md todo <section> ```` block of the manifest.
+/// Returns the location of the added issue.
 pub fn add<ID: HashedId + Default>(
     ProjectData::Fs(project_metadata): ProjectData<ID>,
     config: &SourceConfig,
     order: Order,
+    section: Option<&str>,
     name: impl AsRef<str> + Into<String>,
     content: impl Into<String>,
-) -> io::Result<()> {
+) -> io::Result<SourceLocation> {
     let (project_config, _) = project_metadata.into_config();
+    let config = &config.with_overrides(&project_config.source);
 
     if let Some(plan) = tracker::load_project_plan(
         &project_config,
         &config.manifest_filename_regex,
         &config.issues_filename_regex,
+        &config.list,
     )? {
         let name_ref = name.as_ref();
         if plan.find_issue(name_ref).is_some() {
@@ -42,16 +58,282 @@ pub fn add<ID: HashedId + Default>(
     }
 
     let issue = Issue::new(0, name).with_content(content);
-    let project_root_dir = project_config.root_dir.unwrap_or_default();
-    let project_name = project_config.name;
-    let destination = config
-        .find_issues_placement(&project_root_dir, project_name.as_deref())
-        .unwrap_or_else(|| {
-            Placement::WholeFile(config.make_issues_file_path(project_root_dir, project_name.as_deref()))
-        });
-
-    match order {
-        Order::First => issue.add_first(&destination),
-        Order::Last => issue.add_last(&destination),
+    let destination = match section {
+        Some(section) => {
+            let project_root_dir = project_config.root_dir.clone().unwrap_or_default();
+            let manifest_file = find_by_regex(&project_root_dir, &config.manifest_filename_regex).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("section `{section}` requires the project manifest file"),
+                )
+            })?;
+            Placement::CodeBlockInFile(manifest_file)
+        },
+        None => issues_destination(config, &project_config)?,
+    };
+
+    let lines = match order {
+        Order::First => issue.add_first(&destination, section, &config.list)?,
+        Order::Last => issue.add_last(&destination, section, &config.list)?,
+    };
+    Ok(SourceLocation {
+        path: destination.as_ref().clone(),
+        lines,
+    })
+}
+
+/// Finds the issue of the project, the found issue carries its source location.
+pub fn get<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    issue_id: u64,
+) -> io::Result<Issue<u64>> {
+    let (project_config, _) = project_metadata.into_config();
+    let (_, sources) = load_sources(&project_config, config)?;
+    Ok(find_issue_source(&sources, issue_id)?.issue.clone())
+}
+
+/// Changes of the issue fields, `None` keeps the field as is.
+#[derive(Debug, Default, Clone)]
+pub struct IssueUpdate {
+    pub name: Option<String>,
+    pub content: Option<String>,
+    pub status: Option<IssueStatus>,
+}
+
+/// Changes the issue in its file. Completing the recurring issue inserts its next occurrence right before it,
+/// unless the issue with the name of the next occurrence already exists.
+pub fn update<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    issue_id: u64,
+    IssueUpdate { name, content, status }: IssueUpdate,
+) -> io::Result<()> {
+    let (project_config, _) = project_metadata.into_config();
+    let (config, sources) = load_sources(&project_config, config)?;
+    let found = find_issue_source(&sources, issue_id)?;
+
+    let mut issue = found.issue.clone();
+    if let Some(name) = name {
+        if name != issue.name && sources.iter().any(|source| source.plan.find_issue(&name).is_some()) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("issue `{name}` already exists"),
+            ));
+        }
+        issue.name = name;
+    }
+    if let Some(content) = content {
+        issue.content = content;
     }
+    let mut next_occurrence = None;
+    if let Some(status) = status {
+        if status == IssueStatus::Completed && !issue.is_completed() {
+            next_occurrence = recurrence::next_occurrence_name(&issue.name, Zoned::now().date())
+                .filter(|name| sources.iter().all(|source| source.plan.find_issue(name).is_none()));
+        }
+        issue.status = status;
+    }
+
+    fs_issue::replace_issue(found.source.placement.as_ref(), found.location, &issue, &config.list)?;
+
+    if let Some(name) = next_occurrence {
+        // The lines of the issue are changed by its update
+        let (_, sources) = load_sources(&project_config, &config)?;
+        let found = find_issue_source(&sources, issue_id)?;
+        recurrence::insert_next_occurrence(found.source, issue_id, &name, &config.list)?;
+    }
+    Ok(())
+}
+
+pub fn remove<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    issue_id: u64,
+) -> io::Result<()> {
+    let (project_config, _) = project_metadata.into_config();
+    let (_, sources) = load_sources(&project_config, config)?;
+    let found = find_issue_source(&sources, issue_id)?;
+    fs_issue::remove_lines(found.source.placement.as_ref(), found.subtree)
+}
+
+/// Moves the issue with its subissues inside the file it is defined in. Returns the new location of the issue,
+/// which id may change with the position when the issue has no explicit id.
+pub fn shift<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    issue_id: u64,
+    movement: Movement,
+) -> io::Result<SourceLocation> {
+    let (project_config, _) = project_metadata.into_config();
+    let (config, sources) = load_sources(&project_config, config)?;
+    let found = find_issue_source(&sources, issue_id)?;
+
+    let start = fs_issue::move_issue(found.source, &issue_id, movement, &config.list)?;
+    Ok(SourceLocation {
+        path: found.source.placement.as_ref().clone(),
+        lines: start..start + found.location.lines.len(),
+    })
+}
+
+/// Moves the issue with its subissues to the end of the issues placement of the destination project. The explicit
+/// ids colliding with the destination issues are reassigned from the destination `start_id`, the reassigned ids are
+/// returned as the `(old, new)` pairs.
+pub fn move_to_project<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    ProjectData::Fs(destination_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    issue_id: u64,
+) -> io::Result<Vec<(u64, u64)>> {
+    let (project_config, _) = project_metadata.into_config();
+    let (destination_config, _) = destination_metadata.into_config();
+    if project_config.root_dir == destination_config.root_dir {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("issue `{issue_id}` is already in the destination project"),
+        ));
+    }
+
+    let (source_config, sources) = load_sources(&project_config, config)?;
+    let found = find_issue_source(&sources, issue_id)?;
+    let mut subtree = fs_issue::issue_subtree(found.source, issue_id, &source_config.list)?;
+
+    let (destination_source_config, destination_sources) = load_sources(&destination_config, config)?;
+    if let Some(SubtreeIssue { issue, .. }) = subtree.iter().find(|SubtreeIssue { issue, .. }| {
+        destination_sources
+            .iter()
+            .any(|source| source.plan.find_issue(&issue.name).is_some())
+    }) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("issue `{}` already exists in the destination project", issue.name),
+        ));
+    }
+
+    let mut generated_ids_count = 0;
+    for source in &destination_sources {
+        generated_ids_count += fs_issue::generated_ids_count(source, &destination_source_config.list)?;
+    }
+    let reassigned = reassign_colliding_ids(
+        &mut subtree,
+        &destination_sources,
+        destination_config.start_id.unwrap_or(1),
+        generated_ids_count,
+    );
+
+    let destination = issues_destination(&destination_source_config, &destination_config)?;
+    let text = fs_issue::subtree_to_text(&subtree, &destination_source_config.list);
+    fs_issue::add_text_last(&destination, None, &text)?;
+
+    fs_issue::remove_lines(found.source.placement.as_ref(), found.subtree)?;
+
+    Ok(reassigned)
+}
+
+/// Gives the new ids to the explicit ids of the subtree used by the destination issues and milestones, including the
+/// ids which will be generated for the moved issues without explicit ids.
+fn reassign_colliding_ids(
+    subtree: &mut [SubtreeIssue],
+    destination_sources: &[PlanSource],
+    start_id: u64,
+    generated_ids_count: usize,
+) -> Vec<(u64, u64)> {
+    let mut taken: HashSet<u64> = destination_sources
+        .iter()
+        .flat_map(|source| source.plan.steps())
+        .map(|step| match step {
+            Step::Issue(id) | Step::Milestone(id) => *id,
+        })
+        .collect();
+    let moved_generated_ids_count = subtree.iter().filter(|issue| issue.explicit_id.is_none()).count();
+    let next_generated_id = start_id + generated_ids_count as u64;
+    taken.extend(next_generated_id..next_generated_id + moved_generated_ids_count as u64);
+
+    let mut reassigned = Vec::new();
+    for explicit_id in subtree.iter_mut().filter_map(|issue| issue.explicit_id.as_mut()) {
+        if taken.insert(*explicit_id) {
+            continue;
+        }
+        let new_id = (start_id..).find(|id| !taken.contains(id)).expect("free id must exist");
+        taken.insert(new_id);
+        reassigned.push((*explicit_id, new_id));
+        *explicit_id = new_id;
+    }
+    reassigned
+}
+
+/// Plan sources of the project with the source config overridden by the project, which is returned too.
+pub(crate) fn load_sources<ID: HashedId>(
+    project_config: &FsProjectConfig<ID>,
+    config: &SourceConfig,
+) -> io::Result<(SourceConfig, Vec<PlanSource>)> {
+    let config = config.with_overrides(&project_config.source);
+    let sources = tracker::load_project_plan_sources(
+        project_config,
+        &config.manifest_filename_regex,
+        &config.issues_filename_regex,
+        &config.list,
+    )?;
+    Ok((config, sources))
+}
+
+/// Destination of the new issues of the project. The first file matched by `source.issues_files` of the project
+/// if the globs are given, otherwise the issues file if it exists, then the manifest, otherwise the new issues file.
+pub(crate) fn issues_destination<ID: HashedId>(
+    config: &SourceConfig,
+    project_config: &FsProjectConfig<ID>,
+) -> io::Result<Placement<PathBuf>> {
+    let root_dir = project_config.root_dir.clone().unwrap_or_default();
+    if project_config.source.issues_files.is_empty() {
+        let project_name = project_config.name.as_deref();
+        return Ok(config
+            .find_issues_placement(&root_dir, project_name)
+            .unwrap_or_else(|| Placement::WholeFile(config.make_issues_file_path(root_dir, project_name))));
+    }
+
+    let issues_file = tracker::find_issues_files(
+        &root_dir,
+        &project_config.source.issues_files,
+        &config.manifest_filename_regex,
+    )?
+    .into_iter()
+    .next()
+    .ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no issues file of the project matches `source.issues_files`",
+        )
+    })?;
+    let is_manifest = issues_file
+        .file_name()
+        .is_some_and(|name| config.manifest_filename_regex.is_match(&name.to_string_lossy()));
+    if is_manifest {
+        Ok(Placement::CodeBlockInFile(issues_file))
+    } else {
+        Ok(Placement::WholeFile(issues_file))
+    }
+}
+
+/// Issue found in the plan sources of the project.
+pub(crate) struct FoundIssue<'a> {
+    pub source: &'a PlanSource,
+    pub issue: &'a Issue<u64>,
+    pub location: &'a ItemLines,
+
+    /// Lines of the issue together with its subissues.
+    pub subtree: Range<usize>,
+}
+
+pub(crate) fn find_issue_source(sources: &[PlanSource], issue_id: u64) -> io::Result<FoundIssue<'_>> {
+    sources
+        .iter()
+        .find_map(|source| {
+            Some(FoundIssue {
+                source,
+                issue: source.plan.get_issue(&issue_id)?,
+                location: source.lines.get_issue(&issue_id)?,
+                subtree: source.lines.issue_subtree(&source.plan, &issue_id)?,
+            })
+        })
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("issue `{issue_id}` not found")))
 }
@@ -1,19 +1,27 @@
 use std::io;
 use std::path::Path;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use indexmap::IndexMap;
 use thiserror::Error;
-use todo_tracker_fs::config::{find_project, find_projects, DeserializedId, FsProjectConfig, LoadConfigError};
+use todo_lib::id::HashedId;
+use todo_tracker_fs::cache::DiscoveryCache;
+use todo_tracker_fs::config::{
+    find_project, find_projects, find_projects_cached, DeserializedId, FsProjectConfig, LoadConfigError, ValueOrigin,
+};
+use todo_tracker_fs::discovery::DiscoveryRules;
 use todo_tracker_fs::FsTracker;
 
 use crate::config::{Config, WorkingMode};
 use crate::target::Location;
 
+pub mod check;
 pub mod config;
 pub mod issue;
+pub mod milestone;
 pub mod project;
 pub mod target;
+pub mod time;
 
 #[derive(Debug, Error)]
 pub enum OpenTrackerError {
@@ -34,9 +42,12 @@ where
     <PID as TryFrom<String>>::Error: Into<anyhow::Error>,
 {
     match location {
-        Location::Path(path) => Ok(find_project::<PID>(path, |path| {
-            config.source.find_project_config_placement(path, None)
-        })),
+        Location::Path(path) => {
+            Ok(
+                find_project::<PID>(path, |path| config.source.find_project_config_placement(path, None))
+                    .map(|project_config| inherit_workspace_defaults(project_config, config)),
+            )
+        },
         Location::Id(id) => locate_project_config_by_id::<PID>(id.to_string(), local_search_roots, config),
         Location::Name(name) => locate_project_config_by_name::<PID>(name, local_search_roots, config),
         Location::IdOrName(id_or_name) => {
@@ -69,7 +80,7 @@ where
             }
         },
         None => {
-            projects.extend(projects_list_from_search_roots(local_search_roots, config));
+            projects.extend(projects_list_from_search_roots(local_search_roots, config)?);
 
             if config.working_mode.is_global() && config.list.projects.enabled {
                 for (id, project_config) in &config.project {
@@ -82,6 +93,7 @@ where
                                 project_config.path
                             )
                         })?;
+                    let loaded_project_config = inherit_workspace_defaults(loaded_project_config, config);
 
                     projects.insert(loaded_project_config.id.clone(), loaded_project_config);
                 }
@@ -95,21 +107,80 @@ where
 fn projects_list_from_search_roots<PID>(
     local_search_roots: impl IntoIterator<Item = impl AsRef<Path>>,
     config: &Config,
-) -> IndexMap<PID, FsProjectConfig<PID>>
+) -> anyhow::Result<IndexMap<PID, FsProjectConfig<PID>>>
 where
     PID: DeserializedId + Ord + Clone,
 {
-    match config.working_mode {
-        WorkingMode::Local => find_projects::<PID>(local_search_roots, |path| {
-            config.source.find_project_config_placement(path, None)
-        }),
-        WorkingMode::Global if config.search.projects.enabled => {
-            find_projects::<PID>(&config.search.projects.dirs, |path| {
-                config.source.find_project_config_placement(path, None)
-            })
+    let rules = discovery_rules(config)?;
+    let get_project_config_placement = |path: &Path| config.source.find_project_config_placement(path, None);
+
+    let projects = match config.working_mode {
+        WorkingMode::Local => find_projects::<PID>(local_search_roots, &rules, get_project_config_placement),
+        WorkingMode::Global if config.search.projects.enabled => match config.search.projects.cache_file_path() {
+            Some(cache_file) if config.search.projects.cache => {
+                let mut cache = DiscoveryCache::load(&cache_file).unwrap_or_default();
+                let projects = find_projects_cached::<PID>(
+                    &config.search.projects.dirs,
+                    &mut cache,
+                    &rules,
+                    get_project_config_placement,
+                );
+                // The cache only speeds up the search, so failing to store it is not an error
+                cache.save(&cache_file).ok();
+                projects
+            },
+            _ => find_projects::<PID>(&config.search.projects.dirs, &rules, get_project_config_placement),
         },
         _ => Default::default(),
+    };
+
+    Ok(projects
+        .into_iter()
+        .map(|(id, project_config)| (id, inherit_workspace_defaults(project_config, config)))
+        .collect())
+}
+
+/// Fills the values which are set neither by the project nor by its ancestors with the workspace defaults.
+fn inherit_workspace_defaults<PID>(mut project_config: FsProjectConfig<PID>, config: &Config) -> FsProjectConfig<PID>
+where
+    PID: HashedId + Clone,
+{
+    project_config.inherit(&config.defaults, &ValueOrigin::Workspace);
+    project_config
+}
+
+fn discovery_rules(config: &Config) -> anyhow::Result<DiscoveryRules> {
+    config
+        .search
+        .projects
+        .discovery_rules()
+        .context("invalid `search.projects.exclude` pattern")
+}
+
+/// Walks the search roots of the global mode again, refreshing the discovery cache from scratch.
+pub fn rebuild_discovery_cache(config: &Config) -> anyhow::Result<usize> {
+    let cache_file = config
+        .search
+        .projects
+        .cache_file_path()
+        .ok_or_else(|| anyhow!("could not determine the discovery cache location"))?;
+
+    let rules = discovery_rules(config)?;
+    let mut cache = DiscoveryCache::default();
+    let projects = find_projects_cached::<String>(&config.search.projects.dirs, &mut cache, &rules, |path| {
+        config.source.find_project_config_placement(path, None)
+    });
+    cache.save(&cache_file)?;
+
+    Ok(projects.len())
+}
+
+/// Removes the discovery cache file.
+pub fn clear_discovery_cache(config: &Config) -> anyhow::Result<()> {
+    if let Some(cache_file) = config.search.projects.cache_file_path() {
+        DiscoveryCache::clear(cache_file)?;
     }
+    Ok(())
 }
 
 pub fn locate_project_config_by_id<PID>(
@@ -134,12 +205,12 @@ where
                         )
                     })?;
 
-                return Ok(Some(loaded_project_config));
+                return Ok(Some(inherit_workspace_defaults(loaded_project_config, config)));
             }
         }
     }
 
-    let projects_list = projects_list_from_search_roots(local_search_roots, config);
+    let projects_list = projects_list_from_search_roots(local_search_roots, config)?;
     if !projects_list.is_empty() {
         let project_id = PID::try_from(serialized_id.into()).map_err(Into::into)?;
         for (id, project_config) in projects_list {
@@ -177,12 +248,12 @@ where
 
             let name = loaded_project_config.name.as_ref();
             if name == loaded_project_config.name.as_ref() {
-                return Ok(Some(loaded_project_config));
+                return Ok(Some(inherit_workspace_defaults(loaded_project_config, config)));
             }
         }
     }
 
-    let projects_list = projects_list_from_search_roots(local_search_roots, config);
+    let projects_list = projects_list_from_search_roots(local_search_roots, config)?;
     for (_, project_config) in projects_list {
         if project_config.name.as_deref() == Some(name) {
             return Ok(Some(project_config));
@@ -206,5 +277,6 @@ where
         projects,
         &config.source.manifest_filename_regex,
         &config.source.issues_filename_regex,
-    )?)
+        &config.source.list,
+    ))
 }
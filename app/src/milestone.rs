@@ -0,0 +1,183 @@
+use std::io;
+use std::ops::Range;
+
+use todo_lib::id::HashedId;
+use todo_lib::issue::Milestone;
+use todo_lib::plan::Step;
+use todo_tracker_fs::milestone as fs_milestone;
+use todo_tracker_fs::plan::ItemLines;
+use todo_tracker_fs::tracker::PlanSource;
+
+use crate::config::SourceConfig;
+use crate::issue::{issues_destination, load_sources};
+use crate::project::ProjectData;
+
+/// Position of the added milestone in the plan steps, the issues above the milestone up to the previous one are
+/// needed to reach it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// After all issues of the project issues placement
+    Last,
+
+    /// Right before the top-level issue or the milestone with the given id
+    Before(u64),
+
+    /// Right after the top-level issue with its subissues or the milestone with the given id
+    After(u64),
+}
+
+/// Adds the milestone to the project at the given position.
+pub fn add<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    position: Position,
+    name: &str,
+) -> io::Result<()> {
+    let (project_config, _) = project_metadata.into_config();
+    let (config, sources) = load_sources(&project_config, config)?;
+    check_name_is_free(&sources, name)?;
+
+    match position {
+        Position::Last => {
+            let destination = issues_destination(&config, &project_config)?;
+            fs_milestone::insert_milestone(&destination, None, name)
+        },
+        Position::Before(id) => {
+            let (source, lines) = find_step_lines(&sources, id)?;
+            fs_milestone::insert_milestone(&source.placement, Some(lines.start), name)
+        },
+        Position::After(id) => {
+            let (source, lines) = find_step_lines(&sources, id)?;
+            fs_milestone::insert_milestone(&source.placement, Some(lines.end), name)
+        },
+    }
+}
+
+/// Finds the milestone of the project, the found milestone carries its source location.
+pub fn get<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    milestone_id: u64,
+) -> io::Result<Milestone<u64>> {
+    let (project_config, _) = project_metadata.into_config();
+    let (_, sources) = load_sources(&project_config, config)?;
+    sources
+        .iter()
+        .find_map(|source| source.plan.get_milestone(&milestone_id))
+        .cloned()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("milestone `{milestone_id}` not found")))
+}
+
+/// Milestones of the project in the plan order, each with the issues needed to reach it.
+pub fn list<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+) -> io::Result<Vec<Milestone<u64>>> {
+    let (project_config, _) = project_metadata.into_config();
+    let (_, sources) = load_sources(&project_config, config)?;
+
+    Ok(sources
+        .iter()
+        .flat_map(|source| {
+            source.plan.steps().iter().filter_map(|step| match step {
+                Step::Milestone(id) => source.plan.get_milestone(id).cloned(),
+                Step::Issue(_) => None,
+            })
+        })
+        .collect())
+}
+
+/// Renames the milestone, keeping its explicit id.
+pub fn rename<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    milestone_id: u64,
+    name: &str,
+) -> io::Result<()> {
+    let (project_config, _) = project_metadata.into_config();
+    let (_, sources) = load_sources(&project_config, config)?;
+    let (source, location) = find_milestone_source(&sources, milestone_id)?;
+    let is_renamed = source
+        .plan
+        .get_milestone(&milestone_id)
+        .is_some_and(|milestone| milestone.name != name);
+    if is_renamed {
+        check_name_is_free(&sources, name)?;
+    }
+
+    fs_milestone::rename_milestone(source.placement.as_ref(), location, name)
+}
+
+/// Removes the milestone, its issues are merged into the group of the next milestone.
+pub fn remove<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    milestone_id: u64,
+) -> io::Result<()> {
+    let (project_config, _) = project_metadata.into_config();
+    let (_, sources) = load_sources(&project_config, config)?;
+    let (source, location) = find_milestone_source(&sources, milestone_id)?;
+
+    fs_milestone::remove_milestone(source.placement.as_ref(), location)
+}
+
+fn check_name_is_free(sources: &[PlanSource], name: &str) -> io::Result<()> {
+    let is_taken = sources.iter().any(|source| {
+        source.plan.steps().iter().any(|step| match step {
+            Step::Milestone(id) => source
+                .plan
+                .get_milestone(id)
+                .is_some_and(|milestone| milestone.name == name),
+            Step::Issue(_) => false,
+        })
+    });
+
+    if is_taken {
+        Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("milestone `{name}` already exists"),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Lines of the top-level issue with its subissues or of the milestone, the milestone cannot be placed between
+/// the subissues.
+fn find_step_lines(sources: &[PlanSource], id: u64) -> io::Result<(&PlanSource, Range<usize>)> {
+    for source in sources {
+        if source
+            .plan
+            .get_issue(&id)
+            .is_some_and(|issue| issue.parent_id.is_some())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("issue `{id}` is a subissue, milestones can be placed only next to top-level issues"),
+            ));
+        }
+        if let Some(lines) = source.lines.issue_subtree(&source.plan, &id) {
+            return Ok((source, lines));
+        }
+        if let Some(ItemLines { lines, .. }) = source.lines.get_milestone(&id) {
+            return Ok((source, lines.clone()));
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("issue or milestone `{id}` not found"),
+    ))
+}
+
+fn find_milestone_source(sources: &[PlanSource], milestone_id: u64) -> io::Result<(&PlanSource, &ItemLines)> {
+    sources
+        .iter()
+        .find_map(|source| {
+            source
+                .lines
+                .get_milestone(&milestone_id)
+                .map(|location| (source, location))
+        })
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("milestone `{milestone_id}` not found")))
+}
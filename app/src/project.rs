@@ -1,12 +1,19 @@
-use std::fs;
 use std::path::PathBuf;
+use std::{fs, io};
 
 use anyhow::{anyhow, Context};
+use todo_lib::id::HashedId;
+use todo_lib::plan::{Plan, Step};
+use todo_tracker_fs::archive::{append_to_archive, finished_steps, remove_steps, steps_lines};
 use todo_tracker_fs::config::{FsProjectConfig, SerializedId};
+use todo_tracker_fs::diagnostic::Diagnostic;
 use todo_tracker_fs::file::find_by_name_part;
+use todo_tracker_fs::format::format_file;
+use todo_tracker_fs::tracker;
 
 pub use self::metadata::{FsProjectMetadata, ProjectData};
 use crate::config::SourceConfig;
+use crate::issue::load_sources;
 
 pub mod metadata;
 
@@ -59,8 +66,7 @@ pub fn init<ID: SerializedId>(
             ));
         }
 
-        let project_config_file = root_dir.join(&source_config.project_config_file);
-        if project_config_file.exists() {
+        if let Some(project_config_file) = source_config.find_project_config_file(&root_dir) {
             return Err(anyhow!(
                 "destination `{}` already exists",
                 project_config_file.display()
@@ -81,6 +87,90 @@ pub fn init<ID: SerializedId>(
     Ok(())
 }
 
+/// Formats the plan files of the project in the canonical layout, the files are only checked if `check` is set.
+/// Returns the files which are not formatted.
+pub fn format<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    check: bool,
+) -> io::Result<Vec<PathBuf>> {
+    let (project_config, _) = project_metadata.into_config();
+    let config = &config.with_overrides(&project_config.source);
+    let (sources, diagnostics) = tracker::load_project_plan_sources_diagnosed(
+        &project_config,
+        &config.manifest_filename_regex,
+        &config.issues_filename_regex,
+        &config.list,
+    )?;
+    // The formatting could change the meaning of the broken plan
+    if let Some(diagnostic) = diagnostics.into_iter().find(Diagnostic::is_error) {
+        return Err(diagnostic.into());
+    }
+
+    let mut unformatted = Vec::new();
+    for source in sources {
+        let path = source.placement.as_ref();
+        if let Some(formatted) = format_file(&source.placement, &config.list)? {
+            if !check {
+                formatted.save(path)?;
+            }
+            unformatted.push(path.clone());
+        }
+    }
+    Ok(unformatted)
+}
+
+/// Archived steps of the project.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveReport {
+    pub issues_count: usize,
+    pub milestones_count: usize,
+}
+
+/// Moves the finished issues with their subissues and the passed milestones out of the plan files of the project
+/// into its archive file, under the `## <date>` heading.
+pub fn archive<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    date: &str,
+) -> io::Result<ArchiveReport> {
+    let (project_config, _) = project_metadata.into_config();
+    let (config, sources) = load_sources(&project_config, config)?;
+
+    let mut report = ArchiveReport::default();
+    let mut archived = Vec::new();
+    let mut finished = Vec::new();
+    for source in &sources {
+        let steps = finished_steps(&source.plan);
+        for step in &steps {
+            match step {
+                Step::Issue(id) => report.issues_count += subtree_issues_count(&source.plan, id),
+                Step::Milestone(_) => report.milestones_count += 1,
+            }
+        }
+        if !steps.is_empty() {
+            archived.extend(steps_lines(source, &steps)?);
+            finished.push((source, steps));
+        }
+    }
+
+    // The steps are removed from the plan files only once they are kept in the archive
+    if !archived.is_empty() {
+        let archive_file = project_config.root_dir.unwrap_or_default().join(&config.archive_file);
+        append_to_archive(archive_file, date, &archived)?;
+    }
+    for (source, steps) in finished {
+        remove_steps(source, &steps)?;
+    }
+    Ok(report)
+}
+
+fn subtree_issues_count(plan: &Plan<u64>, id: &u64) -> usize {
+    1 + plan.get_issue(id).map_or(0, |issue| {
+        issue.subissues.iter().map(|id| subtree_issues_count(plan, id)).sum()
+    })
+}
+
 pub fn default_path(root: impl Into<PathBuf>, config: &SourceConfig) -> anyhow::Result<PathBuf> {
     let mut current_dir = root.into();
     let current_dir_string: String = current_dir.to_string_lossy().into();
@@ -88,8 +178,7 @@ pub fn default_path(root: impl Into<PathBuf>, config: &SourceConfig) -> anyhow::
     let filename_part = config.manifest_filename_example.replace(example_project_name, "");
 
     loop {
-        let project_config_file = current_dir.join(&config.project_config_file);
-        if project_config_file.exists() {
+        if config.find_project_config_file(&current_dir).is_some() {
             return Ok(current_dir);
         } else {
             if let Some(_manifest_file) = find_by_name_part(&current_dir, &filename_part) {
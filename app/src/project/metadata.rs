@@ -13,7 +13,7 @@ pub enum ProjectData<ID: HashedId = String> {
     Fs(FsProjectMetadata<ID>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FsProjectMetadata<ID: HashedId> {
     id: Option<ID>,
     name: String,
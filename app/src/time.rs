@@ -0,0 +1,236 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+use indexmap::{IndexMap, IndexSet};
+use jiff::{SignedDuration, Timestamp};
+use todo_lib::id::HashedId;
+use todo_lib::plan::{Plan, Step};
+use todo_tracker_fs::time_log::{load_time_log, remap_entries, start_entry, stop_entry, TimeEntry};
+use todo_tracker_fs::tracker::PlanSource;
+use todo_tracker_fs::{issue as fs_issue, FsTracker};
+
+use crate::config::SourceConfig;
+use crate::issue::{find_issue_source, load_sources};
+use crate::project::ProjectData;
+
+/// Time spent on the issue together with its subissues.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueTime {
+    pub id: u64,
+
+    /// The name of the issue, `None` if the issue is not found in the plan anymore.
+    pub name: Option<String>,
+    pub time: SignedDuration,
+    pub subissues: Vec<IssueTime>,
+}
+
+/// Time spent on the project together with its subprojects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectTime<PID> {
+    pub id: PID,
+    pub name: String,
+    pub time: SignedDuration,
+    pub issues: Vec<IssueTime>,
+    pub subprojects: Vec<ProjectTime<PID>>,
+}
+
+/// Started time entry of the issue.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StartReport {
+    /// The running entry stopped by the start.
+    pub stopped: Option<TimeEntry>,
+
+    /// The id written into the issue line, `None` if the issue already had an explicit id.
+    pub written_id: Option<u64>,
+}
+
+/// Starts the time entry of the issue in the time log of the project, the running entry is stopped. With `pin_id`
+/// the issue without explicit id gets one, so its entries stay with it when the issues are reordered. The ids
+/// generated for the next issues are shifted by that, their entries are moved to the new ids.
+pub fn start<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    issue_id: u64,
+    at: Timestamp,
+    pin_id: bool,
+) -> io::Result<StartReport> {
+    let (project_config, _) = project_metadata.into_config();
+    let (source_config, sources) = load_sources(&project_config, config)?;
+    let found = find_issue_source(&sources, issue_id)?;
+    let time_log_file = project_config
+        .root_dir
+        .clone()
+        .unwrap_or_default()
+        .join(&config.time_log_file);
+    if !pin_id {
+        let stopped = start_entry(time_log_file, issue_id, at)?;
+        return Ok(StartReport {
+            stopped,
+            written_id: None,
+        });
+    }
+
+    let time_log = load_time_log(&time_log_file)?;
+    let start_id = project_config.start_id.unwrap_or(1);
+    let mut generated_ids_count = 0;
+    for plan_source in &sources {
+        generated_ids_count += fs_issue::generated_ids_count(plan_source, &source_config.list)?;
+    }
+    let mut taken: HashSet<u64> = sources
+        .iter()
+        .flat_map(|plan_source| plan_source.plan.steps())
+        .map(|step| match step {
+            Step::Issue(id) | Step::Milestone(id) => *id,
+        })
+        .chain(time_log.iter().map(|entry| entry.issue_id))
+        .collect();
+    taken.extend(start_id..start_id + generated_ids_count as u64);
+    let explicit_id = (start_id..).find(|id| !taken.contains(id)).expect("free id must exist");
+
+    let path = found.source.placement.as_ref();
+    let written_id =
+        fs_issue::write_explicit_id(path, found.location, explicit_id, &source_config.list)?.then_some(explicit_id);
+    if written_id.is_some() {
+        let (_, reloaded) = load_sources(&project_config, &source_config)?;
+        remap_entries(&time_log_file, &reassigned_ids(&sources, &reloaded))?;
+    }
+
+    let stopped = start_entry(time_log_file, written_id.unwrap_or(issue_id), at)?;
+    Ok(StartReport { stopped, written_id })
+}
+
+/// New ids of the issues changed between the loads of the same sources, the issues are matched by their lines.
+fn reassigned_ids(sources: &[PlanSource], reloaded: &[PlanSource]) -> HashMap<u64, u64> {
+    let mut ids = HashMap::new();
+    for (source, reloaded) in sources.iter().zip(reloaded) {
+        let reloaded_ids: HashMap<usize, u64> = reloaded
+            .plan
+            .steps()
+            .iter()
+            .filter_map(|step| match step {
+                Step::Issue(id) => Some((reloaded.lines.get_issue(id)?.lines.start, *id)),
+                Step::Milestone(_) => None,
+            })
+            .collect();
+        for step in source.plan.steps() {
+            if let Step::Issue(id) = step
+                && let Some(location) = source.lines.get_issue(id)
+                && let Some(new_id) = reloaded_ids.get(&location.lines.start)
+                && new_id != id
+            {
+                ids.insert(*id, *new_id);
+            }
+        }
+    }
+    ids
+}
+
+/// Stops the running time entry of the project, `None` if no entry is running.
+pub fn stop<ID: HashedId + Default>(
+    ProjectData::Fs(project_metadata): ProjectData<ID>,
+    config: &SourceConfig,
+    at: Timestamp,
+) -> io::Result<Option<TimeEntry>> {
+    let (project_config, _) = project_metadata.into_config();
+    let time_log_file = project_config.root_dir.unwrap_or_default().join(&config.time_log_file);
+    stop_entry(time_log_file, at)
+}
+
+/// Sums up the time entries of the tracker projects started since the given time, the running entries last until
+/// `now`. The time is aggregated by the issues with their subissues and by the projects with their subprojects,
+/// the issues and projects without the spent time are skipped.
+pub fn report<PID: HashedId + Clone>(
+    tracker: &FsTracker<PID>,
+    config: &SourceConfig,
+    since: Option<Timestamp>,
+    now: Timestamp,
+) -> io::Result<Vec<ProjectTime<PID>>> {
+    let subprojects = tracker.subprojects();
+    let mut reports = Vec::new();
+    for id in tracker.projects().keys() {
+        if !tracker.project_parents().contains_key(id) {
+            reports.extend(project_time(tracker, &subprojects, config, id, since, now)?);
+        }
+    }
+    Ok(reports)
+}
+
+fn project_time<PID: HashedId + Clone>(
+    tracker: &FsTracker<PID>,
+    subprojects: &IndexMap<PID, IndexSet<PID>>,
+    config: &SourceConfig,
+    id: &PID,
+    since: Option<Timestamp>,
+    now: Timestamp,
+) -> io::Result<Option<ProjectTime<PID>>> {
+    let Some(project) = tracker.projects().get(id) else {
+        return Ok(None);
+    };
+
+    let mut issues = Vec::new();
+    if let Some(root_dir) = tracker.project_root_dir(id) {
+        let mut spent: HashMap<u64, SignedDuration> = HashMap::new();
+        for entry in load_time_log(root_dir.join(&config.time_log_file))? {
+            if since.is_none_or(|since| entry.start >= since) {
+                *spent.entry(entry.issue_id).or_default() += entry.duration(now);
+            }
+        }
+
+        if let Some(plan) = tracker.project_plan(id)? {
+            let top_issues = plan.steps().iter().filter_map(|step| match step {
+                Step::Issue(id) => plan.get_issue(id).filter(|issue| issue.parent_id.is_none()),
+                Step::Milestone(_) => None,
+            });
+            for issue in top_issues {
+                issues.extend(issue_time(plan, &mut spent, issue.id));
+            }
+        }
+
+        // The entries of the issues removed from the plan are reported by their ids
+        let mut unknown: Vec<_> = spent.into_iter().filter(|(_, time)| !time.is_zero()).collect();
+        unknown.sort_by_key(|(id, _)| *id);
+        issues.extend(unknown.into_iter().map(|(id, time)| IssueTime {
+            id,
+            name: None,
+            time,
+            subissues: Vec::new(),
+        }));
+    }
+
+    let mut children = Vec::new();
+    for subproject_id in subprojects.get(id).into_iter().flatten() {
+        children.extend(project_time(tracker, subprojects, config, subproject_id, since, now)?);
+    }
+
+    let time = issues.iter().map(|issue| issue.time).sum::<SignedDuration>()
+        + children.iter().map(|child| child.time).sum::<SignedDuration>();
+    if time.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(ProjectTime {
+        id: id.clone(),
+        name: project.name().to_string(),
+        time,
+        issues,
+        subprojects: children,
+    }))
+}
+
+/// Takes the spent time of the issue and its subissues out of the map.
+fn issue_time(plan: &Plan<u64>, spent: &mut HashMap<u64, SignedDuration>, id: u64) -> Option<IssueTime> {
+    let issue = plan.get_issue(&id)?;
+    let subissues: Vec<_> = issue
+        .subissues
+        .iter()
+        .filter_map(|id| issue_time(plan, spent, *id))
+        .collect();
+    let time = spent.remove(&id).unwrap_or_default() + subissues.iter().map(|issue| issue.time).sum::<SignedDuration>();
+
+    (!time.is_zero()).then(|| IssueTime {
+        id,
+        name: Some(issue.name.clone()),
+        time,
+        subissues,
+    })
+}
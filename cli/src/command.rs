@@ -1,15 +1,24 @@
-use std::env;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::{env, process};
 
 use anyhow::{anyhow, Context};
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use jiff::{SignedDuration, Timestamp, Zoned};
+use todo_app::check::CheckReport;
 use todo_app::config::Config;
-use todo_app::project::{self, FsProjectMetadata, ProjectData};
+use todo_app::project::{self, ArchiveReport, FsProjectMetadata, ProjectData};
 use todo_app::target::Location;
-use todo_app::{issue, locate_project_config, open_tracker};
+use todo_app::time::{self, IssueTime, ProjectTime, StartReport};
+use todo_app::{issue, locate_project_config, milestone, open_tracker};
+use todo_server::ServerState;
+use todo_tracker_fs::config::ValueOrigin;
+use todo_tracker_fs::issue::Movement;
 
 use crate::display::DisplayList;
 use crate::opts::{Order, ProjectLocation};
-use crate::outln;
+use crate::{outln, watch};
 
 pub fn new_project(use_manifest: bool, location: impl Into<String>, config: &Config) -> anyhow::Result<()> {
     let location = Location::<String>::from_unknown(location);
@@ -55,17 +64,196 @@ pub fn init_project(use_manifest: bool, location: Option<String>, config: &Confi
 pub fn add_issue(
     location: ProjectLocation,
     order: Order,
+    section: Option<String>,
     issue_name: impl AsRef<str> + Into<String>,
     config: &Config,
 ) -> anyhow::Result<()> {
+    let order = order
+        .into_order()
+        .unwrap_or_else(|| config.issue.add_order.into_order());
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+
+    outln!(
+        "    Adding `{}` issue to `{}` project",
+        issue_name.as_ref(),
+        project_metadata.name()
+    );
+
+    issue::add(
+        ProjectData::Fs(project_metadata),
+        &config.source,
+        order,
+        section.as_deref(),
+        issue_name,
+        "",
+    )?;
+    Ok(())
+}
+
+pub fn show_issue(location: ProjectLocation, issue_id: u64, config: &Config) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let issue = issue::get(ProjectData::Fs(project_metadata), &config.source, issue_id)?;
+
+    outln!("{}", issue.name);
+    outln!("    id: {}", issue.id);
+    outln!(
+        "    status: {}",
+        if issue.is_completed() { "completed" } else { "open" }
+    );
+    if let Some(location) = &issue.location {
+        outln!("    location: {location}");
+    }
+    for line in issue.content.lines() {
+        outln!("    | {line}");
+    }
+    Ok(())
+}
+
+/// Moves the issue with its subissues to the destination project, which is located the same way as the source one.
+pub fn move_issue(
+    location: ProjectLocation,
+    destination: String,
+    issue_id: u64,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let destination_metadata = locate_project_metadata(Some(Location::from_unknown(destination)), config)?;
+    let issue = issue::get(ProjectData::Fs(project_metadata.clone()), &config.source, issue_id)?;
+
+    outln!(
+        "    Moving `{}` issue to `{}` project",
+        issue.name,
+        destination_metadata.name()
+    );
+    let reassigned = issue::move_to_project(
+        ProjectData::Fs(project_metadata),
+        ProjectData::Fs(destination_metadata),
+        &config.source,
+        issue_id,
+    )?;
+    for (old_id, new_id) in reassigned {
+        outln!("    Reassigning id `{old_id}` to `{new_id}`");
+    }
+    Ok(())
+}
+
+/// Moves the issue with its subissues inside its file, changing its siblings or parent.
+pub fn shift_issue(
+    location: ProjectLocation,
+    movement: Movement,
+    issue_id: u64,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let issue = issue::get(ProjectData::Fs(project_metadata.clone()), &config.source, issue_id)?;
+
+    outln!(
+        "    Moving `{}` issue in `{}` project",
+        issue.name,
+        project_metadata.name()
+    );
+    issue::shift(ProjectData::Fs(project_metadata), &config.source, issue_id, movement)?;
+    Ok(())
+}
+
+pub fn add_milestone(
+    location: ProjectLocation,
+    position: milestone::Position,
+    milestone_name: String,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+
+    outln!(
+        "    Adding `{milestone_name}` milestone to `{}` project",
+        project_metadata.name()
+    );
+    milestone::add(
+        ProjectData::Fs(project_metadata),
+        &config.source,
+        position,
+        &milestone_name,
+    )?;
+    Ok(())
+}
+
+pub fn rename_milestone(
+    location: ProjectLocation,
+    milestone_id: u64,
+    name: String,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let milestone = milestone::get(ProjectData::Fs(project_metadata.clone()), &config.source, milestone_id)?;
+
+    outln!("    Renaming `{}` milestone to `{name}`", milestone.name);
+    milestone::rename(ProjectData::Fs(project_metadata), &config.source, milestone_id, &name)?;
+    Ok(())
+}
+
+pub fn remove_milestone(location: ProjectLocation, milestone_id: u64, config: &Config) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let milestone = milestone::get(ProjectData::Fs(project_metadata.clone()), &config.source, milestone_id)?;
+
+    outln!(
+        "    Removing `{}` milestone from `{}` project",
+        milestone.name,
+        project_metadata.name()
+    );
+    milestone::remove(ProjectData::Fs(project_metadata), &config.source, milestone_id)?;
+    Ok(())
+}
+
+/// Prints the milestones of the project with their ids and the numbers of the issues needed to reach them.
+pub fn list_milestones(location: ProjectLocation, config: &Config) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+
+    for milestone in milestone::list(ProjectData::Fs(project_metadata), &config.source)? {
+        let count = milestone.needed_issues.len();
+        outln!(
+            "{} {} ({count} issue{})",
+            milestone.id,
+            milestone.name,
+            if count == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
+/// Launches the `$VISUAL` or `$EDITOR` with the `+<line>` argument on the file of the issue.
+pub fn open_issue(location: ProjectLocation, issue_id: u64, config: &Config) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let issue = issue::get(ProjectData::Fs(project_metadata), &config.source, issue_id)?;
+    let location = issue
+        .location
+        .ok_or_else(|| anyhow!("issue `{issue_id}` has no source location"))?;
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .map_err(|_| anyhow!("set the `EDITOR` environment variable to open the issue"))?;
+    let mut editor_args = editor.split_whitespace();
+    let program = editor_args
+        .next()
+        .ok_or_else(|| anyhow!("the `EDITOR` environment variable is empty"))?;
+
+    let status = process::Command::new(program)
+        .args(editor_args)
+        .arg(format!("+{}", location.lines.start + 1))
+        .arg(&location.path)
+        .status()
+        .with_context(|| format!("fail to launch `{program}`"))?;
+    if !status.success() {
+        return Err(anyhow!("`{program}` exited with {status}"));
+    }
+    Ok(())
+}
+
+/// Finds the project by the location or the project of the current directory.
+fn locate_project_metadata(location: Option<Location>, config: &Config) -> anyhow::Result<FsProjectMetadata<String>> {
     let current_dir = env::current_dir()?;
     let location = location
-        .into_location()
         .map(Ok)
         .unwrap_or_else(|| project::default_path(&current_dir, &config.source).map(Location::Path))?;
-    let order = order
-        .into_order()
-        .unwrap_or_else(|| config.issue.add_order.into_order());
 
     let Some(project_config) = locate_project_config(location, [&current_dir], config)? else {
         return Err(anyhow!(
@@ -81,37 +269,328 @@ pub fn add_issue(
         project_config.root_dir.as_deref().unwrap_or(Path::new("")),
         project_config.name.as_deref(),
     );
-    let project_metadata = FsProjectMetadata::default()
+    Ok(FsProjectMetadata::default()
         .with_config_placement_maybe(config_placement)
-        .with_config(project_config);
+        .with_config(project_config))
+}
+
+pub fn format_project(location: ProjectLocation, check: bool, config: &Config) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let name = project_metadata.name().to_string();
+    let files = project::format(ProjectData::Fs(project_metadata), &config.source, check)?;
+
+    if !check {
+        for path in &files {
+            outln!("    Formatting `{}`", path.display());
+        }
+        return Ok(());
+    }
+
+    for path in &files {
+        outln!("    Unformatted `{}`", path.display());
+    }
+    if files.is_empty() {
+        Ok(())
+    } else {
+        let (files_of, are) = if files.len() == 1 {
+            ("file", "is")
+        } else {
+            ("files", "are")
+        };
+        Err(anyhow!(
+            "{} {files_of} of `{name}` project {are} not formatted",
+            files.len()
+        ))
+    }
+}
+
+/// Archives the finished issues and the passed milestones of the project under the given or today's date.
+pub fn archive_project(location: ProjectLocation, date: Option<Date>, config: &Config) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let name = project_metadata.name().to_string();
+    let date = date.unwrap_or_else(|| Zoned::now().date());
+    let ArchiveReport {
+        issues_count,
+        milestones_count,
+    } = project::archive(ProjectData::Fs(project_metadata), &config.source, &date.to_string())?;
 
+    if issues_count == 0 && milestones_count == 0 {
+        outln!("    Nothing to archive in `{name}` project");
+    } else {
+        outln!(
+            "    Archiving {issues_count} issue{} and {milestones_count} milestone{} of `{name}` project",
+            if issues_count == 1 { "" } else { "s" },
+            if milestones_count == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
+/// Starts the time entry of the issue, the running time entry of the project is stopped.
+pub fn start_timer(location: ProjectLocation, issue_id: u64, pin_id: bool, config: &Config) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let name = project_metadata.name().to_string();
+    let issue = issue::get(ProjectData::Fs(project_metadata.clone()), &config.source, issue_id)?;
+
+    let StartReport { stopped, written_id } = time::start(
+        ProjectData::Fs(project_metadata.clone()),
+        &config.source,
+        issue_id,
+        now_in_seconds()?,
+        pin_id,
+    )?;
+    if let Some(entry) = stopped {
+        outln!(
+            "    Stopping `{}` issue of `{name}` project",
+            issue_name(&project_metadata, entry.issue_id, config)
+        );
+    }
+    if let Some(id) = written_id {
+        outln!("    Writing id `{id}` to `{}` issue", issue.name);
+    }
+    outln!("    Starting `{}` issue of `{name}` project", issue.name);
+    Ok(())
+}
+
+pub fn stop_timer(location: ProjectLocation, config: &Config) -> anyhow::Result<()> {
+    let project_metadata = locate_project_metadata(location.into_location(), config)?;
+    let name = project_metadata.name().to_string();
+
+    let Some(entry) = time::stop(
+        ProjectData::Fs(project_metadata.clone()),
+        &config.source,
+        now_in_seconds()?,
+    )?
+    else {
+        return Err(anyhow!("no time entry is running in `{name}` project"));
+    };
     outln!(
-        "    Adding `{}` issue to `{}` project",
-        issue_name.as_ref(),
-        project_metadata.name()
+        "    Stopping `{}` issue of `{name}` project",
+        issue_name(&project_metadata, entry.issue_id, config)
     );
-
-    issue::add(ProjectData::Fs(project_metadata), &config.source, order, issue_name, "")?;
     Ok(())
 }
 
-pub fn list(root: Option<String>, project_location: ProjectLocation, config: &Config) -> anyhow::Result<()> {
+/// Prints the time spent on the projects since the beginning of the given date in the local time zone.
+pub fn report_time(
+    root: Option<String>,
+    project_location: ProjectLocation,
+    since: Option<Date>,
+    config: &Config,
+) -> anyhow::Result<()> {
     let mut location = project_location.into_location();
     let search_roots = local_search_roots(root.as_deref(), Some(&mut location), config)?;
+    let since = since
+        .map(|date| date.to_zoned(TimeZone::system()).map(|zoned| zoned.timestamp()))
+        .transpose()?;
 
     let tracker = open_tracker(location, search_roots, config)?;
-    tracker.display_projects_list(&config.display.project);
+    tracker.load_plans()?;
+    let reports = time::report(&tracker, &config.source, since, Timestamp::now())?;
+    if reports.is_empty() {
+        outln!("No tracked time");
+    }
+    for report in &reports {
+        display_project_time(report, 0);
+    }
+    Ok(())
+}
+
+fn display_project_time(report: &ProjectTime<String>, level: usize) {
+    let indent = "    ".repeat(level);
+    outln!("{indent}[{}]: {}", report.name, format_duration(report.time));
+    for issue in &report.issues {
+        display_issue_time(issue, level + 1);
+    }
+    for subproject in &report.subprojects {
+        display_project_time(subproject, level + 1);
+    }
+}
+
+fn display_issue_time(issue: &IssueTime, level: usize) {
+    let indent = "    ".repeat(level);
+    match &issue.name {
+        Some(name) => outln!("{indent}{} {name}: {}", issue.id, format_duration(issue.time)),
+        None => outln!("{indent}{}: {}", issue.id, format_duration(issue.time)),
+    }
+    for subissue in &issue.subissues {
+        display_issue_time(subissue, level + 1);
+    }
+}
+
+/// Formats the duration in whole hours and minutes, like `2h 30m`.
+fn format_duration(duration: SignedDuration) -> String {
+    let minutes = duration.as_mins();
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
+
+/// The current time truncated to seconds, as it is written to the time log.
+fn now_in_seconds() -> anyhow::Result<Timestamp> {
+    Ok(Timestamp::from_second(Timestamp::now().as_second())?)
+}
+
+/// The name of the issue, or its id if the issue is not found.
+fn issue_name(project_metadata: &FsProjectMetadata<String>, issue_id: u64, config: &Config) -> String {
+    issue::get(ProjectData::Fs(project_metadata.clone()), &config.source, issue_id)
+        .map_or_else(|_| issue_id.to_string(), |issue| issue.name)
+}
+
+pub fn list(
+    root: Option<String>,
+    project_location: ProjectLocation,
+    watch: bool,
+    archived: bool,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let mut location = project_location.into_location();
+    let search_roots = local_search_roots(root.as_deref(), Some(&mut location), config)?;
+
+    let mut tracker = open_tracker(location, search_roots, config)?;
+    if archived {
+        tracker = tracker.with_archive(&config.source.archive_file);
+    }
+    tracker.load_plans()?;
+    tracker.display_projects_list(&config.display.project)?;
+
+    if watch {
+        watch::redraw_on_changes(&mut tracker, |tracker| {
+            tracker.display_projects_list(&config.display.project)
+        })?;
+    }
 
     Ok(())
 }
 
-pub fn tree(root: Option<String>, project_location: ProjectLocation, config: &Config) -> anyhow::Result<()> {
+pub fn tree(
+    root: Option<String>,
+    project_location: ProjectLocation,
+    watch: bool,
+    config: &Config,
+) -> anyhow::Result<()> {
     let location = project_location.into_location();
     let search_roots = local_search_roots::<String>(root.as_deref(), None, config)?;
 
-    let tracker = open_tracker(location, search_roots, config)?;
-    tracker.display_projects_tree(&config.display.project);
+    let mut tracker = open_tracker(location, search_roots, config)?;
+    tracker.display_projects_tree(&config.display.project)?;
+
+    if watch {
+        watch::redraw_on_changes(&mut tracker, |tracker| {
+            tracker.display_projects_tree(&config.display.project)
+        })?;
+    }
+
+    Ok(())
+}
+
+pub fn serve(addr: SocketAddr, root: Option<String>, config: Config) -> anyhow::Result<()> {
+    let search_roots = local_search_roots::<String>(root.as_deref(), None, &config)?;
+
+    outln!("    Serving API on http://{addr}/api");
+    todo_server::serve(addr, ServerState::new(config, search_roots))?;
+
+    Ok(())
+}
+
+pub fn tui(root: Option<String>, config: Config) -> anyhow::Result<()> {
+    let search_roots = local_search_roots::<String>(root.as_deref(), None, &config)?;
+    todo_tui::run(config, search_roots)
+}
+
+pub fn check(root: Option<String>, config: &Config) -> anyhow::Result<()> {
+    let search_roots = local_search_roots::<String>(root.as_deref(), None, config)?;
+    let CheckReport {
+        projects_count,
+        diagnostics,
+    } = todo_app::check::check::<String>(search_roots, config)?;
+
+    for diagnostic in &diagnostics {
+        outln!("{diagnostic}");
+    }
+
+    let projects = if projects_count == 1 { "project" } else { "projects" };
+    if diagnostics.is_empty() {
+        outln!("    Checked {projects_count} {projects}, no problems found");
+        Ok(())
+    } else {
+        let problems = if diagnostics.len() == 1 { "problem" } else { "problems" };
+        Err(anyhow!(
+            "found {} {problems} in {projects_count} checked {projects}",
+            diagnostics.len()
+        ))
+    }
+}
 
+pub fn rebuild_cache(config: &Config) -> anyhow::Result<()> {
+    outln!("    Rebuilding projects discovery cache");
+    let count = todo_app::rebuild_discovery_cache(config)?;
+    outln!("    Found {count} project{}", if count == 1 { "" } else { "s" });
+
+    Ok(())
+}
+
+pub fn clear_cache(config: &Config) -> anyhow::Result<()> {
+    outln!("    Clearing projects discovery cache");
+    todo_app::clear_discovery_cache(config)
+}
+
+/// Prints the effective config of the project, commenting each value with its origin.
+pub fn show_config(location: ProjectLocation, config: &Config) -> anyhow::Result<()> {
+    let current_dir = env::current_dir()?;
+    let location = location
+        .into_location()
+        .map(Ok)
+        .unwrap_or_else(|| project::default_path(&current_dir, &config.source).map(Location::Path))?;
+    let project_config = locate_project_config(location, [&current_dir], config)?
+        .ok_or_else(|| anyhow!("could not find the project"))?;
+
+    let own_origin = config
+        .source
+        .find_project_config_placement(
+            project_config.root_dir.as_deref().unwrap_or(Path::new("")),
+            project_config.name.as_deref(),
+        )
+        .map(|placement| placement.as_ref().display().to_string())
+        .unwrap_or_default();
+
+    let origin = |path: &str| match (path, project_config.inherited_origin(path)) {
+        ("root_dir", _) => "project location".to_string(),
+        ("subprojects", _) => "found subprojects".to_string(),
+        (_, Some(ValueOrigin::Project(id))) => format!("inherited from `{id}` project"),
+        (_, Some(ValueOrigin::Workspace)) => "workspace defaults".to_string(),
+        (_, None) => own_origin.clone(),
+    };
+    display_config_table(&toml::Table::try_from(&project_config)?, None, &origin)
+}
+
+/// Prints the values of the config table commenting each one with its origin, the subtables go after the values.
+fn display_config_table(
+    table: &toml::Table,
+    path: Option<&str>,
+    origin: &impl Fn(&str) -> String,
+) -> anyhow::Result<()> {
+    let key_path = |key: &str| path.map_or_else(|| key.to_string(), |path| format!("{path}.{key}"));
+    let (tables, values): (Vec<_>, Vec<_>) = table.iter().partition(|(_, value)| value.is_table());
+
+    if let Some(path) = path
+        && !values.is_empty()
+    {
+        outln!();
+        outln!("[{path}]");
+    }
+    for (key, value) in values {
+        let line = toml::to_string(&toml::Table::from_iter([(key.clone(), value.clone())]))?;
+        outln!("{} # {}", line.trim_end(), origin(&key_path(key)));
+    }
+
+    for (key, value) in tables {
+        if let toml::Value::Table(table) = value {
+            display_config_table(table, Some(&key_path(key)), origin)?;
+        }
+    }
     Ok(())
 }
 
@@ -1,8 +1,11 @@
+use std::borrow::Cow;
 use std::fmt::{Display, Write};
+use std::io;
 
 use indexmap::{IndexMap, IndexSet};
 use todo_app::config::{DisplayProjectConfig, TitleConsist};
 use todo_lib::id::HashedId;
+use todo_lib::issue::Issue;
 use todo_lib::plan::Step;
 use todo_lib::project::Project;
 use todo_tracker_fs::FsTracker;
@@ -21,6 +24,13 @@ macro_rules! outln {
     }};
 }
 
+#[macro_export]
+macro_rules! errln {
+    ($($arg:tt)*) => {{
+        eprintln!($($arg)*)
+    }};
+}
+
 pub trait DisplayList<ID> {
     fn format_project_title_key(&self, project: &Project<ID>, title: TitleConsist, with_parents: bool) -> String;
     fn display_project_title(
@@ -29,16 +39,17 @@ pub trait DisplayList<ID> {
         title: TitleConsist,
         title_key: Option<impl AsRef<str>>,
         config: &DisplayProjectConfig,
-    );
+    ) -> io::Result<()>;
     fn display_steps_list(
         &self,
         prefix: impl AsRef<str>,
         indent: impl AsRef<str>,
         project: &Project<ID>,
         config: &DisplayProjectConfig,
-    );
-    fn display_projects_list(&self, config: &DisplayProjectConfig);
-    fn display_projects_tree(&self, config: &DisplayProjectConfig);
+    ) -> io::Result<()>;
+    fn display_projects_list(&self, config: &DisplayProjectConfig) -> io::Result<()>;
+    fn display_projects_tree(&self, config: &DisplayProjectConfig) -> io::Result<()>;
+    fn display_diagnostics(&self);
 }
 
 impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
@@ -54,7 +65,7 @@ impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
         consist: TitleConsist,
         title_key: Option<impl AsRef<str>>,
         config: &DisplayProjectConfig,
-    ) {
+    ) -> io::Result<()> {
         if let Some(before) = match consist {
             TitleConsist::Id => &config.title.id_before,
             TitleConsist::Name => &config.title.name_before,
@@ -96,25 +107,10 @@ impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
         }
 
         if config.title.show_steps_count {
-            let steps_count = self
-                .project_plan(project.id())
-                .map(|plan| {
-                    plan.steps().iter().fold(0_usize, |count, step| {
-                        if !config.show_substeps {
-                            if let Step::Issue(id) = step {
-                                if let Some(issue) = plan.get_issue(id) {
-                                    if issue.parent_id.is_some() {
-                                        return count;
-                                    }
-                                }
-                            }
-                        }
-                        count + 1
-                    })
-                })
-                .unwrap_or(0);
+            let steps_count = self.project_steps_count(project.id())?.total(config.show_substeps);
             out!(": {steps_count}")
         }
+        Ok(())
     }
 
     fn display_steps_list(
@@ -123,12 +119,20 @@ impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
         indent: impl AsRef<str>,
         project: &Project<ID>,
         config: &DisplayProjectConfig,
-    ) {
+    ) -> io::Result<()> {
         let prefix = prefix.as_ref();
         let indent = indent.as_ref();
         let max_count = config.max_steps.unwrap_or(usize::MAX);
+        if max_count == 0 {
+            // No steps are displayed, so the plan is not needed to be loaded
+            let steps_count = self.project_steps_count(project.id())?.total(config.show_substeps);
+            if steps_count > 0 {
+                outln!("{prefix}{indent}..{steps_count}");
+            }
+            return Ok(());
+        }
 
-        if let Some(plan) = self.project_plan(project.id()) {
+        if let Some(plan) = self.project_plan(project.id())? {
             let mut parent_ids = Vec::new();
 
             let mut step_count = 0;
@@ -140,7 +144,7 @@ impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
                 step_count < max_count
             };
 
-            let mut is_next_displayed = max_count > 0;
+            let mut is_next_displayed = true;
             for step in plan.steps() {
                 match step {
                     Step::Issue(id) => {
@@ -153,12 +157,11 @@ impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
                                         }
                                     }
 
-                                    is_next_displayed =
-                                        display_line(parent_ids.len() + 1, &format!("- {}", issue.name));
+                                    is_next_displayed = display_line(parent_ids.len() + 1, &issue_line(issue));
                                     parent_ids.push(issue.id);
                                 }
                             } else {
-                                is_next_displayed = display_line(0, &format!("- {}", issue.name));
+                                is_next_displayed = display_line(0, &issue_line(issue));
                                 parent_ids.clear();
                             }
                         }
@@ -180,9 +183,10 @@ impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
                 outln!("{prefix}{indent}..{}", step_count - max_count);
             }
         }
+        Ok(())
     }
 
-    fn display_projects_list(&self, config: &DisplayProjectConfig) {
+    fn display_projects_list(&self, config: &DisplayProjectConfig) -> io::Result<()> {
         if !config.compact {
             let count = self.projects().len();
 
@@ -195,31 +199,35 @@ impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
 
         let mut projects = Vec::new();
         for project in self.projects().values() {
-            let consist = if matches!(config.title.consist, TitleConsist::IdAndName) && project.name().is_empty() {
-                TitleConsist::Id
-            } else {
-                config.title.consist
-            };
-            projects.push((self.format_project_title_key(project, consist, true), consist, project));
+            let project_config = project_display_config(self, project, config)?;
+            let consist = title_consist(project, &project_config);
+            projects.push((
+                self.format_project_title_key(project, consist, true),
+                consist,
+                project,
+                project_config,
+            ));
         }
 
         projects.sort_by(|(title_key1, ..), (title_key2, ..)| title_key1.cmp(title_key2));
 
         let mut is_first_project = true;
-        for (title_key, consist, project) in projects {
+        for (title_key, consist, project, project_config) in projects {
             if !config.compact || (!is_first_project && config.separate_projects) {
                 outln!();
             }
 
-            self.display_project_title(project, consist, Some(title_key), config);
+            self.display_project_title(project, consist, Some(title_key), &project_config)?;
             outln!();
-            self.display_steps_list("", "", project, config);
+            self.display_steps_list("", "", project, &project_config)?;
 
             is_first_project = false;
         }
+        self.display_diagnostics();
+        Ok(())
     }
 
-    fn display_projects_tree(&self, config: &DisplayProjectConfig) {
+    fn display_projects_tree(&self, config: &DisplayProjectConfig) -> io::Result<()> {
         if !config.compact {
             let count = self.projects().len();
 
@@ -238,7 +246,57 @@ impl<ID: HashedId + Clone + Display> DisplayList<ID> for FsTracker<ID> {
             }
         });
 
-        display_nested_projecs(self, project_ids, &self.subprojects(), config, "");
+        display_nested_projecs(self, project_ids, &self.subprojects(), config, "")?;
+        self.display_diagnostics();
+        Ok(())
+    }
+
+    /// Prints the problems found in the project files of the already loaded plans to the stderr.
+    fn display_diagnostics(&self) {
+        for id in self.projects().keys() {
+            for diagnostic in self.loaded_project_diagnostics(id) {
+                errln!("{diagnostic}");
+            }
+        }
+    }
+}
+
+/// Display config of the project with the `[display]` table of its project config applied.
+fn project_display_config<'a, ID>(
+    tracker: &FsTracker<ID>,
+    project: &Project<ID>,
+    config: &'a DisplayProjectConfig,
+) -> io::Result<Cow<'a, DisplayProjectConfig>>
+where
+    ID: HashedId + Clone + Display,
+{
+    match tracker.project_config(project.id()) {
+        Some(project_config) if !project_config.display.is_empty() => config
+            .with_overrides(&project_config.display)
+            .map(Cow::Owned)
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid display config of `{}` project: {err}", project.id()),
+                )
+            }),
+        _ => Ok(Cow::Borrowed(config)),
+    }
+}
+
+fn title_consist<ID>(project: &Project<ID>, config: &DisplayProjectConfig) -> TitleConsist {
+    if matches!(config.title.consist, TitleConsist::IdAndName) && project.name().is_empty() {
+        TitleConsist::Id
+    } else {
+        config.title.consist
+    }
+}
+
+fn issue_line<ID>(issue: &Issue<ID>) -> String {
+    if issue.is_completed() {
+        format!("- [x] {}", issue.name)
+    } else {
+        format!("- {}", issue.name)
     }
 }
 
@@ -251,13 +309,12 @@ fn format_project_title_key_inner<ID>(
 ) where
     ID: HashedId + Clone + Display,
 {
-    if with_parents {
-        if let Some(parent_id) = project.parent_id() {
-            if let Some(parent) = tracker.projects().get(parent_id) {
-                format_project_title_key_inner(tracker, parent, consist, true, output);
-                write!(output, "/").expect("Failed to write to string");
-            }
-        }
+    if with_parents
+        && let Some(parent_id) = project.parent_id()
+        && let Some(parent) = tracker.projects().get(parent_id)
+    {
+        format_project_title_key_inner(tracker, parent, consist, true, output);
+        write!(output, "/").expect("Failed to write to string");
     }
 
     match consist {
@@ -273,7 +330,8 @@ fn display_nested_projecs<'a, ID>(
     subprojects: &IndexMap<ID, IndexSet<ID>>,
     config: &DisplayProjectConfig,
     prefix: impl AsRef<str>,
-) where
+) -> io::Result<()>
+where
     ID: HashedId + Clone + Display + 'a,
 {
     let prefix = prefix.as_ref();
@@ -281,15 +339,13 @@ fn display_nested_projecs<'a, ID>(
 
     for project_id in project_ids {
         if let Some(project) = tracker.projects().get(project_id) {
-            let consist = if matches!(config.title.consist, TitleConsist::IdAndName) && project.name().is_empty() {
-                TitleConsist::Id
-            } else {
-                config.title.consist
-            };
+            let project_config = project_display_config(tracker, project, config)?;
+            let consist = title_consist(project, &project_config);
             prepared_projects.push((
                 tracker.format_project_title_key(project, consist, false),
                 consist,
                 project,
+                project_config,
             ));
         }
     }
@@ -299,7 +355,7 @@ fn display_nested_projecs<'a, ID>(
     let mut is_first_project = true;
     let count = prepared_projects.len();
 
-    for (idx, (title_key, consist, project)) in prepared_projects.into_iter().enumerate() {
+    for (idx, (title_key, consist, project, project_config)) in prepared_projects.into_iter().enumerate() {
         let is_last_project = idx + 1 == count;
 
         if !config.compact || (!is_first_project && config.separate_projects) {
@@ -312,7 +368,7 @@ fn display_nested_projecs<'a, ID>(
         } else {
             out!("{prefix}");
         }
-        tracker.display_project_title(project, consist, Some(title_key), config);
+        tracker.display_project_title(project, consist, Some(title_key), &project_config)?;
         outln!();
 
         let children = subprojects.get(project.id());
@@ -330,12 +386,13 @@ fn display_nested_projecs<'a, ID>(
         } else {
             format!("{prefix}{child_prefix}")
         };
-        tracker.display_steps_list(steps_prefix, "  ", project, config);
+        tracker.display_steps_list(steps_prefix, "  ", project, &project_config)?;
 
         if let Some(children) = children {
-            display_nested_projecs(tracker, children, subprojects, config, format!("{prefix}  │"));
+            display_nested_projecs(tracker, children, subprojects, config, format!("{prefix}  │"))?;
         }
 
         is_first_project = false;
     }
+    Ok(())
 }
@@ -1,11 +1,16 @@
 use clap::Parser;
 use todo_app::config::{Config, SourceConfig};
 
-use crate::opts::{AddIssue, CliOpts, Command, InitProject, List, NewProject, Tree};
+use crate::opts::{
+    AddIssue, AddMilestone, ArchiveProject, Cache, Check, CliOpts, Command, ConfigCommand, FormatProject, InitProject,
+    List, ListMilestones, MilestoneCommand, MoveIssue, NewProject, OpenIssue, RemoveMilestone, RenameMilestone,
+    ReportCommand, Serve, ShiftIssue, ShowConfig, ShowIssue, StartTimer, StopTimer, TimeReport, Tree, Tui,
+};
 
 mod command;
 mod display;
 mod opts;
+mod watch;
 
 fn main() -> anyhow::Result<()> {
     let CliOpts {
@@ -34,26 +39,111 @@ fn main() -> anyhow::Result<()> {
             let use_manifest = use_manifest(with_manifest, with_project_config, &config.source);
             command::init_project(use_manifest, location, &config)?;
         },
-        Command::Add(AddIssue { location, order, issue }) => {
-            command::add_issue(location, order, issue, &config)?;
+        Command::Add(AddIssue {
+            location,
+            order,
+            section,
+            issue,
+        }) => {
+            command::add_issue(location, order, section, issue, &config)?;
+        },
+        Command::Show(ShowIssue { location, issue }) => {
+            command::show_issue(location, issue, &config)?;
+        },
+        Command::Open(OpenIssue { location, issue }) => {
+            command::open_issue(location, issue, &config)?;
+        },
+        Command::Move(MoveIssue { location, to, issue }) => {
+            command::move_issue(location, to, issue, &config)?;
+        },
+        Command::Mv(ShiftIssue {
+            location,
+            movement,
+            issue,
+        }) => {
+            command::shift_issue(location, movement.into_movement(), issue, &config)?;
         },
         Command::List(List {
             max_steps,
             display,
+            watch,
+            archived,
             location,
             project_location,
         }) => {
             config.update_display_project(display.compact, display.pretty, max_steps);
-            command::list(location, project_location, &config)?;
+            command::list(location, project_location, watch, archived, &config)?;
         },
         Command::Tree(Tree {
             max_steps,
             display,
+            watch,
             location,
             project_location,
         }) => {
             config.update_display_project(display.compact, display.pretty, max_steps);
-            command::tree(location, project_location, &config)?;
+            command::tree(location, project_location, watch, &config)?;
+        },
+        Command::Check(Check { location }) => {
+            command::check(location, &config)?;
+        },
+        Command::Fmt(FormatProject { check, location }) => {
+            command::format_project(location, check, &config)?;
+        },
+        Command::Archive(ArchiveProject { date, location }) => {
+            command::archive_project(location, date, &config)?;
+        },
+        Command::Start(StartTimer {
+            location,
+            issue,
+            pin_id,
+        }) => {
+            command::start_timer(location, issue, pin_id, &config)?;
+        },
+        Command::Stop(StopTimer { location }) => {
+            command::stop_timer(location, &config)?;
+        },
+        Command::Serve(Serve { addr, location }) => {
+            command::serve(addr, location, config)?;
+        },
+        Command::Tui(Tui { location }) => {
+            command::tui(location, config)?;
+        },
+        Command::Cache(Cache::Rebuild) => {
+            command::rebuild_cache(&config)?;
+        },
+        Command::Cache(Cache::Clear) => {
+            command::clear_cache(&config)?;
+        },
+        Command::Config(ConfigCommand::Show(ShowConfig { location })) => {
+            command::show_config(location, &config)?;
+        },
+        Command::Milestone(MilestoneCommand::Add(AddMilestone {
+            location,
+            position,
+            milestone,
+        })) => {
+            command::add_milestone(location, position.into_position(), milestone, &config)?;
+        },
+        Command::Milestone(MilestoneCommand::Rename(RenameMilestone {
+            location,
+            milestone,
+            name,
+        })) => {
+            command::rename_milestone(location, milestone, name, &config)?;
+        },
+        Command::Milestone(MilestoneCommand::Remove(RemoveMilestone { location, milestone })) => {
+            command::remove_milestone(location, milestone, &config)?;
+        },
+        Command::Milestone(MilestoneCommand::List(ListMilestones { location })) => {
+            command::list_milestones(location, &config)?;
+        },
+        Command::Report(ReportCommand::Time(TimeReport {
+            since,
+            location,
+            project_location,
+        })) => {
+            command::report_time(location, project_location, since, &config)?;
         },
     }
 
@@ -1,8 +1,11 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
-use todo_app::issue;
+use clap::{Args, Parser, Subcommand};
+use jiff::civil::Date;
 use todo_app::target::Location;
+use todo_app::{issue, milestone};
+use todo_tracker_fs::issue::Movement;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -39,11 +42,60 @@ pub enum Command {
     /// Add issue
     Add(AddIssue),
 
+    /// Show issue with its source location
+    Show(ShowIssue),
+
+    /// Open the source of the issue in the `$EDITOR`
+    Open(OpenIssue),
+
+    /// Move issue with its subissues to another project
+    Move(MoveIssue),
+
+    /// Reorder or re-parent issue with its subissues inside its file
+    Mv(ShiftIssue),
+
     /// List issues
     List(List),
 
     /// Display projects tree
     Tree(Tree),
+
+    /// Validate the project files without changing them
+    Check(Check),
+
+    /// Format the plan files of the project in the canonical layout
+    Fmt(FormatProject),
+
+    /// Move the finished issues and the passed milestones to the archive file of the project
+    Archive(ArchiveProject),
+
+    /// Start tracking the time spent on the issue, stopping the running time entry
+    Start(StartTimer),
+
+    /// Stop tracking the time spent on the issue
+    Stop(StopTimer),
+
+    /// Serve JSON API for editor and script integrations
+    Serve(Serve),
+
+    /// Open interactive terminal UI
+    Tui(Tui),
+
+    /// Manage the projects discovery cache of the global mode
+    #[command(subcommand)]
+    Cache(Cache),
+
+    /// Inspect the project config
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Manage the milestones of the project
+    #[command(subcommand)]
+    Milestone(MilestoneCommand),
+
+    /// Report the tracked time
+    #[command(subcommand)]
+    Report(ReportCommand),
 }
 
 #[derive(Parser, Clone)]
@@ -84,10 +136,62 @@ pub struct AddIssue {
     #[command(flatten)]
     pub order: Order,
 
+    /// The named ```md todo <section>``` block of the project manifest to add issue to
+    #[arg(short, long)]
+    pub section: Option<String>,
+
     /// The name of the issue
     pub issue: String,
 }
 
+#[derive(Parser, Clone)]
+pub struct ShowIssue {
+    /// The location of the project of the issue (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+
+    /// The id of the issue
+    pub issue: u64,
+}
+
+#[derive(Parser, Clone)]
+pub struct OpenIssue {
+    /// The location of the project of the issue (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+
+    /// The id of the issue
+    pub issue: u64,
+}
+
+#[derive(Parser, Clone)]
+pub struct MoveIssue {
+    /// The location of the project of the issue (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+
+    /// The location of the destination project (path, id or name)
+    #[arg(long)]
+    pub to: String,
+
+    /// The id of the issue
+    pub issue: u64,
+}
+
+#[derive(Parser, Clone)]
+pub struct ShiftIssue {
+    /// The location of the project of the issue (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+
+    /// The movement of the issue
+    #[command(flatten)]
+    pub movement: IssueMovement,
+
+    /// The id of the issue
+    pub issue: u64,
+}
+
 #[derive(Parser, Clone)]
 pub struct List {
     /// Maximum number of steps in the list (project issues and milestones)
@@ -97,6 +201,14 @@ pub struct List {
     #[command(flatten)]
     pub display: DisplayMode,
 
+    /// Redraw the list on changes of the project files
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// List the archived issues instead of the active ones
+    #[arg(long)]
+    pub archived: bool,
+
     /// Listing root location (exists directory path by example, current directory by default)
     pub location: Option<String>,
 
@@ -105,6 +217,56 @@ pub struct List {
     pub project_location: ProjectLocation,
 }
 
+#[derive(Parser, Clone)]
+pub struct Check {
+    /// Checking root location (exists directory path by example, current directory by default)
+    pub location: Option<String>,
+}
+
+#[derive(Parser, Clone)]
+pub struct FormatProject {
+    /// Only check that the files are formatted, without changing them
+    #[arg(long)]
+    pub check: bool,
+
+    /// The location of the project to format (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+}
+
+#[derive(Parser, Clone)]
+pub struct ArchiveProject {
+    /// The archiving date written to the archive file (today by default)
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub date: Option<Date>,
+
+    /// The location of the project to archive (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+}
+
+#[derive(Parser, Clone)]
+pub struct StartTimer {
+    /// The location of the project of the issue (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+
+    /// The id of the issue
+    pub issue: u64,
+
+    /// Write the explicit id into the issue line if it has none, so the time entries follow the issue when the issues
+    /// are reordered. The time entries of the next issues are moved to their shifted generated ids
+    #[arg(long)]
+    pub pin_id: bool,
+}
+
+#[derive(Parser, Clone)]
+pub struct StopTimer {
+    /// The location of the project of the running time entry (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+}
+
 #[derive(Parser, Clone)]
 pub struct Tree {
     /// Maximum number of steps in the tree (project issues and milestones)
@@ -114,6 +276,10 @@ pub struct Tree {
     #[command(flatten)]
     pub display: DisplayMode,
 
+    /// Redraw the tree on changes of the project files
+    #[arg(short, long)]
+    pub watch: bool,
+
     /// Tree from root location (exists directory path by example, current directory by default)
     pub location: Option<String>,
 
@@ -122,6 +288,123 @@ pub struct Tree {
     pub project_location: ProjectLocation,
 }
 
+#[derive(Parser, Clone)]
+pub struct Serve {
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:7878")]
+    pub addr: SocketAddr,
+
+    /// Serving root location (exists directory path by example, current directory by default)
+    pub location: Option<String>,
+}
+
+#[derive(Parser, Clone)]
+pub struct Tui {
+    /// Projects root location (exists directory path by example, current directory by default)
+    pub location: Option<String>,
+}
+
+#[derive(Subcommand, Clone, Copy)]
+pub enum Cache {
+    /// Search for projects in the configured directories again and store the result
+    Rebuild,
+
+    /// Remove the cache file
+    Clear,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ConfigCommand {
+    /// Print the effective config of the project with the origins of its values
+    Show(ShowConfig),
+}
+
+#[derive(Subcommand, Clone)]
+pub enum MilestoneCommand {
+    /// Add milestone needing the issues above it
+    Add(AddMilestone),
+
+    /// Rename milestone
+    Rename(RenameMilestone),
+
+    /// Remove milestone, merging its issues into the next milestone
+    Remove(RemoveMilestone),
+
+    /// List milestones with the numbers of their issues
+    List(ListMilestones),
+}
+
+#[derive(Parser, Clone)]
+pub struct AddMilestone {
+    /// The location of the project to add milestone (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+
+    /// The position of the milestone (after all issues by default)
+    #[command(flatten)]
+    pub position: MilestonePosition,
+
+    /// The name of the milestone
+    pub milestone: String,
+}
+
+#[derive(Parser, Clone)]
+pub struct RenameMilestone {
+    /// The location of the project of the milestone (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+
+    /// The id of the milestone
+    pub milestone: u64,
+
+    /// The new name of the milestone
+    pub name: String,
+}
+
+#[derive(Parser, Clone)]
+pub struct RemoveMilestone {
+    /// The location of the project of the milestone (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+
+    /// The id of the milestone
+    pub milestone: u64,
+}
+
+#[derive(Parser, Clone)]
+pub struct ListMilestones {
+    /// The location of the project to list its milestones (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ReportCommand {
+    /// Sum up the time spent on the issues by the issues, projects and subprojects
+    Time(TimeReport),
+}
+
+#[derive(Parser, Clone)]
+pub struct TimeReport {
+    /// Count only the time entries started since the beginning of the date (all entries by default)
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub since: Option<Date>,
+
+    /// Reporting root location (exists directory path by example, current directory by default)
+    pub location: Option<String>,
+
+    /// The location of the project to report its time
+    #[command(flatten)]
+    pub project_location: ProjectLocation,
+}
+
+#[derive(Parser, Clone)]
+pub struct ShowConfig {
+    /// The location of the project to show its config (current directory project by default)
+    #[command(flatten)]
+    pub location: ProjectLocation,
+}
+
 #[derive(Parser, Clone, Copy, Debug)]
 pub struct Order {
     /// Issue will be added to the top of the list
@@ -145,6 +428,79 @@ impl Order {
     }
 }
 
+#[derive(Args, Clone, Copy, Debug)]
+#[group(required = true, multiple = false)]
+pub struct IssueMovement {
+    /// Swap the issue with its previous sibling
+    #[arg(long)]
+    pub up: bool,
+
+    /// Swap the issue with its next sibling
+    #[arg(long)]
+    pub down: bool,
+
+    /// Place the issue right before the issue with the given id, as its sibling
+    #[arg(long, value_name = "ID")]
+    pub before: Option<u64>,
+
+    /// Place the issue right after the issue with the given id and its subissues, as its sibling
+    #[arg(long, value_name = "ID")]
+    pub after: Option<u64>,
+
+    /// Make the issue the last subissue of its previous sibling
+    #[arg(long)]
+    pub indent: bool,
+
+    /// Make the issue the next sibling of its parent
+    #[arg(long)]
+    pub outdent: bool,
+}
+
+impl IssueMovement {
+    pub fn into_movement(self) -> Movement {
+        let Self {
+            up,
+            down,
+            before,
+            after,
+            indent,
+            outdent,
+        } = self;
+
+        match (before, after) {
+            (Some(id), _) => Movement::Before(id),
+            (_, Some(id)) => Movement::After(id),
+            _ if up => Movement::Up,
+            _ if down => Movement::Down,
+            _ if indent => Movement::Indent,
+            _ if outdent => Movement::Outdent,
+            _ => unreachable!("one of the movements must be given"),
+        }
+    }
+}
+
+#[derive(Args, Clone, Copy, Debug)]
+#[group(multiple = false)]
+pub struct MilestonePosition {
+    /// Place the milestone right before the top-level issue or the milestone with the given id
+    #[arg(long, value_name = "ID")]
+    pub before: Option<u64>,
+
+    /// Place the milestone right after the top-level issue with its subissues or the milestone with the given id
+    #[arg(long, value_name = "ID")]
+    pub after: Option<u64>,
+}
+
+impl MilestonePosition {
+    pub fn into_position(self) -> milestone::Position {
+        match (self.before, self.after) {
+            (Some(id), _) => milestone::Position::Before(id),
+            (_, Some(id)) => milestone::Position::After(id),
+            _ => milestone::Position::Last,
+        }
+    }
+}
+
 #[derive(Parser, Clone, Copy, Debug)]
 pub struct DisplayMode {
     /// Show in compact mode
@@ -0,0 +1,104 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, IsTerminal, Write};
+use std::path::{self, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use todo_lib::id::HashedId;
+use todo_tracker_fs::FsTracker;
+
+use crate::{out, outln};
+
+/// Time to collect the related file system events, editors usually write a file by several operations.
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Watches the root directories and the plan files of the tracker projects, reloads the plans of the changed
+/// projects and redraws the output until the process is stopped.
+pub fn redraw_on_changes<ID: HashedId + Clone>(
+    tracker: &mut FsTracker<ID>,
+    mut redraw: impl FnMut(&FsTracker<ID>) -> io::Result<()>,
+) -> anyhow::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    // The new files and the project configs are noticed in the root directories
+    for (_, root_dir) in tracker.project_root_dirs() {
+        watcher.watch(root_dir, RecursiveMode::NonRecursive)?;
+    }
+    let mut source_projects = HashMap::new();
+    let ids: Vec<_> = tracker.project_root_dirs().map(|(id, _)| id.clone()).collect();
+    for id in &ids {
+        watch_sources(&mut watcher, tracker, id, &mut source_projects)?;
+    }
+
+    loop {
+        let mut changed_projects = HashSet::new();
+        let mut collect_changed = |event: notify::Result<Event>| -> anyhow::Result<()> {
+            let event = event?;
+            if matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                changed_projects.extend(
+                    event
+                        .paths
+                        .iter()
+                        .filter_map(|path| {
+                            let path = path::absolute(path).ok()?;
+                            source_projects
+                                .get(&path)
+                                .or_else(|| tracker.find_project_by_path(&path))
+                        })
+                        .cloned(),
+                );
+            }
+            Ok(())
+        };
+
+        collect_changed(receiver.recv()?)?;
+        while let Ok(event) = receiver.recv_timeout(DEBOUNCE_TIMEOUT) {
+            collect_changed(event)?;
+        }
+        if changed_projects.is_empty() {
+            continue;
+        }
+
+        let mut errors = Vec::new();
+        for id in changed_projects {
+            if let Err(err) = tracker.reload_project_plan(&id) {
+                errors.push(err);
+            }
+            // The files may be added, removed or replaced by the editor
+            if let Err(err) = watch_sources(&mut watcher, tracker, &id, &mut source_projects) {
+                errors.push(io::Error::other(err));
+            }
+        }
+
+        if io::stdout().is_terminal() {
+            out!("\x1b[2J\x1b[H");
+        } else {
+            outln!();
+        }
+        if let Err(err) = redraw(tracker) {
+            errors.push(err);
+        }
+        for err in errors {
+            outln!("Error: {err}");
+        }
+        io::stdout().flush()?;
+    }
+}
+
+/// Watches the plan files of the project, which can be outside its root directory, and maps them to the project.
+fn watch_sources<ID: HashedId + Clone>(
+    watcher: &mut impl Watcher,
+    tracker: &FsTracker<ID>,
+    id: &ID,
+    source_projects: &mut HashMap<PathBuf, ID>,
+) -> anyhow::Result<()> {
+    for path in tracker.project_source_paths(id)? {
+        watcher.watch(&path, RecursiveMode::NonRecursive)?;
+        source_projects.insert(path::absolute(&path)?, id.clone());
+    }
+    Ok(())
+}
@@ -0,0 +1,8 @@
+use self::common::run_test_cases;
+
+mod common;
+
+#[test]
+fn check_test_cases() {
+    run_test_cases("tests/check.md").unwrap();
+}
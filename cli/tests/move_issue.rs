@@ -0,0 +1,8 @@
+use self::common::run_test_cases;
+
+mod common;
+
+#[test]
+fn move_issue_test_cases() {
+    run_test_cases("tests/move_issue.md").unwrap();
+}
@@ -0,0 +1,8 @@
+use self::common::run_test_cases;
+
+mod common;
+
+#[test]
+fn shift_issue_test_cases() {
+    run_test_cases("tests/shift_issue.md").unwrap();
+}
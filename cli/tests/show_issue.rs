@@ -0,0 +1,11 @@
+use md_cli_test::Tester;
+use todo_app::config::{DEFAULT_CONFIG_FILE_NAME, ROOT_CONFIG_ENV_KEY};
+
+#[test]
+fn show_issue_test_cases() {
+    Tester::new("tests/show_issue.md")
+        .with_env(ROOT_CONFIG_ENV_KEY, format!("./{DEFAULT_CONFIG_FILE_NAME}"))
+        .with_env("VISUAL", "echo")
+        .run()
+        .unwrap();
+}
@@ -0,0 +1,73 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{fs, thread};
+
+use temp_testdir::TempDir;
+use todo_app::config::{DEFAULT_CONFIG_FILE_NAME, ROOT_CONFIG_ENV_KEY};
+
+struct KillOnDrop(Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        self.0.kill().ok();
+        self.0.wait().ok();
+    }
+}
+
+/// Reads the lines of the output until the line, or returns all read lines on timeout.
+fn read_until(lines: &mpsc::Receiver<String>, expected: &str) -> Result<(), Vec<String>> {
+    let mut read = Vec::new();
+    while let Ok(line) = lines.recv_timeout(Duration::from_secs(10)) {
+        if line == expected {
+            return Ok(());
+        }
+        read.push(line);
+    }
+    Err(read)
+}
+
+#[test]
+fn list_watch_redraws_changed_project() {
+    let temp_dir = TempDir::default();
+    for project in ["project A", "project B"] {
+        let project_dir = temp_dir.join(project);
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(
+            project_dir.join("Project.toml"),
+            format!("id = \"{project}\"\nname = \"{project}\"\n"),
+        )
+        .unwrap();
+        fs::write(project_dir.join("TODO.md"), "- task 1\n").unwrap();
+    }
+
+    let mut child = KillOnDrop(
+        Command::new(env!("CARGO_BIN_EXE_todo"))
+            .args(["list", "--watch", "."])
+            .current_dir(&temp_dir)
+            .env(ROOT_CONFIG_ENV_KEY, format!("./{DEFAULT_CONFIG_FILE_NAME}"))
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap(),
+    );
+
+    let stdout = child.0.stdout.take().unwrap();
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    read_until(&lines, "[project B]: 1").unwrap();
+    read_until(&lines, "- task 1").unwrap();
+    // Give the watcher time to start after the first output
+    thread::sleep(Duration::from_millis(300));
+
+    fs::write(temp_dir.join("project B").join("TODO.md"), "- task 1\n- task 2\n").unwrap();
+    read_until(&lines, "[project B]: 2").unwrap();
+    read_until(&lines, "- task 2").unwrap();
+}
@@ -1,4 +1,7 @@
+use std::fmt;
 use std::hash::Hash;
+use std::ops::Range;
+use std::path::PathBuf;
 
 use indexmap::IndexSet;
 
@@ -37,14 +40,46 @@ pub struct IssueRelation<ID> {
     pub relation: Relation,
 }
 
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum IssueStatus {
+    #[default]
+    Open,
+    Completed,
+}
+
+/// Position of the issue or the milestone in the file it is loaded from.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+
+    /// Zero-based line indices of the item, including the issue description.
+    pub lines: Range<usize>,
+}
+
+impl fmt::Display for SourceLocation {
+    /// Formats the location as `path:line` or `path:first-last` with one-based line numbers.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { path, lines } = self;
+        write!(f, "{}:{}", path.display(), lines.start + 1)?;
+        if lines.len() > 1 {
+            write!(f, "-{}", lines.end)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Issue<ID> {
     pub id: ID,
     pub parent_id: Option<ID>,
     pub name: String,
     pub content: String,
+    pub status: IssueStatus,
     pub subissues: IndexSet<ID>,
     pub relations: Vec<IssueRelation<ID>>,
+
+    /// Source of the loaded issue, which is not compared.
+    pub location: Option<SourceLocation>,
 }
 
 impl<ID: HashedId + PartialEq> PartialEq for Issue<ID> {
@@ -54,14 +89,17 @@ impl<ID: HashedId + PartialEq> PartialEq for Issue<ID> {
             parent_id,
             name,
             content,
+            status,
             subissues,
             relations,
+            location: _,
         } = self;
 
         *id == other.id
             && *parent_id == other.parent_id
             && *name == other.name
             && *content == other.content
+            && *status == other.status
             && *subissues == other.subissues
             && *relations == other.relations
     }
@@ -76,8 +114,10 @@ impl<ID> Issue<ID> {
             parent_id: None,
             name: name.into(),
             content: Default::default(),
+            status: Default::default(),
             subissues: Default::default(),
             relations: Default::default(),
+            location: None,
         }
     }
 
@@ -90,6 +130,15 @@ impl<ID> Issue<ID> {
         self.content = content.into();
         self
     }
+
+    pub fn with_status(mut self, status: IssueStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.status == IssueStatus::Completed
+    }
 }
 
 impl<ID: HashedId> Issue<ID> {
@@ -104,6 +153,9 @@ pub struct Milestone<ID> {
     pub id: ID,
     pub name: String,
     pub needed_issues: IndexSet<ID>,
+
+    /// Source of the loaded milestone, which is not compared.
+    pub location: Option<SourceLocation>,
 }
 
 impl<ID: HashedId + PartialEq> PartialEq for Milestone<ID> {
@@ -112,6 +164,7 @@ impl<ID: HashedId + PartialEq> PartialEq for Milestone<ID> {
             id,
             name,
             needed_issues,
+            location: _,
         } = self;
 
         *id == other.id && *name == other.name && *needed_issues == other.needed_issues
@@ -126,6 +179,7 @@ impl<ID> Milestone<ID> {
             id,
             name: name.into(),
             needed_issues: Default::default(),
+            location: None,
         }
     }
 }
@@ -9,11 +9,14 @@ pub enum Step<ID> {
     Milestone(ID),
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct Plan<ID> {
     issues: IndexMap<ID, Issue<ID>>,
     milestones: IndexMap<ID, Milestone<ID>>,
     steps: IndexSet<Step<ID>>,
+
+    /// Steps of the named plan sections, like the ```` ```md todo backlog ```` block of a manifest.
+    sections: IndexMap<String, IndexSet<Step<ID>>>,
 }
 
 impl<ID> Plan<ID> {
@@ -22,6 +25,7 @@ impl<ID> Plan<ID> {
             issues: IndexMap::new(),
             milestones: IndexMap::new(),
             steps: IndexSet::new(),
+            sections: IndexMap::new(),
         }
     }
 }
@@ -32,9 +36,10 @@ impl<ID: HashedId + PartialEq + Clone> Plan<ID> {
             issues,
             milestones,
             steps,
+            sections,
         } = self;
 
-        issues.is_empty() && milestones.is_empty() && steps.is_empty()
+        issues.is_empty() && milestones.is_empty() && steps.is_empty() && sections.is_empty()
     }
 
     pub fn get_issue(&self, id: &ID) -> Option<&Issue<ID>> {
@@ -45,10 +50,38 @@ impl<ID: HashedId + PartialEq + Clone> Plan<ID> {
         self.milestones.get(id)
     }
 
+    pub fn get_issue_mut(&mut self, id: &ID) -> Option<&mut Issue<ID>> {
+        self.issues.get_mut(id)
+    }
+
+    pub fn get_milestone_mut(&mut self, id: &ID) -> Option<&mut Milestone<ID>> {
+        self.milestones.get_mut(id)
+    }
+
     pub fn steps(&self) -> &IndexSet<Step<ID>> {
         &self.steps
     }
 
+    pub fn sections(&self) -> &IndexMap<String, IndexSet<Step<ID>>> {
+        &self.sections
+    }
+
+    pub fn section(&self, name: impl AsRef<str>) -> Option<&IndexSet<Step<ID>>> {
+        self.sections.get(name.as_ref())
+    }
+
+    /// Name of the section containing the step.
+    pub fn section_of(&self, step: &Step<ID>) -> Option<&str> {
+        self.sections
+            .iter()
+            .find_map(|(name, steps)| steps.contains(step).then_some(name.as_str()))
+    }
+
+    /// Adds the steps to the named section, the section is created if it does not exist.
+    pub fn add_to_section(&mut self, name: impl Into<String>, steps: impl IntoIterator<Item = Step<ID>>) {
+        self.sections.entry(name.into()).or_default().extend(steps);
+    }
+
     pub fn add_issue(&mut self, issue: Issue<ID>) {
         self.steps.insert(Step::Issue(issue.id.clone()));
         self.issues.insert(issue.id.clone(), issue);
@@ -76,11 +109,15 @@ impl<ID: HashedId + PartialEq + Clone> Plan<ID> {
             issues,
             milestones,
             steps,
+            sections,
         } = &mut self;
 
         issues.extend(other.issues);
         milestones.extend(other.milestones);
         steps.extend(other.steps);
+        for (name, section_steps) in other.sections {
+            sections.entry(name).or_default().extend(section_steps);
+        }
 
         self
     }
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::io;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use todo_app::config::SourceConfig;
+use todo_lib::plan::{Plan, Step};
+use todo_tracker_fs::diagnostic::Severity;
+use todo_tracker_fs::dialect::{ListDialect, TAB_STOP};
+use todo_tracker_fs::generator::IntIdGenerator;
+use todo_tracker_fs::plan::{code_block_lines, LoadProjectPlan, PlanLines};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    /// Whole file is a list of issues, like `TODO.md`
+    Issues,
+
+    /// Issues are placed in the ```` ```md todo ```` code block of a manifest file
+    Manifest,
+}
+
+impl DocumentKind {
+    pub fn detect(file_name: &str, config: &SourceConfig) -> Self {
+        if config.manifest_filename_regex.is_match(file_name) {
+            Self::Manifest
+        } else {
+            Self::Issues
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Id(u64),
+    Anchor(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub range: Range,
+    pub target: Target,
+}
+
+pub struct Analysis {
+    pub lines: Vec<String>,
+    pub plan: Plan<u64>,
+    pub plan_lines: PlanLines<u64>,
+    pub references: Vec<Reference>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Analysis {
+    pub fn new(text: &str, kind: DocumentKind, start_id: u64, dialect: &ListDialect) -> Self {
+        let lines: Vec<String> = text.lines().map(ToOwned::to_owned).collect();
+        let numbered = lines.iter().cloned().enumerate().map(|(idx, line)| (idx, Ok(line)));
+        let plan_lines: Vec<(usize, String)> = match kind {
+            DocumentKind::Issues => numbered
+                .filter_map(|(idx, line)| line.ok().map(|line| (idx, line)))
+                .collect(),
+            DocumentKind::Manifest => code_block_lines(numbered)
+                .filter_map(|(idx, line)| line.ok().map(|line| (idx, line)))
+                .collect(),
+        };
+
+        let id_generator = IntIdGenerator::new(start_id);
+        let numbered_lines = plan_lines
+            .iter()
+            .cloned()
+            .map(|(idx, line)| (idx, io::Result::Ok(line)));
+        let located = match kind {
+            DocumentKind::Issues => Plan::load_diagnosed_from_lines(numbered_lines, &id_generator, dialect),
+            DocumentKind::Manifest => Plan::load_diagnosed_from_code_blocks(numbered_lines, &id_generator, dialect),
+        };
+        let (plan, located_lines, diagnostics) = match located {
+            Ok((plan, located_lines, plan_diagnostics)) => {
+                let diagnostics = plan_diagnostics
+                    .into_iter()
+                    .map(|diagnostic| {
+                        let range = column_range(&lines, diagnostic.line, diagnostic.column);
+                        let severity = match diagnostic.severity {
+                            Severity::Error => DiagnosticSeverity::ERROR,
+                            Severity::Warning => DiagnosticSeverity::WARNING,
+                        };
+                        self::diagnostic(range, severity, diagnostic.message)
+                    })
+                    .collect();
+                (plan, located_lines, diagnostics)
+            },
+            Err(err) => (Plan::new(), PlanLines::default(), vec![error(
+                line_range(&lines, 0),
+                err.to_string(),
+            )]),
+        };
+
+        let mut analysis = Self {
+            lines,
+            plan,
+            plan_lines: located_lines,
+            references: Vec::new(),
+            diagnostics,
+        };
+        analysis.collect_references(plan_lines.iter().map(|(idx, _)| *idx));
+        analysis
+    }
+
+    pub fn anchors(&self) -> HashMap<String, u64> {
+        self.plan
+            .steps()
+            .iter()
+            .filter_map(|step| match step {
+                Step::Milestone(id) => self
+                    .plan
+                    .get_milestone(id)
+                    .map(|milestone| (anchor_of(&milestone.name), *id)),
+                Step::Issue(_) => None,
+            })
+            .collect()
+    }
+
+    /// Line of the issue or milestone the target refers to.
+    pub fn resolve(&self, target: &Target) -> Option<usize> {
+        let id = match target {
+            Target::Id(id) => *id,
+            Target::Anchor(anchor) => *self.anchors().get(anchor)?,
+        };
+
+        self.plan_lines
+            .get_issue(&id)
+            .or_else(|| self.plan_lines.get_milestone(&id))
+            .map(|item| item.lines.start)
+    }
+
+    pub fn reference_at(&self, position: Position) -> Option<&Reference> {
+        self.references.iter().find(|reference| {
+            reference.range.start.line == position.line
+                && reference.range.start.character <= position.character
+                && position.character <= reference.range.end.character
+        })
+    }
+
+    pub fn line_range(&self, line_idx: usize) -> Range {
+        line_range(&self.lines, line_idx)
+    }
+
+    fn collect_references(&mut self, line_indices: impl IntoIterator<Item = usize>) {
+        static ID_REFERENCE_REGEX: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"(?:^|[\s(\[,])(#([0-9]+))\b").expect("regex must be correct"));
+        static ANCHOR_REFERENCE_REGEX: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"\]\((#([\w-]+))\)").expect("regex must be correct"));
+
+        let anchors = self.anchors();
+
+        for line_idx in line_indices {
+            let line = &self.lines[line_idx];
+
+            for captures in ID_REFERENCE_REGEX.captures_iter(line) {
+                let (Some(reference), Some(id)) = (captures.get(1), captures.get(2)) else {
+                    continue;
+                };
+                let Ok(id) = id.as_str().parse::<u64>() else {
+                    continue;
+                };
+                let range = span_range(line, line_idx, reference.start(), reference.end());
+
+                if self.plan.get_issue(&id).is_none() && self.plan.get_milestone(&id).is_none() {
+                    self.diagnostics.push(warning(
+                        range,
+                        format!("unknown relation target `{}`", reference.as_str()),
+                    ));
+                }
+                self.references.push(Reference {
+                    range,
+                    target: Target::Id(id),
+                });
+            }
+
+            for captures in ANCHOR_REFERENCE_REGEX.captures_iter(line) {
+                let (Some(reference), Some(anchor)) = (captures.get(1), captures.get(2)) else {
+                    continue;
+                };
+                let range = span_range(line, line_idx, reference.start(), reference.end());
+
+                if !anchors.contains_key(anchor.as_str()) {
+                    self.diagnostics.push(warning(
+                        range,
+                        format!("unknown relation target `{}`", reference.as_str()),
+                    ));
+                }
+                self.references.push(Reference {
+                    range,
+                    target: Target::Anchor(anchor.as_str().into()),
+                });
+            }
+        }
+    }
+}
+
+/// Markdown heading anchor of the milestone name: `Mile 2` becomes `mile-2`.
+pub fn anchor_of(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|ch| match ch {
+            ' ' => Some('-'),
+            ch if ch.is_alphanumeric() || ch == '-' || ch == '_' => Some(ch),
+            _ => None,
+        })
+        .collect()
+}
+
+fn error(range: Range, message: String) -> Diagnostic {
+    diagnostic(range, DiagnosticSeverity::ERROR, message)
+}
+
+fn warning(range: Range, message: String) -> Diagnostic {
+    diagnostic(range, DiagnosticSeverity::WARNING, message)
+}
+
+fn diagnostic(range: Range, severity: DiagnosticSeverity, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        source: Some("todo".into()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Range from the column, counted with the expanded tabs, to the end of the line.
+fn column_range(lines: &[String], line_idx: usize, column: usize) -> Range {
+    let line = lines.get(line_idx).map(String::as_str).unwrap_or_default();
+    let mut current = 0;
+    let start = line
+        .char_indices()
+        .find(|(_, ch)| {
+            let found = current >= column;
+            current = match ch {
+                '\t' => current + TAB_STOP - current % TAB_STOP,
+                _ => current + 1,
+            };
+            found
+        })
+        .map_or(line.len(), |(idx, _)| idx);
+    span_range(line, line_idx, start, line.len())
+}
+
+fn line_range(lines: &[String], line_idx: usize) -> Range {
+    let line = lines.get(line_idx).map(String::as_str).unwrap_or_default();
+    span_range(line, line_idx, 0, line.len())
+}
+
+/// Converts the byte span of the line into the LSP range, which counts characters in UTF-16 code units.
+fn span_range(line: &str, line_idx: usize, start: usize, end: usize) -> Range {
+    let character = |byte_idx: usize| line[..byte_idx].encode_utf16().count() as u32;
+    Range::new(
+        Position::new(line_idx as u32, character(start)),
+        Position::new(line_idx as u32, character(end)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_errors() {
+        let analysis = Analysis::new(
+            "- task A\n      - task B\n   - task C\n",
+            DocumentKind::Issues,
+            1,
+            &ListDialect::default(),
+        );
+        let messages: Vec<_> = analysis
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let Range { start, end } = diagnostic.range;
+                (start.line, start.character, end.character, diagnostic.message.as_str())
+            })
+            .collect();
+
+        assert_eq!(messages, vec![(
+            1,
+            6,
+            14,
+            "issue level = 2 is greater than previous issue level + 1 = 1"
+        )]);
+    }
+
+    #[test]
+    fn relation_targets() {
+        let text =
+            "```md todo\n- task A\n- task B after #1, see #9\n- task C [Mile](#mile) [Other](#other)\n\n# Mile\n```\n";
+        let analysis = Analysis::new(text, DocumentKind::Manifest, 1, &ListDialect::default());
+
+        let messages: Vec<_> = analysis
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        assert_eq!(messages, vec![
+            "unknown relation target `#9`",
+            "unknown relation target `#other`"
+        ]);
+
+        assert_eq!(analysis.references.len(), 4);
+        assert_eq!(analysis.resolve(&Target::Id(1)), Some(1));
+        assert_eq!(analysis.resolve(&Target::Anchor("mile".into())), Some(5));
+    }
+}
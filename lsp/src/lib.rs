@@ -0,0 +1,4 @@
+pub use crate::server::run;
+
+pub mod analysis;
+pub mod server;
@@ -0,0 +1,13 @@
+use lsp_server::Connection;
+use todo_app::config::Config;
+
+fn main() -> anyhow::Result<()> {
+    let config = Config::load(None)?;
+
+    let (connection, io_threads) = Connection::stdio();
+    todo_lsp::run(&connection, &config.source)?;
+    drop(connection);
+    io_threads.join()?;
+
+    Ok(())
+}
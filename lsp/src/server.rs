@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, LogMessage, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, DocumentSymbolRequest, GotoDefinition, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse, DocumentSymbol,
+    DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse, Location,
+    LogMessageParams, MessageType, OneOf, PublishDiagnosticsParams, ServerCapabilities, SymbolKind,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+use todo_app::config::SourceConfig;
+use todo_lib::plan::Step;
+use todo_tracker_fs::config::FsProjectConfig;
+use url::Url;
+
+use crate::analysis::{Analysis, DocumentKind};
+
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["#".into()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Serves the language server protocol over the connection until the client requests shutdown.
+pub fn run(connection: &Connection, config: &SourceConfig) -> anyhow::Result<()> {
+    let capabilities = serde_json::to_value(capabilities())?;
+    connection.initialize(capabilities)?;
+
+    let mut server = Server {
+        connection,
+        config,
+        documents: HashMap::new(),
+    };
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                server.handle_request(request)?;
+            },
+            Message::Notification(notification) => server.handle_notification(notification)?,
+            Message::Response(_) => {},
+        }
+    }
+
+    Ok(())
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    config: &'a SourceConfig,
+    documents: HashMap<Uri, Analysis>,
+}
+
+impl Server<'_> {
+    fn handle_request(&mut self, request: Request) -> anyhow::Result<()> {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            DocumentSymbolRequest::METHOD => extract::<DocumentSymbolRequest>(request)
+                .map(|params| serde_json::to_value(self.document_symbols(params))),
+            GotoDefinition::METHOD => {
+                extract::<GotoDefinition>(request).map(|params| serde_json::to_value(self.definition(params)))
+            },
+            Completion::METHOD => {
+                extract::<Completion>(request).map(|params| serde_json::to_value(self.completion(params)))
+            },
+            method => Err(Box::new(Response::new_err(
+                id.clone(),
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request `{method}`"),
+            ))),
+        };
+
+        let response = match result {
+            Ok(value) => Response::new_ok(id, value?),
+            Err(response) => *response,
+        };
+        self.connection.sender.send(response.into())?;
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> anyhow::Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Some(params) = self.extract_notification::<DidOpenTextDocument>(notification)? else {
+                    return Ok(());
+                };
+                self.update(params.text_document.uri, &params.text_document.text)
+            },
+            DidChangeTextDocument::METHOD => {
+                let Some(params) = self.extract_notification::<DidChangeTextDocument>(notification)? else {
+                    return Ok(());
+                };
+                match params.content_changes.into_iter().last() {
+                    Some(change) => self.update(params.text_document.uri, &change.text),
+                    None => Ok(()),
+                }
+            },
+            DidCloseTextDocument::METHOD => {
+                let Some(params) = self.extract_notification::<DidCloseTextDocument>(notification)? else {
+                    return Ok(());
+                };
+                self.documents.remove(&params.text_document.uri);
+                self.publish_diagnostics(params.text_document.uri, Vec::new())
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Parameters of the notification, the malformed notification is logged to the client and skipped.
+    fn extract_notification<N>(&self, notification: Notification) -> anyhow::Result<Option<N::Params>>
+    where
+        N: lsp_types::notification::Notification,
+    {
+        match notification.extract(N::METHOD) {
+            Ok(params) => Ok(Some(params)),
+            Err(err) => {
+                self.log_error(format!("invalid `{}` notification: {err}", N::METHOD))?;
+                Ok(None)
+            },
+        }
+    }
+
+    fn log_error(&self, message: String) -> anyhow::Result<()> {
+        let params = LogMessageParams {
+            typ: MessageType::ERROR,
+            message,
+        };
+        let notification = Notification::new(LogMessage::METHOD.into(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+
+    fn update(&mut self, uri: Uri, text: &str) -> anyhow::Result<()> {
+        let path = uri_to_path(&uri);
+        let file_name = path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let kind = DocumentKind::detect(&file_name, self.config);
+        let start_id = path
+            .as_deref()
+            .and_then(Path::parent)
+            .and_then(|dir| self.config.find_project_config_placement(dir, None))
+            .and_then(|placement| FsProjectConfig::<String>::load(&placement).ok())
+            .and_then(|project_config| project_config.start_id)
+            .unwrap_or(1);
+
+        let analysis = Analysis::new(text, kind, start_id, &self.config.list);
+        let diagnostics = analysis.diagnostics.clone();
+        self.documents.insert(uri.clone(), analysis);
+        self.publish_diagnostics(uri, diagnostics)
+    }
+
+    fn publish_diagnostics(&self, uri: Uri, diagnostics: Vec<lsp_types::Diagnostic>) -> anyhow::Result<()> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        let notification = Notification::new(PublishDiagnostics::METHOD.into(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+
+    fn document_symbols(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let analysis = self.documents.get(&params.text_document.uri)?;
+        let plan = &analysis.plan;
+
+        let symbols = plan
+            .steps()
+            .iter()
+            .filter_map(|step| match step {
+                Step::Issue(id) => plan
+                    .get_issue(id)
+                    .filter(|issue| issue.parent_id.is_none())
+                    .and_then(|_| issue_symbol(analysis, *id)),
+                Step::Milestone(id) => {
+                    let milestone = plan.get_milestone(id)?;
+                    let range = analysis.line_range(analysis.plan_lines.get_milestone(id)?.lines.start);
+                    Some(symbol(
+                        milestone.name.clone(),
+                        *id,
+                        SymbolKind::MODULE,
+                        range,
+                        range,
+                        None,
+                    ))
+                },
+            })
+            .collect();
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        let analysis = self.documents.get(&position.text_document.uri)?;
+        let reference = analysis.reference_at(position.position)?;
+        let line_idx = analysis.resolve(&reference.target)?;
+
+        Some(GotoDefinitionResponse::Scalar(Location::new(
+            position.text_document.uri,
+            analysis.line_range(line_idx),
+        )))
+    }
+
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let analysis = self.documents.get(&position.text_document.uri)?;
+        let line = analysis.lines.get(position.position.line as usize)?;
+
+        let before_cursor: String = char::decode_utf16(line.encode_utf16().take(position.position.character as usize))
+            .filter_map(Result::ok)
+            .collect();
+        let typed_id = before_cursor.trim_end_matches(|ch: char| ch.is_ascii_digit());
+        if !typed_id.ends_with('#') {
+            return None;
+        }
+
+        let items = analysis
+            .plan
+            .steps()
+            .iter()
+            .filter_map(|step| match step {
+                Step::Issue(id) => analysis.plan.get_issue(id).map(|issue| CompletionItem {
+                    label: id.to_string(),
+                    kind: Some(CompletionItemKind::REFERENCE),
+                    detail: Some(issue.name.clone()),
+                    ..Default::default()
+                }),
+                Step::Milestone(id) => analysis.plan.get_milestone(id).map(|milestone| CompletionItem {
+                    label: id.to_string(),
+                    kind: Some(CompletionItemKind::MODULE),
+                    detail: Some(milestone.name.clone()),
+                    ..Default::default()
+                }),
+            })
+            .collect();
+
+        Some(CompletionResponse::Array(items))
+    }
+}
+
+fn issue_symbol(analysis: &Analysis, id: u64) -> Option<DocumentSymbol> {
+    let issue = analysis.plan.get_issue(&id)?;
+    let lines = &analysis.plan_lines.get_issue(&id)?.lines;
+    let selection_range = analysis.line_range(lines.start);
+    let mut range = selection_range;
+    range.end = analysis.line_range(lines.end - 1).end;
+
+    let children: Vec<_> = issue
+        .subissues
+        .iter()
+        .filter_map(|subissue_id| issue_symbol(analysis, *subissue_id))
+        .collect();
+    if let Some(last_child) = children.last() {
+        range.end = range.end.max(last_child.range.end);
+    }
+
+    Some(symbol(
+        issue.name.clone(),
+        id,
+        SymbolKind::EVENT,
+        range,
+        selection_range,
+        (!children.is_empty()).then_some(children),
+    ))
+}
+
+#[allow(deprecated)]
+fn symbol(
+    name: String,
+    id: u64,
+    kind: SymbolKind,
+    range: lsp_types::Range,
+    selection_range: lsp_types::Range,
+    children: Option<Vec<DocumentSymbol>>,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail: Some(format!("#{id}")),
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children,
+    }
+}
+
+fn extract<R>(request: Request) -> Result<R::Params, Box<Response>>
+where
+    R: lsp_types::request::Request,
+{
+    let id = request.id.clone();
+    request
+        .extract(R::METHOD)
+        .map(|(_, params)| params)
+        .map_err(|err| Box::new(Response::new_err(id, ErrorCode::InvalidParams as i32, err.to_string())))
+}
+
+/// Local file path of the `file://` URI.
+fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+    Url::parse(uri.as_str()).ok()?.to_file_path().ok()
+}
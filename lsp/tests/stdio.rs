@@ -0,0 +1,185 @@
+use std::io::{BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use lsp_server::{Message, Notification, Request, RequestId};
+use serde_json::{json, Value};
+use temp_testdir::TempDir;
+
+struct Client {
+    process: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i32,
+}
+
+impl Client {
+    fn start(work_dir: &TempDir) -> Self {
+        let mut process = Command::new(env!("CARGO_BIN_EXE_todo-lsp"))
+            .current_dir(work_dir.as_ref())
+            .env("TODO_ROOT_CONFIG", "./todo.toml")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("todo-lsp must start");
+        let stdin = process.stdin.take().unwrap();
+        let stdout = BufReader::new(process.stdout.take().unwrap());
+
+        let mut client = Self {
+            process,
+            stdin,
+            stdout,
+            next_id: 0,
+        };
+        let result = client.request("initialize", json!({ "capabilities": {} }));
+        assert_eq!(
+            result["capabilities"]["completionProvider"]["triggerCharacters"],
+            json!(["#"])
+        );
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn send(&mut self, message: Message) {
+        message.write(&mut self.stdin).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Message {
+        Message::read(&mut self.stdout).unwrap().expect("server must answer")
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(Notification::new(method.into(), params).into());
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        self.send(Request::new(id.clone(), method.into(), params).into());
+
+        loop {
+            if let Message::Response(response) = self.receive() {
+                assert_eq!(response.id, id);
+                assert!(response.error.is_none(), "{:?}", response.error);
+                return response.result.unwrap_or(Value::Null);
+            }
+        }
+    }
+
+    fn diagnostics(&mut self) -> Value {
+        loop {
+            if let Message::Notification(notification) = self.receive()
+                && notification.method == "textDocument/publishDiagnostics"
+            {
+                return notification.params["diagnostics"].clone();
+            }
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": { "uri": uri, "languageId": "markdown", "version": 1, "text": text } }),
+        );
+        self.diagnostics()
+    }
+
+    fn stop(mut self) {
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.process.wait().unwrap().success());
+    }
+}
+
+const TODO_URI: &str = "file:///project/TODO.md";
+
+#[test]
+fn diagnostics() {
+    let work_dir = TempDir::default();
+    let mut client = Client::start(&work_dir);
+
+    let diagnostics = client.open(TODO_URI, "- task A\n      - task B\n- task C after #7\n");
+    assert_eq!(diagnostics.as_array().unwrap().len(), 2);
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+    assert_eq!(
+        diagnostics[0]["message"],
+        "issue level = 2 is greater than previous issue level + 1 = 1"
+    );
+    assert_eq!(diagnostics[1]["message"], "unknown relation target `#7`");
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": TODO_URI, "version": 2 },
+            "contentChanges": [{ "text": "- task A\n  - task B\n- task C after #2\n" }],
+        }),
+    );
+    assert_eq!(client.diagnostics(), json!([]));
+
+    client.stop();
+}
+
+#[test]
+fn malformed_notification() {
+    let work_dir = TempDir::default();
+    let mut client = Client::start(&work_dir);
+
+    client.notify("textDocument/didOpen", json!({ "textDocument": { "uri": TODO_URI } }));
+    let Message::Notification(notification) = client.receive() else {
+        panic!("server must log the malformed notification");
+    };
+    assert_eq!(notification.method, "window/logMessage");
+    assert_eq!(notification.params["type"], 1);
+
+    assert_eq!(client.open(TODO_URI, "- task A\n"), json!([]));
+
+    client.stop();
+}
+
+#[test]
+fn outline_definition_and_completion() {
+    let work_dir = TempDir::default();
+    let mut client = Client::start(&work_dir);
+
+    let uri = "file:///project/project.manifest.md";
+    let text = "# Project\n\n```md todo\n- task A\n  - task B\n\n# Mile\n\n- task C after #2\n- task D #\n```\n";
+    assert_eq!(client.open(uri, text), json!([]));
+
+    let symbols = client.request("textDocument/documentSymbol", json!({ "textDocument": { "uri": uri } }));
+    let names: Vec<_> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|symbol| symbol["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["task A", "Mile", "task C after #2", "task D #"]);
+    assert_eq!(symbols[0]["children"][0]["name"], "task B");
+    assert_eq!(symbols[0]["children"][0]["detail"], "#2");
+
+    let definition = client.request(
+        "textDocument/definition",
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 8, "character": 16 } }),
+    );
+    assert_eq!(definition["uri"], uri);
+    assert_eq!(definition["range"]["start"]["line"], 4);
+
+    let completion = client.request(
+        "textDocument/completion",
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 9, "character": 10 } }),
+    );
+    let labels: Vec<_> = completion
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| (item["label"].as_str().unwrap(), item["detail"].as_str().unwrap()))
+        .collect();
+    assert_eq!(labels, [
+        ("1", "task A"),
+        ("2", "task B"),
+        ("3", "Mile"),
+        ("4", "task C after #2"),
+        ("5", "task D #")
+    ]);
+
+    client.stop();
+}
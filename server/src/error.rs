@@ -0,0 +1,48 @@
+use std::io;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+use crate::model::ErrorResponse;
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("project `{0}` not found")]
+    ProjectNotFound(String),
+
+    #[error("previous change of the project files has failed, restart the server")]
+    WriteLockPoisoned,
+
+    #[error("{0}")]
+    Io(#[from] io::Error),
+
+    #[error("{0}")]
+    Other(#[from] anyhow::Error),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::ProjectNotFound(_) => StatusCode::NOT_FOUND,
+            Self::WriteLockPoisoned => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Io(err) => match err.kind() {
+                io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+                io::ErrorKind::AlreadyExists => StatusCode::CONFLICT,
+                io::ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            Self::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: self.to_string(),
+        };
+        (self.status(), Json(body)).into_response()
+    }
+}
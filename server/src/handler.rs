@@ -0,0 +1,193 @@
+use std::io;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use todo_app::issue::IssueUpdate;
+use todo_app::project::{FsProjectMetadata, ProjectData};
+use todo_app::target::Location;
+use todo_app::{issue, locate_project_config, open_tracker};
+use todo_lib::issue::IssueStatus;
+use todo_lib::plan::Step;
+use todo_tracker_fs::config::FsProjectConfig;
+use todo_tracker_fs::tracker;
+
+use crate::error::ApiError;
+use crate::model::{AddIssueRequest, IssueInfo, PlanInfo, ProjectInfo, UpdateIssueRequest};
+use crate::ServerState;
+
+pub async fn list_projects(State(state): State<Arc<ServerState>>) -> Result<Json<Vec<ProjectInfo>>, ApiError> {
+    blocking(state, |state| {
+        let tracker = open_tracker::<String>(None, state.search_roots(), state.config())?;
+        let subprojects = tracker.subprojects();
+
+        let projects = tracker
+            .projects()
+            .values()
+            .map(|project| {
+                let root_dir = tracker.project_root_dir(project.id()).map(ToOwned::to_owned);
+                let children = subprojects
+                    .get(project.id())
+                    .map(|children| children.iter().cloned().collect())
+                    .unwrap_or_default();
+                ProjectInfo::new(project, root_dir, children)
+            })
+            .collect();
+
+        Ok(Json(projects))
+    })
+    .await
+}
+
+pub async fn get_plan(
+    State(state): State<Arc<ServerState>>,
+    Path(project): Path<String>,
+) -> Result<Json<PlanInfo>, ApiError> {
+    blocking(state, move |state| {
+        let tracker = open_tracker(
+            Some(Location::Id(project.clone())),
+            state.search_roots(),
+            state.config(),
+        )?;
+        if !tracker.projects().contains_key(&project) {
+            return Err(ApiError::ProjectNotFound(project));
+        }
+
+        let plan = PlanInfo::new(project.clone(), tracker.project_plan(&project)?);
+        Ok(Json(plan))
+    })
+    .await
+}
+
+pub async fn get_issue(
+    State(state): State<Arc<ServerState>>,
+    Path((project, issue_id)): Path<(String, u64)>,
+) -> Result<Json<IssueInfo>, ApiError> {
+    blocking(state, move |state| {
+        let project_config = locate(state, &project)?;
+        find_issue(state, &project_config, issue_id).map(Json)
+    })
+    .await
+}
+
+pub async fn add_issue(
+    State(state): State<Arc<ServerState>>,
+    Path(project): Path<String>,
+    Json(request): Json<AddIssueRequest>,
+) -> Result<(StatusCode, Json<IssueInfo>), ApiError> {
+    blocking(state, move |state| {
+        let config = state.config();
+        let AddIssueRequest { name, content, order } = request;
+        let order = order.unwrap_or_else(|| config.issue.add_order.into_order());
+
+        let _guard = state.write_lock()?;
+        let project_config = locate(state, &project)?;
+        let project_data = ProjectData::Fs(FsProjectMetadata::default().with_config(project_config.clone()));
+        let location = issue::add(project_data, &config.source, order, None, &name, content)?;
+
+        // The issues with the same name are told apart by their lines
+        let plan = tracker::load_project_plan(
+            &project_config,
+            &config.source.manifest_filename_regex,
+            &config.source.issues_filename_regex,
+            &config.source.list,
+        )?;
+        let issue = plan
+            .as_ref()
+            .and_then(|plan| {
+                plan.steps().iter().find_map(|step| match step {
+                    Step::Issue(id) => plan.get_issue(id).filter(|issue| {
+                        issue.location.as_ref().is_some_and(|issue_location| {
+                            issue_location.path == location.path && issue_location.lines.start == location.lines.start
+                        })
+                    }),
+                    Step::Milestone(_) => None,
+                })
+            })
+            .map(IssueInfo::from)
+            .ok_or_else(|| io::Error::other(format!("added issue `{name}` is not found")))?;
+
+        Ok((StatusCode::CREATED, Json(issue)))
+    })
+    .await
+}
+
+pub async fn update_issue(
+    State(state): State<Arc<ServerState>>,
+    Path((project, issue_id)): Path<(String, u64)>,
+    Json(request): Json<UpdateIssueRequest>,
+) -> Result<Json<IssueInfo>, ApiError> {
+    let UpdateIssueRequest {
+        name,
+        content,
+        completed,
+    } = request;
+    let update = IssueUpdate {
+        name,
+        content,
+        status: completed.map(|completed| {
+            if completed {
+                IssueStatus::Completed
+            } else {
+                IssueStatus::Open
+            }
+        }),
+    };
+
+    blocking(state, move |state| {
+        let _guard = state.write_lock()?;
+        let project_config = locate(state, &project)?;
+        let project_data = ProjectData::Fs(FsProjectMetadata::default().with_config(project_config.clone()));
+        issue::update(project_data, &state.config().source, issue_id, update)?;
+
+        find_issue(state, &project_config, issue_id).map(Json)
+    })
+    .await
+}
+
+pub async fn remove_issue(
+    State(state): State<Arc<ServerState>>,
+    Path((project, issue_id)): Path<(String, u64)>,
+) -> Result<StatusCode, ApiError> {
+    blocking(state, move |state| {
+        let _guard = state.write_lock()?;
+        let project_config = locate(state, &project)?;
+        let project_data = ProjectData::Fs(FsProjectMetadata::default().with_config(project_config));
+        issue::remove(project_data, &state.config().source, issue_id)?;
+
+        Ok(StatusCode::NO_CONTENT)
+    })
+    .await
+}
+
+/// Runs the handler work, which reads and writes the project files, on the blocking threads of the runtime.
+async fn blocking<T, F>(state: Arc<ServerState>, work: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&ServerState) -> Result<T, ApiError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || work(&state))
+        .await
+        .map_err(|err| ApiError::Other(err.into()))?
+}
+
+fn locate(state: &ServerState, project: &str) -> Result<FsProjectConfig, ApiError> {
+    locate_project_config(Location::Id(project.to_string()), state.search_roots(), state.config())?
+        .ok_or_else(|| ApiError::ProjectNotFound(project.into()))
+}
+
+fn find_issue(state: &ServerState, project_config: &FsProjectConfig, issue_id: u64) -> Result<IssueInfo, ApiError> {
+    let source = &state.config().source;
+    let plan = tracker::load_project_plan(
+        project_config,
+        &source.manifest_filename_regex,
+        &source.issues_filename_regex,
+        &source.list,
+    )?;
+
+    plan.as_ref()
+        .and_then(|plan| plan.get_issue(&issue_id))
+        .map(IssueInfo::from)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("issue `{issue_id}` not found")).into())
+}
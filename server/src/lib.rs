@@ -0,0 +1,68 @@
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use axum::routing::{get, post};
+use axum::Router;
+use todo_app::config::Config;
+
+pub use crate::error::ApiError;
+
+pub mod error;
+pub mod handler;
+pub mod model;
+
+pub struct ServerState {
+    config: Config,
+    search_roots: Vec<PathBuf>,
+    write_lock: Mutex<()>,
+}
+
+impl ServerState {
+    pub fn new(config: Config, search_roots: Vec<PathBuf>) -> Self {
+        Self {
+            config,
+            search_roots,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn search_roots(&self) -> &[PathBuf] {
+        &self.search_roots
+    }
+
+    /// Serializes the changes of the project files, a handler panicked while holding the lock is reported as
+    /// the error instead of the panic.
+    fn write_lock(&self) -> Result<MutexGuard<'_, ()>, ApiError> {
+        self.write_lock.lock().map_err(|_| ApiError::WriteLockPoisoned)
+    }
+}
+
+pub fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/api/projects", get(handler::list_projects))
+        .route("/api/projects/{project}/plan", get(handler::get_plan))
+        .route("/api/projects/{project}/issues", post(handler::add_issue))
+        .route(
+            "/api/projects/{project}/issues/{issue}",
+            get(handler::get_issue)
+                .patch(handler::update_issue)
+                .delete(handler::remove_issue),
+        )
+        .with_state(Arc::new(state))
+}
+
+/// Runs the API server on the given address until the process is stopped.
+pub fn serve(addr: SocketAddr, state: ServerState) -> io::Result<()> {
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_io().build()?;
+
+    runtime.block_on(async move {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, router(state)).await
+    })
+}
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use todo_app::issue::Order;
+use todo_lib::issue::{Issue, Milestone};
+use todo_lib::plan::{Plan, Step};
+use todo_lib::project::Project;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProjectInfo {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub root_dir: Option<PathBuf>,
+    pub subprojects: Vec<String>,
+}
+
+impl ProjectInfo {
+    pub fn new(project: &Project<String>, root_dir: Option<PathBuf>, subprojects: Vec<String>) -> Self {
+        Self {
+            id: project.id().clone(),
+            name: project.name().into(),
+            parent_id: project.parent_id().cloned(),
+            root_dir,
+            subprojects,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct IssueInfo {
+    pub id: u64,
+    pub parent_id: Option<u64>,
+    pub name: String,
+    pub content: String,
+    #[serde(default)]
+    pub completed: bool,
+    pub subissues: Vec<u64>,
+}
+
+impl From<&Issue<u64>> for IssueInfo {
+    fn from(issue: &Issue<u64>) -> Self {
+        Self {
+            id: issue.id,
+            parent_id: issue.parent_id,
+            name: issue.name.clone(),
+            content: issue.content.clone(),
+            completed: issue.is_completed(),
+            subissues: issue.subissues.iter().copied().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MilestoneInfo {
+    pub id: u64,
+    pub name: String,
+    pub needed_issues: Vec<u64>,
+}
+
+impl From<&Milestone<u64>> for MilestoneInfo {
+    fn from(milestone: &Milestone<u64>) -> Self {
+        Self {
+            id: milestone.id,
+            name: milestone.name.clone(),
+            needed_issues: milestone.needed_issues.iter().copied().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepInfo {
+    Issue(IssueInfo),
+    Milestone(MilestoneInfo),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlanInfo {
+    pub project: String,
+    pub steps: Vec<StepInfo>,
+}
+
+impl PlanInfo {
+    pub fn new(project: impl Into<String>, plan: Option<&Plan<u64>>) -> Self {
+        let steps = plan
+            .map(|plan| {
+                plan.steps()
+                    .iter()
+                    .filter_map(|step| match step {
+                        Step::Issue(id) => plan.get_issue(id).map(|issue| StepInfo::Issue(issue.into())),
+                        Step::Milestone(id) => plan
+                            .get_milestone(id)
+                            .map(|milestone| StepInfo::Milestone(milestone.into())),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            project: project.into(),
+            steps,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AddIssueRequest {
+    pub name: String,
+
+    #[serde(default)]
+    pub content: String,
+
+    /// Defaults to the `issue.add_order` config value
+    pub order: Option<Order>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UpdateIssueRequest {
+    pub name: Option<String>,
+    pub content: Option<String>,
+    pub completed: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
@@ -0,0 +1,168 @@
+use std::fs;
+use std::path::Path;
+
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use http_body_util::BodyExt;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use temp_testdir::TempDir;
+use todo_app::config::Config;
+use todo_server::model::{IssueInfo, PlanInfo, ProjectInfo, StepInfo};
+use todo_server::{router, ServerState};
+
+fn create_project(root: &Path, id: &str, todo: &str) -> anyhow::Result<()> {
+    let project_dir = root.join(id);
+    fs::create_dir_all(&project_dir)?;
+    fs::write(
+        project_dir.join("Project.toml"),
+        format!("id = \"{id}\"\nname = \"{id}\"\n"),
+    )?;
+    fs::write(project_dir.join("TODO.md"), todo)?;
+    Ok(())
+}
+
+fn app(root: &Path) -> Router {
+    router(ServerState::new(Config::default(), vec![root.to_path_buf()]))
+}
+
+async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Vec<u8>) {
+    use tower::ServiceExt;
+
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, bytes.to_vec())
+}
+
+fn parse<T: DeserializeOwned>(bytes: &[u8]) -> T {
+    serde_json::from_slice(bytes).unwrap()
+}
+
+#[tokio::test]
+async fn list_projects() -> anyhow::Result<()> {
+    let temp_dir = TempDir::default();
+    create_project(&temp_dir, "alpha", "- task 1\n")?;
+    create_project(&temp_dir.join("alpha"), "beta", "")?;
+
+    let (status, body) = send(&app(&temp_dir), Method::GET, "/api/projects", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let mut projects: Vec<ProjectInfo> = parse(&body);
+    projects.sort_by(|a, b| a.id.cmp(&b.id));
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0].id, "alpha");
+    assert_eq!(projects[0].subprojects, vec!["beta".to_string()]);
+    assert_eq!(projects[1].id, "beta");
+    assert_eq!(projects[1].parent_id.as_deref(), Some("alpha"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_plan() -> anyhow::Result<()> {
+    let temp_dir = TempDir::default();
+    create_project(&temp_dir, "alpha", "- task 1\n  - task 2\n\n# Mile\n")?;
+    let app = app(&temp_dir);
+
+    let (status, body) = send(&app, Method::GET, "/api/projects/alpha/plan", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let plan: PlanInfo = parse(&body);
+    assert_eq!(plan.project, "alpha");
+    assert_eq!(plan.steps.len(), 3);
+    assert!(matches!(&plan.steps[0], StepInfo::Issue(issue) if issue.name == "task 1" && issue.subissues == [2]));
+    assert!(matches!(&plan.steps[1], StepInfo::Issue(issue) if issue.parent_id == Some(1)));
+    assert!(matches!(&plan.steps[2], StepInfo::Milestone(milestone) if milestone.needed_issues == [1, 2]));
+
+    let (status, _) = send(&app, Method::GET, "/api/projects/unknown/plan", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_issue() -> anyhow::Result<()> {
+    let temp_dir = TempDir::default();
+    create_project(&temp_dir, "alpha", "- task 1\n")?;
+    let app = app(&temp_dir);
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/projects/alpha/issues",
+        Some(json!({ "name": "task 0", "order": "first" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(parse::<IssueInfo>(&body).name, "task 0");
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/projects/alpha/issues",
+        Some(json!({ "name": "task 2", "content": "details" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let issue: IssueInfo = parse(&body);
+    assert_eq!((issue.id, issue.name.as_str()), (3, "task 2"));
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        "/api/projects/alpha/issues",
+        Some(json!({ "name": "task 1" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let todo = fs::read_to_string(temp_dir.join("alpha").join("TODO.md"))?;
+    assert_eq!(todo, "- task 0\n- task 1\n- task 2\n  details\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn update_and_remove_issue() -> anyhow::Result<()> {
+    let temp_dir = TempDir::default();
+    create_project(&temp_dir, "alpha", "- task 1\n  - task 2\n    old\n- task 3\n")?;
+    let app = app(&temp_dir);
+    let todo_file = temp_dir.join("alpha").join("TODO.md");
+
+    let (status, body) = send(
+        &app,
+        Method::PATCH,
+        "/api/projects/alpha/issues/2",
+        Some(json!({ "name": "task two", "content": "new\nlines" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let issue: IssueInfo = parse(&body);
+    assert_eq!(issue.name, "task two");
+    assert_eq!(issue.content, "new\nlines");
+    assert_eq!(
+        fs::read_to_string(&todo_file)?,
+        "- task 1\n  - task two\n    new\n    lines\n- task 3\n"
+    );
+
+    let (status, _) = send(&app, Method::DELETE, "/api/projects/alpha/issues/1", None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(fs::read_to_string(&todo_file)?, "- task 3\n");
+
+    let (status, body) = send(&app, Method::DELETE, "/api/projects/alpha/issues/7", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(parse::<Value>(&body)["error"], "issue `7` not found");
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use indexmap::IndexMap;
+use regex::Regex;
+use temp_testdir::TempDir;
+use todo_tracker_fs::config::FsProjectConfig;
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::tracker::load_project_plan;
+use todo_tracker_fs::FsTracker;
+
+const ISSUES_PER_PROJECT: usize = 200;
+
+/// Creates the tree of projects: each top-level project has 4 subprojects with the issues files.
+fn generate_projects(root: &Path, count: usize) -> IndexMap<String, FsProjectConfig> {
+    let mut todo = String::new();
+    for issue_idx in 0..ISSUES_PER_PROJECT {
+        todo.push_str(&format!("- task {issue_idx}\n  description of the task {issue_idx}\n"));
+        if issue_idx % 5 == 0 {
+            todo.push_str(&format!("  - subtask {issue_idx}\n"));
+        }
+        if issue_idx % 50 == 49 {
+            todo.push_str(&format!("\n# Milestone {issue_idx}\n\n"));
+        }
+    }
+
+    let mut configs = IndexMap::new();
+    for project_idx in 0..count {
+        let parent_dir = root.join(format!("group {}", project_idx / 5));
+        let project_dir = if project_idx % 5 == 0 {
+            parent_dir
+        } else {
+            parent_dir.join(format!("project {project_idx}"))
+        };
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("TODO.md"), &todo).unwrap();
+
+        let id = format!("project {project_idx}");
+        configs.insert(id.clone(), FsProjectConfig {
+            id,
+            root_dir: Some(project_dir),
+            ..Default::default()
+        });
+    }
+    configs
+}
+
+fn load_plans(c: &mut Criterion) {
+    let manifest_regex = Regex::new(r"\.manifest\.md$").unwrap();
+    let issues_regex = Regex::new(r"^TODO\.md$").unwrap();
+    let dialect = ListDialect::default();
+    let mut group = c.benchmark_group("load_plans");
+    group.sample_size(20);
+
+    for count in [20, 100, 400] {
+        let temp_dir = TempDir::default();
+        let configs = generate_projects(&temp_dir, count);
+
+        group.bench_with_input(BenchmarkId::new("sequential", count), &configs, |b, configs| {
+            b.iter(|| {
+                for config in configs.values() {
+                    black_box(load_project_plan(config, &manifest_regex, &issues_regex, &dialect).unwrap());
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("tracker", count), &configs, |b, configs| {
+            b.iter(|| {
+                let tracker = FsTracker::new(configs.clone(), &manifest_regex, &issues_regex, &dialect);
+                tracker.load_plans().unwrap();
+                black_box(tracker)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("lazy", count), &configs, |b, configs| {
+            b.iter(|| {
+                let tracker = FsTracker::new(configs.clone(), &manifest_regex, &issues_regex, &dialect);
+                for id in configs.keys() {
+                    black_box(tracker.project_plan(id).unwrap());
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("steps_count", count), &configs, |b, configs| {
+            b.iter(|| {
+                let tracker = FsTracker::new(configs.clone(), &manifest_regex, &issues_regex, &dialect);
+                for id in configs.keys() {
+                    black_box(tracker.project_steps_count(id).unwrap());
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, load_plans);
+criterion_main!(benches);
@@ -0,0 +1,117 @@
+use std::ops::Range;
+use std::path::Path;
+use std::{fs, io};
+
+use todo_lib::plan::{Plan, Step};
+
+use crate::document::Document;
+use crate::plan::ItemLines;
+use crate::tracker::PlanSource;
+
+/// Steps of the plan which are done: the top-level issues completed together with all of their subissues and
+/// the milestones all needed issues of which are completed. The milestones without needed issues are never done.
+pub fn finished_steps(plan: &Plan<u64>) -> Vec<Step<u64>> {
+    let is_finished_subtree = |id: &u64| {
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            match plan.get_issue(id) {
+                Some(issue) if issue.is_completed() => pending.extend(issue.subissues.iter()),
+                _ => return false,
+            }
+        }
+        true
+    };
+
+    plan.steps()
+        .iter()
+        .filter(|step| match step {
+            Step::Issue(id) => plan
+                .get_issue(id)
+                .is_some_and(|issue| issue.parent_id.is_none() && is_finished_subtree(id)),
+            Step::Milestone(id) => plan.get_milestone(id).is_some_and(|milestone| {
+                !milestone.needed_issues.is_empty()
+                    && milestone
+                        .needed_issues
+                        .iter()
+                        .all(|id| plan.get_issue(id).is_some_and(|issue| issue.is_completed()))
+            }),
+        })
+        .cloned()
+        .collect()
+}
+
+/// Lines of the steps of the plan source, the top-level issues are given with their subissues. The lines are
+/// returned in the file order, with the milestones surrounded by empty lines.
+pub fn steps_lines(source: &PlanSource, steps: &[Step<u64>]) -> io::Result<Vec<String>> {
+    let doc = Document::load(source.placement.as_ref())?;
+    let mut taken: Vec<String> = Vec::new();
+    for (is_milestone, range) in steps_ranges(source, steps) {
+        let range_lines = range.filter_map(|idx| doc.line(idx)).map(str::to_string);
+        if is_milestone {
+            taken.push(String::new());
+            taken.extend(range_lines);
+            taken.push(String::new());
+        } else {
+            taken.extend(range_lines);
+        }
+    }
+    taken.dedup_by(|line, previous| line.is_empty() && previous.is_empty());
+    let start = taken.iter().take_while(|line| line.is_empty()).count();
+    let end = taken.len() - taken.iter().rev().take_while(|line| line.is_empty()).count();
+
+    Ok(taken.get(start..end).unwrap_or_default().to_vec())
+}
+
+/// Removes the lines of the steps from the plan source, the top-level issues are removed with their subissues.
+pub fn remove_steps(source: &PlanSource, steps: &[Step<u64>]) -> io::Result<()> {
+    let path = source.placement.as_ref();
+    let mut doc = Document::load(path)?;
+
+    // The lines are removed from the last ones, so the lines of the preceding steps keep their indices
+    for (_, range) in steps_ranges(source, steps).into_iter().rev() {
+        let is_empty_line = |idx: usize| doc.line(idx).is_some_and(|line| line.trim().is_empty());
+        let end = if is_empty_line(range.end) && (range.start == 0 || is_empty_line(range.start - 1)) {
+            range.end + 1
+        } else {
+            range.end
+        };
+        doc.remove_lines(range.start..end);
+    }
+    doc.save(path)
+}
+
+/// Lines ranges of the steps in the file order, flagged for the milestones.
+fn steps_ranges(PlanSource { plan, lines, .. }: &PlanSource, steps: &[Step<u64>]) -> Vec<(bool, Range<usize>)> {
+    let mut ranges: Vec<(bool, Range<usize>)> = steps
+        .iter()
+        .filter_map(|step| match step {
+            Step::Issue(id) => lines.issue_subtree(plan, id).map(|range| (false, range)),
+            Step::Milestone(id) => lines
+                .get_milestone(id)
+                .map(|ItemLines { lines, .. }| (true, lines.clone())),
+        })
+        .collect();
+    ranges.sort_by_key(|(_, range)| range.start);
+    ranges
+}
+
+/// Appends the lines to the archive file under the `## <heading>` line, the heading usually is the archiving date.
+/// The missing directories of the archive file are created.
+pub fn append_to_archive(path: impl AsRef<Path>, heading: &str, lines: &[String]) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut doc = Document::load(path)?;
+
+    let mut archived = Vec::new();
+    if doc.lines().last().is_some_and(|line| !line.trim().is_empty()) {
+        archived.push(String::new());
+    }
+    archived.push(format!("## {heading}"));
+    archived.push(String::new());
+    archived.extend(lines.iter().cloned());
+
+    doc.insert_lines(doc.lines_count(), archived);
+    doc.save(path)
+}
@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::{DiscoveryRules, IgnoreStack, IGNORE_FILE_NAMES};
+use crate::Placement;
+
+/// Directory tree of the search roots remembered between runs, so that only the directories modified since
+/// the previous run are listed again while searching for projects. The project configs are looked up on every run,
+/// since they depend on the source settings and the files changed in place.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DiscoveryCache {
+    dirs: HashMap<PathBuf, CachedDir>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct CachedDir {
+    modified: SystemTime,
+    subdirs: Vec<PathBuf>,
+    #[serde(default)]
+    ignore_files: Vec<PathBuf>,
+}
+
+/// Directory found in the search roots with the placement of its project config, if any.
+pub type DiscoveredDir = (PathBuf, Option<Placement<PathBuf>>);
+
+impl DiscoveryCache {
+    /// Loads the cache file. Missing or unreadable cache is treated as an empty one.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read(path.as_ref()) {
+            Ok(data) => Ok(serde_json::from_slice(&data).unwrap_or_default()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec(self)?)
+    }
+
+    /// Removes the cache file if it exists.
+    pub fn clear(path: impl AsRef<Path>) -> io::Result<()> {
+        match fs::remove_file(path.as_ref()) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.dirs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty()
+    }
+
+    /// Walks the search roots in the depth-first order, listing again only the directories which modification time
+    /// differs from the cached one. Directories which are not found anymore are dropped from the cache.
+    pub fn refresh(
+        &mut self,
+        search_roots: impl IntoIterator<Item = impl AsRef<Path>>,
+        rules: &DiscoveryRules,
+        get_project_config_placement: impl Fn(&Path) -> Option<Placement<PathBuf>>,
+    ) -> Vec<DiscoveredDir> {
+        let mut walk = Walk {
+            cached: std::mem::take(&mut self.dirs),
+            rules,
+            get_project_config_placement: &get_project_config_placement,
+            visited: HashSet::new(),
+            found: Vec::new(),
+        };
+
+        for root in search_roots {
+            let root = root.as_ref();
+            let real_root = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+            walk.visit(self, root, root, &real_root, 0, &IgnoreStack::default());
+        }
+
+        walk.found
+    }
+}
+
+struct Walk<'a, F> {
+    cached: HashMap<PathBuf, CachedDir>,
+    rules: &'a DiscoveryRules,
+    get_project_config_placement: &'a F,
+
+    /// Canonical paths of the visited directories, the links leading to them again are not followed.
+    visited: HashSet<PathBuf>,
+    found: Vec<DiscoveredDir>,
+}
+
+impl<F> Walk<'_, F>
+where
+    F: Fn(&Path) -> Option<Placement<PathBuf>>,
+{
+    fn visit(
+        &mut self,
+        cache: &mut DiscoveryCache,
+        root: &Path,
+        path: &Path,
+        real_path: &Path,
+        depth: usize,
+        ignore: &IgnoreStack,
+    ) {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return;
+        };
+        if !metadata.is_dir() || cache.dirs.contains_key(path) || !self.visited.insert(real_path.to_path_buf()) {
+            return;
+        }
+        let modified = metadata.modified().ok();
+
+        let dir = match self.cached.remove(path) {
+            Some(dir) if modified == Some(dir.modified) => dir,
+            _ => {
+                let (subdirs, ignore_files) = read_dir(path);
+                CachedDir {
+                    modified: modified.unwrap_or(SystemTime::UNIX_EPOCH),
+                    subdirs,
+                    ignore_files,
+                }
+            },
+        };
+        self.found
+            .push((path.to_path_buf(), (self.get_project_config_placement)(path)));
+
+        let subdirs = dir.subdirs.clone();
+        let ignore = if self.rules.uses_ignore_files() {
+            ignore.with_dir(path, &dir.ignore_files)
+        } else {
+            IgnoreStack::default()
+        };
+        cache.dirs.insert(path.to_path_buf(), dir);
+
+        if self.rules.is_too_deep(depth + 1) {
+            return;
+        }
+
+        for name in subdirs {
+            let subdir = path.join(&name);
+            let relative_path = subdir.strip_prefix(root).unwrap_or(&subdir);
+            if self.rules.is_excluded(&name, relative_path) || ignore.is_ignored(&subdir) {
+                continue;
+            }
+
+            let real_subdir = match std::fs::symlink_metadata(&subdir) {
+                Ok(metadata) if metadata.file_type().is_symlink() => match std::fs::canonicalize(&subdir) {
+                    Ok(target) => target,
+                    Err(_) => continue,
+                },
+                Ok(_) => real_path.join(&name),
+                Err(_) => continue,
+            };
+
+            self.visit(cache, root, &subdir, &real_subdir, depth + 1, &ignore);
+        }
+    }
+}
+
+/// Names of the subdirectories, including the linked ones, and of the ignore files in the sorted order.
+fn read_dir(path: &Path) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return Default::default();
+    };
+
+    let mut subdirs = Vec::new();
+    let mut ignore_files = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Ok(metadata) = std::fs::metadata(entry.path()) else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            subdirs.push(PathBuf::from(name));
+        } else if IGNORE_FILE_NAMES.iter().any(|ignore_file| name == *ignore_file) {
+            ignore_files.push(PathBuf::from(name));
+        }
+    }
+    subdirs.sort();
+    ignore_files.sort();
+
+    (subdirs, ignore_files)
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use todo_lib::plan::Step;
+
+use crate::diagnostic::Diagnostic;
+use crate::tracker::PlanSource;
+
+/// Finds the problems of the project plan, which are not seen in the plan files one by one: the issue ids used
+/// in several files, which are overwritten by merging the plans, and the duplicate issue names.
+pub fn check_plan_sources(sources: &[PlanSource]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut ids: HashMap<u64, (&Path, usize)> = HashMap::new();
+    let mut names: HashMap<&str, (&Path, usize)> = HashMap::new();
+
+    for source in sources {
+        let path = source.placement.as_ref().as_path();
+        for step in source.plan.steps() {
+            let Step::Issue(id) = step else {
+                continue;
+            };
+            let (Some(issue), Some(item)) = (source.plan.get_issue(id), source.lines.get_issue(id)) else {
+                continue;
+            };
+            let line = item.lines.start;
+
+            if let Some((first_path, first_line)) = ids.get(id) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        line,
+                        0,
+                        format!("issue id `{id}` collides with {}", location(first_path, *first_line)),
+                    )
+                    .with_path(path),
+                );
+            } else {
+                ids.insert(*id, (path, line));
+            }
+
+            if let Some((first_path, first_line)) = names.get(issue.name.as_str()) {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        line,
+                        0,
+                        format!(
+                            "duplicate issue name `{}`, first used in {}",
+                            issue.name,
+                            location(first_path, *first_line)
+                        ),
+                    )
+                    .with_path(path),
+                );
+            } else {
+                names.insert(issue.name.as_str(), (path, line));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn location(path: &Path, line: usize) -> String {
+    format!("`{}:{}`", path.display(), line + 1)
+}
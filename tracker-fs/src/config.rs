@@ -3,11 +3,16 @@ use std::path::{Path, PathBuf};
 
 use fs_err as fs;
 use indexmap::{IndexMap, IndexSet};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use todo_lib::id::HashedId;
-use walkdir::WalkDir;
+use toml_edit::{DocumentMut, Item, Table};
 
+use crate::cache::{DiscoveredDir, DiscoveryCache};
+use crate::dialect::ListDialect;
+use crate::discovery::DiscoveryRules;
+use crate::document::{CodeBlock, Document};
 use crate::Placement;
 
 #[derive(Debug, Error)]
@@ -17,6 +22,12 @@ pub enum LoadConfigError {
 
     #[error("fail to deserialize: {0}")]
     FailToDeserialize(#[from] toml::de::Error),
+
+    #[error("fail to deserialize: {0}")]
+    FailToDeserializeYaml(#[from] serde_yaml::Error),
+
+    #[error("fail to deserialize: {0}")]
+    FailToDeserializeJson(#[from] serde_json::Error),
 }
 
 #[derive(Debug, Error)]
@@ -26,37 +37,199 @@ pub enum SaveConfigError {
 
     #[error("fail to serialize: {0}")]
     FailToSerialize(#[from] toml::ser::Error),
+
+    #[error("fail to serialize: {0}")]
+    FailToSerializeYaml(#[from] serde_yaml::Error),
+
+    #[error("fail to serialize: {0}")]
+    FailToSerializeJson(#[from] serde_json::Error),
+}
+
+/// Format of the project config file or of the config block of the manifest.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigFormat {
+    #[default]
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    pub const ALL: [Self; 3] = [Self::Toml, Self::Yaml, Self::Json];
+
+    /// Detects the format by the file extension, `None` is returned for the unknown extensions.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()?.to_lowercase().as_str() {
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::Toml => &["toml"],
+            Self::Yaml => &["yaml", "yml"],
+            Self::Json => &["json"],
+        }
+    }
+
+    /// Opening fence of the config block of the manifest.
+    pub fn block_start(&self) -> &'static str {
+        match self {
+            Self::Toml => "```toml project",
+            Self::Yaml => "```yaml project",
+            Self::Json => "```json project",
+        }
+    }
 }
 
-pub trait SerializedId: Serialize + HashedId {}
-impl<T> SerializedId for T where T: Serialize + HashedId {}
+pub trait SerializedId: Serialize + HashedId + Clone {}
+impl<T> SerializedId for T where T: Serialize + HashedId + Clone {}
 
 pub trait DeserializedId: for<'a> Deserialize<'a> + HashedId {}
 impl<T> DeserializedId for T where T: for<'a> Deserialize<'a> + HashedId {}
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FsProjectConfig<ID: HashedId = String> {
     pub id: ID,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub desc: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub root_dir: Option<PathBuf>,
 
     #[serde(default = "Default::default")]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub start_id: Option<u64>,
 
     #[serde(default = "Default::default")]
     #[serde(skip_serializing_if = "IndexSet::is_empty")]
     pub subprojects: IndexSet<ID>,
+
+    /// Defaults of the subprojects.
+    #[serde(default = "Default::default")]
+    #[serde(skip_serializing_if = "ProjectDefaults::is_empty")]
+    pub defaults: ProjectDefaults,
+
+    /// Overrides of the global source settings for this project.
+    #[serde(default = "Default::default")]
+    #[serde(skip_serializing_if = "SourceOverrides::is_empty")]
+    pub source: SourceOverrides,
+
+    /// Overrides of the global display settings for this project. The keys are interpreted by the application.
+    #[serde(default = "Default::default")]
+    #[serde(skip_serializing_if = "toml::Table::is_empty")]
+    pub display: toml::Table,
+
+    /// Origins of the values inherited from the defaults of the ancestor projects or of the workspace, by the dotted
+    /// paths of the values, like `tags` or `display.max_steps`.
+    #[serde(skip, default = "IndexMap::new")]
+    pub inherited: IndexMap<String, ValueOrigin<ID>>,
+}
+
+/// Config values declared for the subprojects, which are used unless a subproject sets its own ones.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectDefaults {
+    #[serde(default = "Default::default")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_id: Option<u64>,
+
+    /// Display settings, merged key by key into the `[display]` table of the subprojects.
+    #[serde(default = "Default::default")]
+    #[serde(skip_serializing_if = "toml::Table::is_empty")]
+    pub display: toml::Table,
+}
+
+impl ProjectDefaults {
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            tags,
+            start_id,
+            display,
+        } = self;
+        tags.is_empty() && start_id.is_none() && display.is_empty()
+    }
+}
+
+/// Source settings of the project, the unset ones are taken from the global config.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceOverrides {
+    #[serde(with = "serde_regex", skip_serializing_if = "Option::is_none")]
+    pub manifest_filename_regex: Option<Regex>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_filename_example: Option<String>,
+
+    #[serde(with = "serde_regex", skip_serializing_if = "Option::is_none")]
+    pub issues_filename_regex: Option<Regex>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issues_filename_example: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list: Option<ListDialect>,
+
+    /// Glob patterns of the issues files relative to the project root, like `docs/TODO.md` or `todo/*.md`.
+    /// The issues files are found by `issues_filename_regex` in the project root only if no patterns are given.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub issues_files: Vec<String>,
+}
+
+impl SourceOverrides {
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            manifest_filename_regex,
+            manifest_filename_example,
+            issues_filename_regex,
+            issues_filename_example,
+            list,
+            issues_files,
+        } = self;
+        manifest_filename_regex.is_none()
+            && manifest_filename_example.is_none()
+            && issues_filename_regex.is_none()
+            && issues_filename_example.is_none()
+            && list.is_none()
+            && issues_files.is_empty()
+    }
+
+    pub fn manifest_filename_regex<'a>(&'a self, default: &'a Regex) -> &'a Regex {
+        self.manifest_filename_regex.as_ref().unwrap_or(default)
+    }
+
+    pub fn issues_filename_regex<'a>(&'a self, default: &'a Regex) -> &'a Regex {
+        self.issues_filename_regex.as_ref().unwrap_or(default)
+    }
+
+    pub fn list<'a>(&'a self, default: &'a ListDialect) -> &'a ListDialect {
+        self.list.as_ref().unwrap_or(default)
+    }
+}
+
+/// Origin of the inherited config value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueOrigin<ID> {
+    /// Defaults of the ancestor project with the given id.
+    Project(ID),
+
+    /// Defaults of the workspace config.
+    Workspace,
 }
 
 impl<ID: HashedId> FsProjectConfig<ID> {
-    const MD_BLOCK_START: &'static str = "```toml project";
     const MD_BLOCK_END: &'static str = "```";
 
     pub fn new(id: ID) -> Self {
@@ -68,6 +241,10 @@ impl<ID: HashedId> FsProjectConfig<ID> {
             tags: Default::default(),
             start_id: None,
             subprojects: Default::default(),
+            defaults: Default::default(),
+            source: Default::default(),
+            display: Default::default(),
+            inherited: Default::default(),
         }
     }
 
@@ -82,34 +259,121 @@ impl<ID: HashedId> FsProjectConfig<ID> {
     }
 }
 
+impl<ID: HashedId + Clone> FsProjectConfig<ID> {
+    /// Fills the values which are not set in the config by the defaults, remembering the origin of the values.
+    pub fn inherit(&mut self, defaults: &ProjectDefaults, origin: &ValueOrigin<ID>) {
+        if self.tags.is_empty() && !defaults.tags.is_empty() {
+            self.tags = defaults.tags.clone();
+            self.inherited.insert("tags".into(), origin.clone());
+        }
+        if self.start_id.is_none() && defaults.start_id.is_some() {
+            self.start_id = defaults.start_id;
+            self.inherited.insert("start_id".into(), origin.clone());
+        }
+        inherit_table(
+            &mut self.display,
+            &defaults.display,
+            "display",
+            &mut self.inherited,
+            origin,
+        );
+    }
+
+    /// Origin of the value by its dotted path, the value of the inherited table is inherited too.
+    pub fn inherited_origin(&self, path: &str) -> Option<&ValueOrigin<ID>> {
+        let mut path = path;
+        loop {
+            if let Some(origin) = self.inherited.get(path) {
+                return Some(origin);
+            }
+            path = path.rsplit_once('.')?.0;
+        }
+    }
+
+    /// The config without the inherited values, as it is stored in the project.
+    pub fn without_inherited(&self) -> Self {
+        let mut config = self.clone();
+        for path in self.inherited.keys() {
+            match path.split_once('.') {
+                Some(("display", key_path)) => remove_table_value(&mut config.display, key_path),
+                _ if path == "tags" => config.tags.clear(),
+                _ if path == "start_id" => config.start_id = None,
+                _ => {},
+            }
+        }
+        config.inherited.clear();
+        config
+    }
+}
+
+/// Inserts the default values missing in the table, descending into the subtables present in both.
+fn inherit_table<ID: Clone>(
+    table: &mut toml::Table,
+    defaults: &toml::Table,
+    path: &str,
+    inherited: &mut IndexMap<String, ValueOrigin<ID>>,
+    origin: &ValueOrigin<ID>,
+) {
+    for (key, value) in defaults {
+        let key_path = format!("{path}.{key}");
+        match (table.get_mut(key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(defaults)) => {
+                inherit_table(table, defaults, &key_path, inherited, origin)
+            },
+            (Some(_), _) => {},
+            (None, _) => {
+                table.insert(key.clone(), value.clone());
+                inherited.insert(key_path, origin.clone());
+            },
+        }
+    }
+}
+
+fn remove_table_value(table: &mut toml::Table, key_path: &str) {
+    match key_path.split_once('.') {
+        Some((key, key_path)) => {
+            if let Some(toml::Value::Table(table)) = table.get_mut(key) {
+                remove_table_value(table, key_path);
+            }
+        },
+        // Rebuilt instead of removing the key, so the order of the own values is kept
+        None => {
+            *table = std::mem::take(table)
+                .into_iter()
+                .filter(|(key, _)| key != key_path)
+                .collect()
+        },
+    }
+}
+
 impl<ID: DeserializedId> FsProjectConfig<ID> {
     pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
         toml::from_str(content)
     }
 
+    pub fn from_str(content: &str, format: ConfigFormat) -> Result<Self, LoadConfigError> {
+        match format {
+            ConfigFormat::Toml => Ok(Self::from_toml(content)?),
+            ConfigFormat::Yaml => Ok(serde_yaml::from_str(content)?),
+            ConfigFormat::Json => Ok(serde_json::from_str(content)?),
+        }
+    }
+
+    /// Loads the config in the format given by the file extension or by the tag of the first config block
+    /// of the manifest, like ```` ```yaml project ````. The file with an unknown extension is read as TOML.
     pub fn load(source: &Placement<impl AsRef<Path>>) -> Result<Self, LoadConfigError> {
         match source {
             Placement::WholeFile(path) => {
                 let content = fs::read_to_string(path.as_ref())?;
-                Ok(Self::from_toml(&content)?)
+                let format = ConfigFormat::from_path(path.as_ref()).unwrap_or_default();
+                Self::from_str(&content, format)
             },
             Placement::CodeBlockInFile(path) => {
-                let mut content = String::new();
-                let file_content = fs::read_to_string(path.as_ref())?;
-
-                let mut in_block = false;
-                for line in file_content.lines() {
-                    if line.trim().starts_with('`') && line.trim().to_lowercase() == Self::MD_BLOCK_START {
-                        in_block = true;
-                    } else if line.trim().starts_with('`') && line.trim().to_lowercase() == Self::MD_BLOCK_END {
-                        in_block = false;
-                    } else if in_block {
-                        content.push_str(line);
-                        content.push('\n');
-                    }
+                let doc = Document::parse(fs::read_to_string(path.as_ref())?);
+                match find_config_block(&doc) {
+                    Some((block, format)) => Self::from_str(block_text(&doc, &block).trim_end(), format),
+                    None => Self::from_toml("").map_err(Into::into),
                 }
-
-                Self::from_toml(content.trim_end()).map_err(Into::into)
             },
         }
     }
@@ -120,54 +384,141 @@ impl<ID: SerializedId> FsProjectConfig<ID> {
         toml::to_string(self)
     }
 
+    pub fn to_string(&self, format: ConfigFormat) -> Result<String, SaveConfigError> {
+        match format {
+            ConfigFormat::Toml => Ok(self.to_toml()?),
+            ConfigFormat::Yaml => Ok(serde_yaml::to_string(self)?),
+            ConfigFormat::Json => {
+                let mut json = serde_json::to_string_pretty(self)?;
+                json.push('\n');
+                Ok(json)
+            },
+        }
+    }
+
+    /// Saves the config in the format of the destination, see `load`. For TOML only the changed values of
+    /// the existing config are updated, so the comments and the formatting are preserved. The text around
+    /// the config block of the manifest is preserved in any format, the new block is written in TOML.
     pub fn save(&self, destination: Placement<impl AsRef<Path>>) -> Result<(), SaveConfigError> {
+        if !self.inherited.is_empty() {
+            return self.without_inherited().save(destination);
+        }
+
         match destination {
             Placement::WholeFile(path) => {
-                let content = self.to_toml()?;
-                fs::write(path, content).map_err(Into::into)
+                let format = ConfigFormat::from_path(path.as_ref()).unwrap_or_default();
+                let config = self.to_string(format)?;
+                let content = match fs::read_to_string(path.as_ref()) {
+                    Ok(old_config) => merge_config(&old_config, &config, format),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => config,
+                    Err(err) => return Err(err.into()),
+                };
+                fs::write(path.as_ref(), content).map_err(Into::into)
             },
             Placement::CodeBlockInFile(path) => {
-                let content = format!(
-                    "{prefix}\n{config}\n{suffix}\n",
-                    prefix = Self::MD_BLOCK_START,
-                    config = self.to_toml()?.trim_end(),
-                    suffix = Self::MD_BLOCK_END
-                );
-
-                if path.as_ref().exists() {
-                    let file_content = fs::read_to_string(path.as_ref())?;
-
-                    let mut new_content = String::new();
-                    let mut inserted = false;
-                    let mut in_block = false;
-                    for line in file_content.lines() {
-                        if line.trim().starts_with('`') && line.trim().to_lowercase() == Self::MD_BLOCK_START {
-                            in_block = true;
-                            new_content.push_str(&content);
-                            inserted = true;
-                        }
-                        if !in_block {
-                            new_content.push_str(line);
-                            new_content.push('\n');
-                        }
-                        if line.trim().starts_with('`') && line.trim().to_lowercase() == Self::MD_BLOCK_END {
-                            in_block = false;
-                        }
-                    }
-                    if !inserted {
-                        new_content.push_str(&content);
-                    }
-
-                    fs::write(path, new_content).map_err(Into::into)
+                let path = path.as_ref();
+                let mut doc = if path.exists() {
+                    Document::load(path)?
                 } else {
                     let project_name = self.name.as_deref().unwrap_or("");
-                    fs::write(path, format!("# {project_name}\n\n{content}")).map_err(Into::into)
+                    Document::parse(format!("# {project_name}\n\n"))
+                };
+
+                if let Some((block, format)) = find_config_block(&doc) {
+                    let content = merge_config(&block_text(&doc, &block), &self.to_string(format)?, format);
+                    doc.replace_lines(block.content.clone(), content.lines());
+                } else {
+                    let format = ConfigFormat::default();
+                    let config = self.to_string(format)?;
+                    let lines = std::iter::once(format.block_start())
+                        .chain(config.trim_end().lines())
+                        .chain(std::iter::once(Self::MD_BLOCK_END));
+                    doc.insert_lines(doc.lines_count(), lines);
+                }
+                doc.save(path).map_err(Into::into)
+            },
+        }
+    }
+}
+
+/// The first config block of the manifest in any format.
+fn find_config_block(doc: &Document) -> Option<(CodeBlock, ConfigFormat)> {
+    ConfigFormat::ALL
+        .into_iter()
+        .filter_map(|format| {
+            let block = doc.code_blocks(format.block_start()).into_iter().next()?;
+            Some((block, format))
+        })
+        .min_by_key(|(block, _)| block.start)
+}
+
+fn block_text(doc: &Document, block: &CodeBlock) -> String {
+    block
+        .content
+        .clone()
+        .filter_map(|idx| doc.line(idx))
+        .flat_map(|line| [line, "\n"])
+        .collect()
+}
+
+fn merge_config(old: &str, new: &str, format: ConfigFormat) -> String {
+    match format {
+        ConfigFormat::Toml => merge_toml(old, new),
+        ConfigFormat::Yaml | ConfigFormat::Json => new.to_string(),
+    }
+}
+
+/// Updates the old TOML document with the values of the new one, keeping the comments and formatting of the values
+/// which are not changed. Unparsable old document is replaced entirely.
+fn merge_toml(old: &str, new: &str) -> String {
+    let (Ok(mut old_doc), Ok(new_doc)) = (old.parse::<DocumentMut>(), new.parse::<DocumentMut>()) else {
+        return new.to_string();
+    };
+
+    merge_table(old_doc.as_table_mut(), new_doc.as_table());
+    old_doc.to_string()
+}
+
+fn merge_table(old: &mut Table, new: &Table) {
+    let removed: Vec<_> = old
+        .iter()
+        .filter(|(key, _)| !new.contains_key(key))
+        .map(|(key, _)| key.to_string())
+        .collect();
+    for key in removed {
+        old.remove(&key);
+    }
+
+    for (key, new_item) in new.iter() {
+        match (old.get_mut(key), new_item) {
+            (Some(Item::Table(old_table)), Item::Table(new_table)) => merge_table(old_table, new_table),
+            (Some(old_item), _) if same_value(old_item, new_item) => {},
+            (Some(old_item), _) => {
+                let decor = old_item.as_value().map(|value| value.decor().clone());
+                *old_item = new_item.clone();
+                if let (Some(decor), Some(value)) = (decor, old_item.as_value_mut()) {
+                    *value.decor_mut() = decor;
                 }
             },
+            (None, _) => {
+                old.insert(key, new_item.clone());
+            },
         }
     }
 }
 
+fn same_value(old: &Item, new: &Item) -> bool {
+    let strip = |item: &Item| {
+        let mut item = item.clone();
+        if let Some(value) = item.as_value_mut() {
+            value.decor_mut().clear();
+        }
+        item.to_string()
+    };
+    strip(old) == strip(new)
+}
+
+/// Loads the project of the directory, which inherits the defaults of the projects in the ancestor directories.
 pub fn find_project<ID>(
     project_root: impl AsRef<Path>,
     get_project_config_placement: impl Fn(&Path) -> Option<Placement<PathBuf>>,
@@ -175,72 +526,163 @@ pub fn find_project<ID>(
 where
     ID: DeserializedId + Ord + Clone,
 {
-    if let Some(project_config_placement) = get_project_config_placement(project_root.as_ref()) {
-        let root_dir = project_config_placement.as_ref().parent();
-        if let Ok(mut project_config) = FsProjectConfig::<ID>::load(&project_config_placement) {
-            if project_config.root_dir.is_none() {
-                project_config.root_dir = root_dir.map(ToOwned::to_owned);
-            }
+    let mut project_config = get_project_config_placement(project_root.as_ref())
+        .and_then(|placement| load_found_project::<ID>(&placement))?;
+    let ancestors = ancestor_defaults(project_root.as_ref(), &get_project_config_placement);
+    inherit_defaults(&mut project_config, &ancestors);
 
-            return Some(project_config);
-        }
+    Some(project_config)
+}
+
+/// Defaults declared by the projects of the ancestor directories, the nearest one goes first.
+fn ancestor_defaults<ID>(
+    project_root: &Path,
+    get_project_config_placement: impl Fn(&Path) -> Option<Placement<PathBuf>>,
+) -> Vec<(ID, ProjectDefaults)>
+where
+    ID: DeserializedId + Ord + Clone,
+{
+    let Ok(project_root) = std::fs::canonicalize(project_root) else {
+        return Vec::new();
+    };
+
+    project_root
+        .ancestors()
+        .skip(1)
+        .filter_map(get_project_config_placement)
+        .filter_map(|placement| FsProjectConfig::<ID>::load(&placement).ok())
+        .filter(|project_config| !project_config.defaults.is_empty())
+        .map(|project_config| (project_config.id, project_config.defaults))
+        .collect()
+}
+
+fn inherit_defaults<ID: HashedId + Clone>(
+    project_config: &mut FsProjectConfig<ID>,
+    ancestors: &[(ID, ProjectDefaults)],
+) {
+    for (id, defaults) in ancestors {
+        project_config.inherit(defaults, &ValueOrigin::Project(id.clone()));
+    }
+}
+
+fn load_found_project<ID>(project_config_placement: &Placement<PathBuf>) -> Option<FsProjectConfig<ID>>
+where
+    ID: DeserializedId + Ord + Clone,
+{
+    let root_dir = project_config_placement.as_ref().parent();
+    let mut project_config = FsProjectConfig::<ID>::load(project_config_placement).ok()?;
+    if project_config.root_dir.is_none() {
+        project_config.root_dir = root_dir.map(ToOwned::to_owned);
     }
 
-    None
+    Some(project_config)
 }
 
 #[derive(Debug)]
 struct Parent<ID> {
     id: ID,
     root_dir: PathBuf,
+
+    /// Defaults inherited by the subprojects, the nearest project goes first.
+    defaults: Vec<(ID, ProjectDefaults)>,
 }
 
 pub fn find_projects<ID>(
     search_roots: impl IntoIterator<Item = impl AsRef<Path>>,
+    rules: &DiscoveryRules,
+    get_project_config_placement: impl Fn(&Path) -> Option<Placement<PathBuf>>,
+) -> IndexMap<ID, FsProjectConfig<ID>>
+where
+    ID: DeserializedId + Ord + Clone,
+{
+    find_projects_cached(
+        search_roots,
+        &mut DiscoveryCache::default(),
+        rules,
+        get_project_config_placement,
+    )
+}
+
+/// Same as `find_projects`, but reads only the directories changed since the cache was refreshed last time.
+pub fn find_projects_cached<ID>(
+    search_roots: impl IntoIterator<Item = impl AsRef<Path>>,
+    cache: &mut DiscoveryCache,
+    rules: &DiscoveryRules,
     get_project_config_placement: impl Fn(&Path) -> Option<Placement<PathBuf>>,
 ) -> IndexMap<ID, FsProjectConfig<ID>>
+where
+    ID: DeserializedId + Ord + Clone,
+{
+    collect_projects(cache.refresh(search_roots, rules, get_project_config_placement))
+}
+
+/// Configs of the projects under the search roots, which fail to load. Such projects are skipped by `find_projects`.
+pub fn find_broken_project_configs<ID>(
+    search_roots: impl IntoIterator<Item = impl AsRef<Path>>,
+    rules: &DiscoveryRules,
+    get_project_config_placement: impl Fn(&Path) -> Option<Placement<PathBuf>>,
+) -> Vec<(Placement<PathBuf>, LoadConfigError)>
+where
+    ID: DeserializedId,
+{
+    DiscoveryCache::default()
+        .refresh(search_roots, rules, get_project_config_placement)
+        .into_iter()
+        .filter_map(|(_, placement)| placement)
+        .filter_map(|placement| {
+            let err = FsProjectConfig::<ID>::load(&placement).err()?;
+            Some((placement, err))
+        })
+        .collect()
+}
+
+/// Loads the projects of the directories given in the depth-first order, links them with their parents and applies
+/// the defaults of the parent projects.
+fn collect_projects<ID>(dirs: impl IntoIterator<Item = DiscoveredDir>) -> IndexMap<ID, FsProjectConfig<ID>>
 where
     ID: DeserializedId + Ord + Clone,
 {
     let mut projects: IndexMap<ID, FsProjectConfig<ID>> = IndexMap::new();
     let mut parents: Vec<Parent<ID>> = Vec::new();
 
-    for root in search_roots {
-        for entry in WalkDir::new(root)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|entry| entry.ok().and_then(|entry| entry.file_type().is_dir().then_some(entry)))
-        {
-            let path = entry.path();
-
-            let mut parent = loop {
-                if let Some(parent) = parents.pop() {
-                    if path.starts_with(&parent.root_dir) {
-                        break Some(parent);
-                    }
-                } else {
-                    break None;
+    for (path, project_config_placement) in dirs {
+        let mut parent = loop {
+            if let Some(parent) = parents.pop() {
+                if path.starts_with(&parent.root_dir) {
+                    break Some(parent);
                 }
-            };
-
-            if let Some(project_config) = find_project::<ID>(path, &get_project_config_placement) {
-                if let Some(parent) = parent {
-                    if let Some(parent_project_config) = projects.get_mut(&parent.id) {
-                        parent_project_config.subprojects.insert(project_config.id.clone());
-                    }
-                    parents.push(parent);
-                }
-
-                parent = Some(Parent {
-                    id: project_config.id.clone(),
-                    root_dir: project_config.root_dir.clone().unwrap_or_default(),
-                });
-                projects.insert(project_config.id.clone(), project_config);
+            } else {
+                break None;
+            }
+        };
+
+        if let Some(mut project_config) = project_config_placement.as_ref().and_then(load_found_project::<ID>) {
+            let mut defaults = parent
+                .as_ref()
+                .map(|parent| parent.defaults.clone())
+                .unwrap_or_default();
+            inherit_defaults(&mut project_config, &defaults);
+            if !project_config.defaults.is_empty() {
+                defaults.insert(0, (project_config.id.clone(), project_config.defaults.clone()));
             }
 
             if let Some(parent) = parent {
+                if let Some(parent_project_config) = projects.get_mut(&parent.id) {
+                    parent_project_config.subprojects.insert(project_config.id.clone());
+                }
                 parents.push(parent);
             }
+
+            parent = Some(Parent {
+                id: project_config.id.clone(),
+                root_dir: project_config.root_dir.clone().unwrap_or_default(),
+                defaults,
+            });
+            projects.insert(project_config.id.clone(), project_config);
+        }
+
+        if let Some(parent) = parent {
+            parents.push(parent);
         }
     }
 
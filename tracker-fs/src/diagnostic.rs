@@ -0,0 +1,86 @@
+use std::path::{Path, PathBuf};
+use std::{fmt, io};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => f.write_str("error"),
+            Self::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// Problem found in the plan file, the plan is loaded anyway by skipping or fixing the problem line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// File of the plan, unknown for the plans loaded from the lines.
+    pub path: Option<PathBuf>,
+
+    /// Zero-based line index in the file.
+    pub line: usize,
+
+    /// Zero-based column in the line, the tabs are expanded to the tab stops.
+    pub column: usize,
+
+    pub severity: Severity,
+
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            path: None,
+            line,
+            column,
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(line, column, message)
+        }
+    }
+
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    /// Formats the diagnostic as `path:line:column: severity: message` with one-based line and column.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            path,
+            line,
+            column,
+            severity,
+            message,
+        } = self;
+
+        if let Some(path) = path {
+            write!(f, "{}:", path.display())?;
+        }
+        write!(f, "{}:{}: {severity}: {message}", line + 1, column + 1)
+    }
+}
+
+impl From<Diagnostic> for io::Error {
+    fn from(diagnostic: Diagnostic) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, diagnostic.to_string())
+    }
+}
@@ -0,0 +1,190 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+/// Columns between the tab stops, as defined by CommonMark.
+pub const TAB_STOP: usize = 4;
+
+/// Markdown list syntax of the plan files.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ListDialect {
+    /// Bullet characters of the list items, the first one is used for the written issues.
+    pub bullets: Vec<char>,
+
+    /// Recognize the ordered list items, like `1.` or `1)`. The written issues are numbered if there are no bullets.
+    pub ordered: bool,
+
+    /// Number of columns of one nesting level of the written issues.
+    pub indent: usize,
+
+    /// Indent the written issues by tabs instead of spaces.
+    pub tabs: bool,
+}
+
+impl Default for ListDialect {
+    fn default() -> Self {
+        Self {
+            bullets: vec!['-', '+'],
+            ordered: false,
+            indent: 2,
+            tabs: false,
+        }
+    }
+}
+
+/// List item marker found at the start of the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListMarker {
+    /// Column of the marker, the tabs are expanded to the tab stops.
+    pub column: usize,
+
+    /// Byte range of the marker, i.e. the bullet or the number with its delimiter.
+    pub marker: Range<usize>,
+
+    /// Byte index of the item content.
+    pub content_start: usize,
+
+    /// Column of the item content, which the nested lines are aligned to.
+    pub content_column: usize,
+}
+
+impl ListDialect {
+    /// Finds the list item marker by the CommonMark rules: the marker is followed by at least one space, and up
+    /// to four spaces make the content column.
+    pub fn list_marker(&self, line: &str) -> Option<ListMarker> {
+        let (indent_len, column) = leading_whitespace(line);
+        let rest = &line[indent_len..];
+
+        let marker_len = if rest.starts_with(|ch| self.bullets.contains(&ch)) {
+            rest.chars().next().map(char::len_utf8)?
+        } else if self.ordered {
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            match rest.as_bytes().get(digits) {
+                Some(b'.' | b')') if (1..=9).contains(&digits) => digits + 1,
+                _ => return None,
+            }
+        } else {
+            return None;
+        };
+
+        let marker = indent_len..indent_len + marker_len;
+        let marker_end_column = column + marker_len;
+        let after_marker = &line[marker.end..];
+        if !after_marker.starts_with([' ', '\t']) {
+            return None;
+        }
+
+        let (spaces_len, spaces_end_column) = whitespace_from(after_marker, marker_end_column);
+        let (content_start, content_column) =
+            if spaces_end_column - marker_end_column > TAB_STOP || spaces_len == after_marker.len() {
+                // The indented code or empty item: the content starts after one space
+                (marker.end + 1, marker_end_column + 1)
+            } else {
+                (marker.end + spaces_len, spaces_end_column)
+            };
+
+        Some(ListMarker {
+            column,
+            marker,
+            content_start,
+            content_column,
+        })
+    }
+
+    /// Marker of the written issues.
+    pub fn marker(&self) -> String {
+        match self.bullets.first() {
+            Some(bullet) => format!("{bullet} "),
+            None if self.ordered => "1. ".into(),
+            None => "- ".into(),
+        }
+    }
+
+    /// Indentation of one nesting level, which is at least as wide as the issue marker.
+    pub fn indent_unit(&self) -> String {
+        if self.tabs {
+            "\t".into()
+        } else {
+            " ".repeat(self.indent.max(self.marker().len()))
+        }
+    }
+
+    /// Width of the indentation of one nesting level in columns.
+    pub fn indent_width(&self) -> usize {
+        if self.tabs {
+            TAB_STOP
+        } else {
+            self.indent.max(self.marker().len())
+        }
+    }
+}
+
+/// Length of the leading whitespace in bytes and the column it ends.
+pub fn leading_whitespace(line: &str) -> (usize, usize) {
+    whitespace_from(line, 0)
+}
+
+/// Strips the leading whitespace up to the column.
+pub fn strip_indent(line: &str, column: usize) -> &str {
+    let mut current = 0;
+    let mut len = 0;
+    for ch in line.chars() {
+        let next = match ch {
+            ' ' => current + 1,
+            '\t' => current + TAB_STOP - current % TAB_STOP,
+            _ => break,
+        };
+        if next > column {
+            break;
+        }
+        current = next;
+        len += 1;
+    }
+    &line[len..]
+}
+
+fn whitespace_from(text: &str, start_column: usize) -> (usize, usize) {
+    let mut column = start_column;
+    let mut len = 0;
+    for ch in text.chars() {
+        match ch {
+            ' ' => column += 1,
+            '\t' => column += TAB_STOP - column % TAB_STOP,
+            _ => break,
+        }
+        len += 1;
+    }
+    (len, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_list_markers() {
+        let dialect = ListDialect {
+            bullets: vec!['*', '-'],
+            ordered: true,
+            ..Default::default()
+        };
+
+        let marker = dialect.list_marker("* task").unwrap();
+        assert_eq!((marker.column, marker.marker, marker.content_column), (0, 0..1, 2));
+
+        let marker = dialect.list_marker("  12. task").unwrap();
+        assert_eq!((marker.column, marker.marker, marker.content_column), (2, 2..5, 6));
+
+        let marker = dialect.list_marker("\t-   task").unwrap();
+        assert_eq!((marker.column, marker.content_start, marker.content_column), (4, 5, 8));
+
+        let marker = dialect.list_marker("-      code").unwrap();
+        assert_eq!((marker.content_start, marker.content_column), (2, 2));
+
+        assert_eq!(dialect.list_marker("+ task"), None);
+        assert_eq!(dialect.list_marker("-task"), None);
+        assert_eq!(dialect.list_marker("1234567890. task"), None);
+        assert_eq!(ListDialect::default().list_marker("1. task"), None);
+    }
+}
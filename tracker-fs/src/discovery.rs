@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+pub use globset::Error as GlobError;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+
+/// Files with the gitignore-like patterns of the directories skipped while searching for projects.
+pub const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".todoignore"];
+
+/// Rules limiting the directories visited while searching for projects.
+#[derive(Debug, Clone)]
+pub struct DiscoveryRules {
+    max_depth: Option<usize>,
+    exclude: GlobSet,
+    use_ignore_files: bool,
+}
+
+impl Default for DiscoveryRules {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            exclude: GlobSet::empty(),
+            use_ignore_files: false,
+        }
+    }
+}
+
+impl DiscoveryRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Directories deeper than `max_depth` below the search root are not visited, the root itself has depth 0.
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Glob patterns matched against the directory name and its path relative to the search root.
+    pub fn with_exclude(mut self, patterns: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Self, GlobError> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern.as_ref())?);
+        }
+        self.exclude = builder.build()?;
+        Ok(self)
+    }
+
+    /// Honour the `.gitignore` and `.todoignore` files of the visited directories.
+    pub fn with_ignore_files(mut self, use_ignore_files: bool) -> Self {
+        self.use_ignore_files = use_ignore_files;
+        self
+    }
+
+    pub fn is_too_deep(&self, depth: usize) -> bool {
+        self.max_depth.is_some_and(|max_depth| depth > max_depth)
+    }
+
+    pub fn is_excluded(&self, name: &Path, relative_path: &Path) -> bool {
+        self.exclude.is_match(name) || self.exclude.is_match(relative_path)
+    }
+
+    pub fn uses_ignore_files(&self) -> bool {
+        self.use_ignore_files
+    }
+}
+
+/// Ignore patterns collected from the ignore files of the directory and its ancestors.
+#[derive(Debug, Default, Clone)]
+pub struct IgnoreStack {
+    matchers: Vec<Gitignore>,
+}
+
+impl IgnoreStack {
+    /// Adds the patterns of the ignore files placed in the directory.
+    pub fn with_dir(&self, dir: &Path, ignore_files: &[PathBuf]) -> Self {
+        let mut stack = self.clone();
+        if ignore_files.is_empty() {
+            return stack;
+        }
+
+        let mut builder = GitignoreBuilder::new(dir);
+        for file_name in ignore_files {
+            // Broken patterns are skipped the same way as git does
+            builder.add(dir.join(file_name));
+        }
+        if let Ok(matcher) = builder.build() {
+            stack.matchers.push(matcher);
+        }
+        stack
+    }
+
+    /// Checks the directory against the patterns, the deepest ignore file wins.
+    pub fn is_ignored(&self, dir: &Path) -> bool {
+        for matcher in self.matchers.iter().rev() {
+            match matcher.matched(dir, true) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {},
+            }
+        }
+        false
+    }
+}
@@ -0,0 +1,394 @@
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+use fs_err as fs;
+
+use crate::dialect::ListDialect;
+use crate::issue::{MD_BLOCK_END, MD_BLOCK_START};
+use crate::plan::parse::{LineKind, LineParser};
+use crate::Placement;
+
+/// Lossless model of a Markdown file: the source text is kept as is together with the spans of its lines,
+/// so the edits touch only the replaced lines and the rest of the bytes are written back unchanged.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Document {
+    text: String,
+    lines: Vec<Range<usize>>,
+}
+
+/// Parsed plan item of the document with its position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocItem {
+    pub kind: LineKind,
+
+    /// Nesting level of the item.
+    pub level: usize,
+
+    /// Zero-based index of the item line.
+    pub line: usize,
+
+    /// Byte range of the item in the document text, without the line terminator.
+    pub span: Range<usize>,
+}
+
+/// Fenced code block of the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// Index of the opening fence line.
+    pub start: usize,
+
+    /// Rest of the opening fence after the start mark, empty for the unnamed block.
+    pub name: String,
+
+    /// Indices of the lines inside the block.
+    pub content: Range<usize>,
+
+    /// Index of the closing fence line, `None` for the block which is not closed until the end of the document.
+    pub end: Option<usize>,
+}
+
+impl Document {
+    pub fn parse(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for line in text.split_inclusive('\n') {
+            lines.push(start..start + line.len());
+            start += line.len();
+        }
+
+        Self { text, lines }
+    }
+
+    /// Reads the document from the file, a missing file is read as an empty document.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path.as_ref()) {
+            Ok(text) => Ok(Self::parse(text)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path.as_ref(), &self.text)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn lines_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// The line without its terminator.
+    pub fn line(&self, idx: usize) -> Option<&str> {
+        self.line_span(idx).map(|span| &self.text[span])
+    }
+
+    /// Byte range of the line without its terminator.
+    pub fn line_span(&self, idx: usize) -> Option<Range<usize>> {
+        let span = self.lines.get(idx)?;
+        let line = &self.text[span.clone()];
+        let content_len = line
+            .strip_suffix('\n')
+            .map_or(line.len(), |line| line.strip_suffix('\r').map_or(line.len(), str::len));
+
+        Some(span.start..span.start + content_len)
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        (0..self.lines.len()).filter_map(|idx| self.line(idx))
+    }
+
+    /// Line terminator used by the document, the first one found wins.
+    pub fn newline(&self) -> &'static str {
+        match self.lines.first().map(|span| &self.text[span.clone()]) {
+            Some(line) if line.ends_with("\r\n") => "\r\n",
+            _ => "\n",
+        }
+    }
+
+    /// Code blocks opened by the `start_mark` fence, e.g. ```` ```md todo ````.
+    pub fn code_blocks(&self, start_mark: &str) -> Vec<CodeBlock> {
+        let mut blocks = Vec::new();
+        let mut fences = Fences::new(start_mark);
+
+        for (idx, line) in self.lines().enumerate() {
+            match fences.next(line) {
+                Fence::Open => blocks.push(CodeBlock {
+                    start: idx,
+                    name: fence_name(line, start_mark).unwrap_or_default().into(),
+                    content: idx + 1..idx + 1,
+                    end: None,
+                }),
+                Fence::Inside => {
+                    if let Some(block) = blocks.last_mut() {
+                        block.content.end = idx + 1;
+                    }
+                },
+                Fence::Close => {
+                    if let Some(block) = blocks.last_mut() {
+                        block.end = Some(idx);
+                    }
+                },
+                Fence::Outside => {},
+            }
+        }
+        blocks
+    }
+
+    /// Plan items of the document: all lines of the issues file or the lines inside the ```` ```md todo ````
+    /// blocks of the manifest.
+    pub fn plan_items<P>(&self, placement: &Placement<P>, dialect: &ListDialect) -> Vec<DocItem> {
+        let line_indices: Vec<_> = match placement {
+            Placement::WholeFile(_) => (0..self.lines_count()).collect(),
+            Placement::CodeBlockInFile(_) => self
+                .code_blocks(MD_BLOCK_START)
+                .into_iter()
+                .flat_map(|block| block.content)
+                .collect(),
+        };
+
+        let mut parser = LineParser::new(dialect);
+        line_indices
+            .into_iter()
+            .filter_map(|idx| {
+                let span = self.line_span(idx)?;
+                let (kind, level) = parser.detect(&self.text[span.clone()]);
+                Some(DocItem {
+                    kind,
+                    level,
+                    line: idx,
+                    span,
+                })
+            })
+            .collect()
+    }
+
+    /// Replaces the lines with the given indices by the new lines, which are given without terminators.
+    /// The bytes outside of the replaced lines, including the absence of the final newline, stay untouched.
+    pub fn replace_lines(&mut self, lines: Range<usize>, new_lines: impl IntoIterator<Item = impl AsRef<str>>) {
+        let count = self.lines_count();
+        let lines = lines.start.min(count)..lines.end.min(count);
+        let newline = self.newline();
+
+        let start = self.lines.get(lines.start).map_or(self.text.len(), |span| span.start);
+        let end = lines
+            .end
+            .checked_sub(1)
+            .and_then(|idx| self.lines.get(idx))
+            .map_or(start, |span| span.end.max(start));
+        let is_terminated = |text: &str| text.is_empty() || text.ends_with('\n');
+
+        let new_lines: Vec<_> = new_lines.into_iter().collect();
+        let mut replacement = String::new();
+        if lines.start == count && !new_lines.is_empty() && !is_terminated(&self.text) {
+            replacement.push_str(newline);
+        }
+        for line in &new_lines {
+            replacement.push_str(line.as_ref());
+            replacement.push_str(newline);
+        }
+        // The last line of the document keeps its missing terminator, unless the line is empty
+        if end == self.text.len()
+            && !is_terminated(&self.text[start..end])
+            && new_lines.last().is_some_and(|line| !line.as_ref().is_empty())
+        {
+            replacement.truncate(replacement.len() - newline.len());
+        }
+
+        self.text.replace_range(start..end, &replacement);
+        *self = Self::parse(std::mem::take(&mut self.text));
+    }
+
+    pub fn insert_lines(&mut self, idx: usize, new_lines: impl IntoIterator<Item = impl AsRef<str>>) {
+        self.replace_lines(idx..idx, new_lines);
+    }
+
+    pub fn remove_lines(&mut self, lines: Range<usize>) {
+        self.replace_lines(lines, [""; 0]);
+    }
+
+    /// Replaces all lines of the document, but rewrites only the range of the lines which differ.
+    pub fn set_lines(&mut self, new_lines: impl IntoIterator<Item = impl AsRef<str>>) {
+        let new_lines: Vec<_> = new_lines.into_iter().collect();
+        let old_lines: Vec<_> = self.lines().collect();
+
+        let prefix = old_lines
+            .iter()
+            .zip(&new_lines)
+            .take_while(|(old, new)| **old == new.as_ref())
+            .count();
+        let suffix = old_lines[prefix..]
+            .iter()
+            .rev()
+            .zip(new_lines[prefix..].iter().rev())
+            .take_while(|(old, new)| **old == new.as_ref())
+            .count();
+
+        let old_end = old_lines.len() - suffix;
+        let new_end = new_lines.len() - suffix;
+        self.replace_lines(prefix..old_end, &new_lines[prefix..new_end]);
+    }
+}
+
+impl std::fmt::Display for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Kind of the line regarding the fenced code blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fence {
+    Open,
+    Inside,
+    Close,
+    Outside,
+}
+
+/// Tracks the fences of the code blocks with the given start mark line by line, the nested code blocks with
+/// an info string are kept inside.
+#[derive(Debug, Clone)]
+pub struct Fences<'a> {
+    start_mark: &'a str,
+    in_block: bool,
+    inner_blocks: usize,
+}
+
+impl<'a> Fences<'a> {
+    pub fn new(start_mark: &'a str) -> Self {
+        Self {
+            start_mark,
+            in_block: false,
+            inner_blocks: 0,
+        }
+    }
+
+    /// Tracks all fenced code blocks, whatever their info string and indentation.
+    pub fn any() -> Self {
+        Self::new("")
+    }
+
+    pub fn next(&mut self, line: &str) -> Fence {
+        let any = self.start_mark.is_empty();
+        if !self.in_block {
+            let opens = if any {
+                line.trim_start().starts_with(MD_BLOCK_END)
+            } else {
+                fence_name(line, self.start_mark).is_some()
+            };
+            if opens {
+                self.in_block = true;
+                return Fence::Open;
+            }
+            return Fence::Outside;
+        }
+
+        let line = if any { line.trim() } else { line.trim_end() };
+        if line.starts_with(MD_BLOCK_END) {
+            if line.chars().nth(3).is_some_and(|ch| !ch.is_whitespace()) {
+                self.inner_blocks += 1;
+            } else if self.inner_blocks == 0 {
+                self.in_block = false;
+                return Fence::Close;
+            } else {
+                self.inner_blocks -= 1;
+            }
+        }
+        Fence::Inside
+    }
+}
+
+/// Name of the code block opened by the fence line with the start mark, e.g. `backlog` for
+/// ```` ```md todo backlog ````. The name of the unnamed block is empty, `None` is returned for the other lines.
+pub fn fence_name<'a>(line: &'a str, start_mark: &str) -> Option<&'a str> {
+    let start = line.get(..line.len().min(start_mark.len() + 1))?;
+    (start.trim().to_lowercase() == start_mark).then(|| line[start.len()..].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_text_on_parse() {
+        for text in ["", "\n", "line", "line\n", "a\r\nb\r\n", "a\n\n  b\nc"] {
+            let doc = Document::parse(text);
+            assert_eq!(doc.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn replace_keeps_other_bytes() {
+        let mut doc = Document::parse("# Title\r\n\r\n- task 1\r\n- task 2");
+        doc.replace_lines(2..3, ["- [x] task 1", "  description"]);
+        assert_eq!(doc.as_str(), "# Title\r\n\r\n- [x] task 1\r\n  description\r\n- task 2");
+
+        doc.replace_lines(4..5, ["- task 3"]);
+        assert_eq!(doc.as_str(), "# Title\r\n\r\n- [x] task 1\r\n  description\r\n- task 3");
+
+        doc.insert_lines(5, ["- task 4"]);
+        assert_eq!(
+            doc.as_str(),
+            "# Title\r\n\r\n- [x] task 1\r\n  description\r\n- task 3\r\n- task 4\r\n"
+        );
+
+        doc.remove_lines(2..4);
+        assert_eq!(doc.as_str(), "# Title\r\n\r\n- task 3\r\n- task 4\r\n");
+    }
+
+    #[test]
+    fn find_code_blocks() {
+        let doc =
+            Document::parse("# Project\n\n```md todo\n- task\n```rust\nfn main() {}\n```\n```\ntext\n```md todo\n");
+        assert_eq!(doc.code_blocks(MD_BLOCK_START), vec![
+            CodeBlock {
+                start: 2,
+                name: String::new(),
+                content: 3..7,
+                end: Some(7),
+            },
+            CodeBlock {
+                start: 9,
+                name: String::new(),
+                content: 10..10,
+                end: None,
+            },
+        ]);
+
+        let items = doc.plan_items(&Placement::CodeBlockInFile(()), &ListDialect::default());
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].kind, LineKind::Issue);
+        assert_eq!(&doc.as_str()[items[0].span.clone()], "- task");
+    }
+
+    #[test]
+    fn find_named_code_blocks() {
+        assert_eq!(fence_name("```md todo", MD_BLOCK_START), Some(""));
+        assert_eq!(fence_name("```MD todo  backlog ", MD_BLOCK_START), Some("backlog"));
+        assert_eq!(fence_name("```md todos", MD_BLOCK_START), None);
+
+        let doc = Document::parse(
+            "```md todo backlog
+- task 1
+```
+```md todo sprint
+- task 2
+```
+",
+        );
+        let names: Vec<_> = doc
+            .code_blocks(MD_BLOCK_START)
+            .into_iter()
+            .map(|block| block.name)
+            .collect();
+        assert_eq!(names, ["backlog", "sprint"]);
+    }
+}
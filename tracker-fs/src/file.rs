@@ -1,8 +1,11 @@
 use std::path::{Path, PathBuf};
 
+use globset::{GlobBuilder, GlobMatcher};
 use regex::Regex;
 use walkdir::{DirEntry, WalkDir};
 
+use crate::discovery::DiscoveryRules;
+
 pub fn find_in_dir_and_parents(dir: impl AsRef<Path>, file_name: impl AsRef<Path>) -> Option<PathBuf> {
     let mut current_dir = dir.as_ref();
     loop {
@@ -23,10 +26,10 @@ pub fn find_by_name_part(dir: impl AsRef<Path>, file_name_part: impl AsRef<str>)
         .filter_map(Result::ok)
     {
         let path = entry.path();
-        if let Some(file_name) = path.file_name() {
-            if file_name.to_string_lossy().contains(file_name_part.as_ref()) {
-                return Some(path.to_path_buf());
-            }
+        if let Some(file_name) = path.file_name()
+            && file_name.to_string_lossy().contains(file_name_part.as_ref())
+        {
+            return Some(path.to_path_buf());
         }
     }
     None
@@ -43,6 +46,49 @@ pub fn find_match_files(dir: impl AsRef<Path>, regex: &Regex) -> impl Iterator<I
         })
 }
 
+/// Finds the files of the directory tree matched by the glob patterns against their path relative to the directory.
+/// The directories skipped by the rules and the project roots below the directory are not walked into. The files
+/// are ordered by the patterns and then by the path, the file matched by several patterns is taken once.
+pub fn find_by_globs(
+    dir: impl AsRef<Path>,
+    patterns: impl IntoIterator<Item = impl AsRef<str>>,
+    rules: &DiscoveryRules,
+    is_project_root: impl Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>, globset::Error> {
+    let dir = dir.as_ref();
+    let matchers = patterns
+        .into_iter()
+        .map(|pattern| {
+            GlobBuilder::new(pattern.as_ref())
+                .literal_separator(true)
+                .build()
+                .map(|glob| glob.compile_matcher())
+        })
+        .collect::<Result<Vec<GlobMatcher>, _>>()?;
+
+    let mut files: Vec<_> = WalkDir::new(dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            if !entry.file_type().is_dir() {
+                return true;
+            }
+            let relative_path = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+            !rules.is_too_deep(entry.depth())
+                && !rules.is_excluded(Path::new(entry.file_name()), relative_path)
+                && !is_project_root(entry.path())
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| Some(entry.path().strip_prefix(dir).ok()?.to_path_buf()))
+        .filter_map(|path| Some((matchers.iter().position(|matcher| matcher.is_match(&path))?, path)))
+        .collect();
+    files.sort();
+
+    Ok(files.into_iter().map(|(_, path)| dir.join(path)).collect())
+}
+
 pub fn find_by_regex(dir: impl AsRef<Path>, file_name_regex: &Regex) -> Option<PathBuf> {
     find_match_files(dir, file_name_regex)
         .next()
@@ -0,0 +1,204 @@
+use std::io;
+use std::path::Path;
+
+use todo_lib::issue::{Issue, Milestone};
+
+use crate::dialect::{strip_indent, ListDialect};
+use crate::document::{Document, Fence, Fences};
+use crate::generator::IntIdGenerator;
+use crate::issue::MD_BLOCK_START;
+use crate::plan::parse::{LineKind, LineParser, ParseLine};
+use crate::Placement;
+
+/// Kind of the previous non-empty line of the formatted plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line {
+    None,
+    Issue,
+    Description,
+    Milestone,
+    Separator,
+    Other,
+}
+
+/// Re-emits the plan lines in the canonical layout: the issues get the dialect marker and indentation of their
+/// nesting level, the descriptions are aligned with their issues, the milestones and the separators are surrounded
+/// by single empty lines. The runs of the empty lines are collapsed, the rest of the text is kept as is.
+pub fn format_plan_lines<'a>(lines: impl IntoIterator<Item = &'a str>, dialect: &ListDialect) -> Vec<String> {
+    let indent = dialect.indent_unit();
+    let mut parser = LineParser::new(dialect);
+    let mut formatted = Vec::new();
+    let mut last = Line::None;
+    let mut last_issue_level = None;
+    let mut empty_before = false;
+    let mut empty_after = false;
+    let mut fences = Fences::any();
+
+    for line in lines {
+        // The lines of the code blocks are kept as is, but still followed by the parser like the plan loader does
+        let fence = fences.next(line);
+        let in_block = matches!(fence, Fence::Inside | Fence::Close);
+
+        let (kind, level) = parser.detect(line);
+        // The separator following the text without an empty line is the underline of the heading
+        let kind = match kind {
+            LineKind::Separator if last == Line::Other && !empty_before => LineKind::Text,
+            LineKind::Separator if last == Line::None && !empty_before => LineKind::Text,
+            kind => kind,
+        };
+
+        let (text, current) = match kind {
+            LineKind::Empty if in_block => {
+                formatted.push(String::new());
+                continue;
+            },
+            LineKind::Empty => {
+                empty_before = true;
+                continue;
+            },
+            LineKind::Separator => {
+                empty_before = true;
+                last_issue_level = None;
+                (line.to_string(), Line::Separator)
+            },
+            LineKind::Milestone => {
+                empty_before = true;
+                last_issue_level = None;
+                (milestone_text(line), Line::Milestone)
+            },
+            LineKind::Issue => {
+                // The too deep issue is nested into the deepest open one
+                let level = if level > 0 && parser.open_item(level - 1).is_none() {
+                    level - 1
+                } else {
+                    level
+                };
+                let content_start = dialect
+                    .list_marker(line)
+                    .map_or(line.len(), |marker| marker.content_start);
+                last_issue_level = Some(level);
+                (
+                    format!(
+                        "{}{}{}",
+                        indent.repeat(level),
+                        dialect.marker(),
+                        issue_text(&line[content_start..])
+                    ),
+                    Line::Issue,
+                )
+            },
+            LineKind::Text => match last_issue_level {
+                Some(issue_level) if level > issue_level && matches!(last, Line::Issue | Line::Description) => {
+                    let description_indent = parser
+                        .open_item(issue_level)
+                        .map_or(0, |item| item.content_column.max(item.column + dialect.indent_width()));
+                    (
+                        format!(
+                            "{}{}",
+                            indent.repeat(issue_level + 1),
+                            strip_indent(line, description_indent)
+                        ),
+                        Line::Description,
+                    )
+                },
+                _ if line.trim().is_empty() => {
+                    if in_block {
+                        formatted.push(line.to_string());
+                    } else {
+                        empty_before = true;
+                    }
+                    continue;
+                },
+                _ => {
+                    // The text nested into the list items is aligned with the canonical indentation
+                    let text = match level {
+                        0 => match last_issue_level.take() {
+                            // The text closing the list items is indented less than the content of the written
+                            // issues, so it is not nested into them. One space is kept to not make it a plan item
+                            Some(_) if line.starts_with([' ', '\t']) => format!(" {}", line.trim_start()),
+                            _ => line.to_string(),
+                        },
+                        level => format!("{}{}", indent.repeat(level), line.trim_start()),
+                    };
+                    (text, Line::Other)
+                },
+            },
+        };
+
+        if (empty_before || empty_after) && !formatted.is_empty() && !in_block {
+            formatted.push(String::new());
+        }
+        formatted.push(if fence == Fence::Outside {
+            text
+        } else {
+            line.to_string()
+        });
+        empty_after = matches!(current, Line::Milestone | Line::Separator);
+        empty_before = false;
+        last = current;
+    }
+    formatted
+}
+
+/// Formats the plan lines of the document: all lines of the issues file or the lines inside each
+/// ```` ```md todo ```` block of the manifest. The rest of the manifest is kept untouched.
+pub fn format_document<P>(doc: &Document, placement: &Placement<P>, dialect: &ListDialect) -> Document {
+    let mut formatted = doc.clone();
+    match placement {
+        Placement::WholeFile(_) => formatted.set_lines(format_plan_lines(doc.lines(), dialect)),
+        Placement::CodeBlockInFile(_) => {
+            // The blocks are formatted from the last one, so the lines of the preceding blocks keep their indices
+            for block in doc.code_blocks(MD_BLOCK_START).into_iter().rev() {
+                let lines: Vec<_> = block.content.clone().filter_map(|idx| doc.line(idx)).collect();
+                formatted.replace_lines(block.content, format_plan_lines(lines, dialect));
+            }
+        },
+    }
+    formatted
+}
+
+/// Formats the plan file, the formatted document is returned only if it differs from the file.
+pub fn format_file(placement: &Placement<impl AsRef<Path>>, dialect: &ListDialect) -> io::Result<Option<Document>> {
+    let doc = Document::load(placement.as_ref())?;
+    let formatted = format_document(&doc, placement, dialect);
+    Ok((formatted != doc).then_some(formatted))
+}
+
+/// Issue content with the canonical spacing of the status box, the explicit id and the name.
+fn issue_text(content: &str) -> String {
+    let Some(captures) = <Issue<u64> as ParseLine<&IntIdGenerator>>::regex().captures(content) else {
+        return content.trim_end().to_string();
+    };
+
+    let mut text = String::new();
+    match captures.name("status").map(|status| status.as_str()) {
+        Some(" ") => text.push_str("[ ] "),
+        Some(_) => text.push_str("[x] "),
+        None => {},
+    }
+    if let Some(id) = captures.name("id") {
+        text.push_str(id.as_str().trim());
+        text.push(' ');
+    }
+    if let Some(name) = captures.name("name") {
+        text.push_str(name.as_str().trim());
+    }
+    text
+}
+
+/// Milestone line with the canonical spacing of the heading mark, the explicit id and the name.
+fn milestone_text(line: &str) -> String {
+    let Some(captures) = <Milestone<u64> as ParseLine<&IntIdGenerator>>::regex().captures(line) else {
+        return line.trim_end().to_string();
+    };
+
+    let mut text = String::from("# ");
+    if let Some(id) = captures.get(1) {
+        text.push_str(id.as_str().trim());
+        text.push(' ');
+    }
+    if let Some(name) = captures.get(2) {
+        text.push_str(name.as_str().trim());
+    }
+    text
+}
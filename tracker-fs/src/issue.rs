@@ -1,9 +1,17 @@
-use std::fs;
-use std::io::{self, Read, Seek, Write};
+use std::io;
+use std::ops::Range;
 use std::path::Path;
 
-use todo_lib::issue::Issue;
+use todo_lib::id::HashedId;
+use todo_lib::issue::{Issue, IssueStatus, Milestone};
+use todo_lib::plan::{Plan, Step};
 
+use crate::dialect::{strip_indent, ListDialect, ListMarker, TAB_STOP};
+use crate::document::{CodeBlock, Document};
+use crate::generator::IntIdGenerator;
+use crate::plan::parse::ParseLine;
+use crate::plan::ItemLines;
+use crate::tracker::PlanSource;
 use crate::Placement;
 
 pub const MD_BLOCK_START: &str = "```md todo";
@@ -12,145 +20,523 @@ pub const MD_BLOCK_END: &str = "```";
 pub trait SaveIssue {
     type Id;
 
-    fn to_text(&self) -> String;
-    fn add_first(&self, destination: &Placement<impl AsRef<Path>>) -> io::Result<()>;
-    fn add_last(&self, destination: &Placement<impl AsRef<Path>>) -> io::Result<()>;
+    fn to_text(&self, dialect: &ListDialect) -> String;
+
+    /// Adds the issue at the start of the destination. The issue is added to the ```` ```md todo <section> ````
+    /// block of the manifest if the section is given, or to the first block otherwise. Returns the zero-based
+    /// lines of the added issue.
+    fn add_first(
+        &self,
+        destination: &Placement<impl AsRef<Path>>,
+        section: Option<&str>,
+        dialect: &ListDialect,
+    ) -> io::Result<Range<usize>>;
+
+    /// Adds the issue at the end of the destination, the section is chosen the same way as by `add_first`.
+    fn add_last(
+        &self,
+        destination: &Placement<impl AsRef<Path>>,
+        section: Option<&str>,
+        dialect: &ListDialect,
+    ) -> io::Result<Range<usize>>;
 }
 
 impl<ID> SaveIssue for Issue<ID> {
     type Id = ID;
 
-    fn to_text(&self) -> String {
-        let mut text = format!("- {}", self.name);
+    fn to_text(&self, dialect: &ListDialect) -> String {
+        let mut text = dialect.marker();
+        if self.is_completed() {
+            text.push_str("[x] ");
+        }
+        text.push_str(&self.name);
+
+        let indent = dialect.indent_unit();
         for line in self.content.lines() {
-            text.push_str("\n  ");
+            text.push('\n');
+            text.push_str(&indent);
             text.push_str(line);
         }
         text
     }
 
-    fn add_first(&self, destination: &Placement<impl AsRef<Path>>) -> io::Result<()> {
-        if !destination.as_ref().as_ref().exists() {
-            fs::File::create(destination.as_ref())?;
-        }
-
-        let text = self.to_text();
+    fn add_first(
+        &self,
+        destination: &Placement<impl AsRef<Path>>,
+        section: Option<&str>,
+        dialect: &ListDialect,
+    ) -> io::Result<Range<usize>> {
+        let path = destination.as_ref();
+        let mut doc = Document::load(path)?;
+        let text = self.to_text(dialect);
 
-        match destination {
-            Placement::WholeFile(path) => {
-                let mut issues_content = fs::read_to_string(path)?;
-                issues_content.insert(0, '\n');
-                issues_content.insert_str(0, &text);
-
-                fs::write(path, issues_content)?;
+        let start = match destination {
+            Placement::WholeFile(_) => {
+                check_no_section(section)?;
+                0
             },
-            Placement::CodeBlockInFile(path) => {
-                let mut manifest_content = fs::read_to_string(path)?;
-
-                if let Some(start_range) = get_newline_mark_range(&manifest_content, MD_BLOCK_START, 0) {
-                    let idx = manifest_content[start_range.1..]
-                        .find('\n')
-                        .map(|idx| idx + start_range.1)
-                        .unwrap_or(manifest_content.len());
-                    manifest_content.insert(idx, '\n');
-                    manifest_content.insert_str(idx + 1, &text);
-                } else {
-                    if !manifest_content.is_empty() && !manifest_content.ends_with('\n') {
-                        manifest_content.push('\n');
-                    }
-                    manifest_content.push_str(MD_BLOCK_START);
-                    manifest_content.push('\n');
-                    manifest_content.push_str(&text);
-                    manifest_content.push('\n');
-                    manifest_content.push_str(MD_BLOCK_END);
-                    manifest_content.push('\n');
-                }
-                fs::write(path, manifest_content)?;
+            Placement::CodeBlockInFile(_) => match find_code_block(&doc, section) {
+                Some(block) => block.content.start,
+                None => append_code_block(&mut doc, section, ""),
             },
-        }
-        Ok(())
+        };
+        doc.insert_lines(start, text.lines());
+        doc.save(path)?;
+        Ok(start..start + text.lines().count())
+    }
+
+    fn add_last(
+        &self,
+        destination: &Placement<impl AsRef<Path>>,
+        section: Option<&str>,
+        dialect: &ListDialect,
+    ) -> io::Result<Range<usize>> {
+        add_text_last(destination, section, &self.to_text(dialect))
+    }
+}
+
+/// Adds the text at the end of the destination, the section is chosen the same way as by `SaveIssue::add_first`.
+/// Returns the zero-based lines of the added text.
+pub fn add_text_last(
+    destination: &Placement<impl AsRef<Path>>,
+    section: Option<&str>,
+    text: &str,
+) -> io::Result<Range<usize>> {
+    let path = destination.as_ref();
+    let mut doc = Document::load(path)?;
+
+    let start = match destination {
+        Placement::WholeFile(_) => {
+            check_no_section(section)?;
+            doc.lines_count()
+        },
+        Placement::CodeBlockInFile(_) => match find_code_block(&doc, section) {
+            Some(block) => block.end.unwrap_or(block.content.end),
+            None => append_code_block(&mut doc, section, ""),
+        },
+    };
+    doc.insert_lines(start, text.lines());
+    doc.save(path)?;
+    Ok(start..start + text.lines().count())
+}
+
+/// Issue of the subtree taken out of its plan source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtreeIssue {
+    /// Nesting level relative to the root of the subtree.
+    pub level: usize,
+
+    pub issue: Issue<u64>,
+
+    /// Id written in the issue line, `None` for the generated id.
+    pub explicit_id: Option<u64>,
+}
+
+/// Issue with its subissues in the file order.
+pub fn issue_subtree(source: &PlanSource, id: u64, dialect: &ListDialect) -> io::Result<Vec<SubtreeIssue>> {
+    let PlanSource { placement, plan, lines } = source;
+    let not_found = || io::Error::new(io::ErrorKind::NotFound, format!("issue `{id}` not found"));
+    let doc = Document::load(placement.as_ref())?;
+
+    let mut subtree = Vec::new();
+    let mut pending = vec![(0, id)];
+    while let Some((level, id)) = pending.pop() {
+        let issue = plan.get_issue(&id).ok_or_else(not_found)?;
+        let explicit_id = lines
+            .get_issue(&id)
+            .and_then(|location| doc.line(location.lines.start))
+            .and_then(|header| explicit_id(header, dialect));
+        pending.extend(
+            issue
+                .subissues
+                .iter()
+                .rev()
+                .map(|subissue_id| (level + 1, *subissue_id)),
+        );
+        subtree.push(SubtreeIssue {
+            level,
+            issue: issue.clone(),
+            explicit_id,
+        });
     }
+    Ok(subtree)
+}
 
-    fn add_last(&self, destination: &Placement<impl AsRef<Path>>) -> io::Result<()> {
-        if !destination.as_ref().as_ref().exists() {
-            fs::File::create(destination.as_ref())?;
+/// Text of the subtree issues in the dialect, only the explicit ids are written.
+pub fn subtree_to_text(subtree: &[SubtreeIssue], dialect: &ListDialect) -> String {
+    let indent = dialect.indent_unit();
+    let mut text = String::new();
+    for SubtreeIssue {
+        level,
+        issue,
+        explicit_id,
+    } in subtree
+    {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&indent.repeat(*level));
+        text.push_str(&dialect.marker());
+        if issue.is_completed() {
+            text.push_str("[x] ");
         }
+        if let Some(id) = explicit_id {
+            text.push_str(&format!("{id} "));
+        }
+        text.push_str(&issue.name);
+
+        for line in issue.content.lines() {
+            text.push('\n');
+            text.push_str(&indent.repeat(level + 1));
+            text.push_str(line);
+        }
+    }
+    text
+}
+
+/// Number of the issues and milestones of the source which ids are generated rather than written in their lines.
+pub fn generated_ids_count(source: &PlanSource, dialect: &ListDialect) -> io::Result<usize> {
+    let doc = Document::load(source.placement.as_ref())?;
+    Ok(source
+        .plan
+        .steps()
+        .iter()
+        .filter(|step| match step {
+            Step::Issue(id) => source
+                .lines
+                .get_issue(id)
+                .and_then(|location| doc.line(location.lines.start))
+                .is_some_and(|header| explicit_id(header, dialect).is_none()),
+            Step::Milestone(id) => source
+                .lines
+                .get_milestone(id)
+                .and_then(|location| doc.line(location.lines.start))
+                .is_some_and(|header| milestone_explicit_id(header).is_none()),
+        })
+        .count())
+}
 
-        let text = self.to_text();
+/// Id written in the issue line, `None` for the line without id or the line which is not an issue.
+pub fn explicit_id(line: &str, dialect: &ListDialect) -> Option<u64> {
+    let content_start = dialect.list_marker(line)?.content_start;
+    <Issue<u64> as ParseLine<&IntIdGenerator>>::regex()
+        .captures(&line[content_start..])?
+        .name("id")?
+        .as_str()
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Id written in the milestone line, `None` for the line without id or the line which is not a milestone.
+fn milestone_explicit_id(line: &str) -> Option<u64> {
+    <Milestone<u64> as ParseLine<&IntIdGenerator>>::regex()
+        .captures(line)?
+        .get(1)?
+        .as_str()
+        .trim()
+        .parse()
+        .ok()
+}
 
-        match destination {
-            Placement::WholeFile(path) => {
-                let mut file = fs::File::options().append(true).read(true).open(path)?;
+/// Rewrites the name, status and description of the issue located at `location`, keeping its list marker,
+/// explicit id and subissues untouched. The description is aligned with the issue content.
+pub fn replace_issue<ID>(
+    path: impl AsRef<Path>,
+    location: &ItemLines,
+    issue: &Issue<ID>,
+    dialect: &ListDialect,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut doc = Document::load(path)?;
+    let header = doc
+        .line(location.lines.start)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "issue line is out of file"))?;
+    let (header_text, content_start) = issue_header(header, issue, dialect)?;
 
-                let file_size = std::fs::metadata(path)?.len();
-                if file_size > 0 {
-                    let mut reader = io::BufReader::new(&file);
-                    reader.seek(io::SeekFrom::End(-1))?;
+    let padding: String = header[..content_start]
+        .chars()
+        .map(|ch| if ch == '\t' { ch } else { ' ' })
+        .collect();
+    let mut lines = vec![header_text];
+    for line in issue.content.lines() {
+        lines.push(format!("{padding}{line}"));
+    }
+
+    doc.replace_lines(location.lines.clone(), lines);
+    doc.save(path)
+}
 
-                    let mut last_ch = [0];
-                    reader.read_exact(&mut last_ch)?;
+/// Writes the id into the line of the issue located at `location`, `false` if the line already has an explicit id.
+pub fn write_explicit_id(
+    path: impl AsRef<Path>,
+    location: &ItemLines,
+    id: u64,
+    dialect: &ListDialect,
+) -> io::Result<bool> {
+    let path = path.as_ref();
+    let mut doc = Document::load(path)?;
+    let header = doc
+        .line(location.lines.start)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "issue line is out of file"))?;
+    let not_issue = || io::Error::new(io::ErrorKind::InvalidData, format!("`{header}` is not an issue line"));
+    let content_start = dialect.list_marker(header).ok_or_else(not_issue)?.content_start;
+    let captures = <Issue<u64> as ParseLine<&IntIdGenerator>>::regex()
+        .captures(&header[content_start..])
+        .ok_or_else(not_issue)?;
+    if captures.name("id").is_some() {
+        return Ok(false);
+    }
+
+    let name_start = content_start + captures.name("name").map_or(0, |name| name.start());
+    let header = format!("{}{id} {}", &header[..name_start], &header[name_start..]);
+    doc.replace_lines(location.lines.start..location.lines.start + 1, [header]);
+    doc.save(path)?;
+    Ok(true)
+}
+
+/// Removes the lines with the given zero-based indices from the file.
+pub fn remove_lines(path: impl AsRef<Path>, lines: Range<usize>) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut doc = Document::load(path)?;
+    doc.remove_lines(lines);
+    doc.save(path)
+}
 
-                    if &last_ch != b"\n" {
-                        file.write_all(b"\n")?;
-                    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement<ID = u64> {
+    /// Swap the issue with its previous sibling
+    Up,
+
+    /// Swap the issue with its next sibling
+    Down,
+
+    /// Make the issue the last subissue of its previous sibling
+    Indent,
+
+    /// Make the issue the next sibling of its parent
+    Outdent,
+
+    /// Make the issue the previous sibling of the given issue
+    Before(ID),
+
+    /// Make the issue the next sibling of the given issue
+    After(ID),
+}
+
+/// Moves the issue together with its subissues inside the source it was loaded from. Returns the file line index
+/// of the moved issue.
+pub fn move_issue<ID: HashedId + Clone>(
+    source: &PlanSource<ID>,
+    id: &ID,
+    movement: Movement<ID>,
+    dialect: &ListDialect,
+) -> io::Result<usize> {
+    let PlanSource { placement, plan, lines } = source;
+    let not_found = || io::Error::new(io::ErrorKind::NotFound, "issue not found");
+    let cannot_move = |reason: &str| io::Error::new(io::ErrorKind::InvalidInput, reason.to_string());
+
+    let issue = plan.get_issue(id).ok_or_else(not_found)?;
+    let subtree = lines.issue_subtree(plan, id).ok_or_else(not_found)?;
+    let path = placement.as_ref();
+    let mut doc = Document::load(path)?;
+    let mut file_lines: Vec<_> = doc.lines().map(ToString::to_string).collect();
+
+    let moved_start = match movement {
+        Movement::Up | Movement::Down => {
+            let siblings = sibling_ids(plan, issue);
+            let idx = siblings
+                .iter()
+                .position(|sibling| *sibling == id)
+                .ok_or_else(not_found)?;
+            let sibling = if movement == Movement::Up {
+                idx.checked_sub(1).and_then(|idx| siblings.get(idx))
+            } else {
+                siblings.get(idx + 1)
+            }
+            .ok_or_else(|| cannot_move("there is no sibling issue to swap with"))?;
+            let sibling_subtree = lines.issue_subtree(plan, sibling).ok_or_else(not_found)?;
+
+            let (first, second) = if movement == Movement::Up {
+                (sibling_subtree, subtree.clone())
+            } else {
+                (subtree.clone(), sibling_subtree)
+            };
+            let second_lines: Vec<_> = file_lines.drain(second.clone()).collect();
+            let first_lines: Vec<_> = file_lines.drain(first.clone()).collect();
+            let gap = second.start - first.end;
+            file_lines.splice(first.start + gap..first.start + gap, first_lines);
+            file_lines.splice(first.start..first.start, second_lines);
+
+            if movement == Movement::Up {
+                first.start
+            } else {
+                second.end - subtree.len()
+            }
+        },
+        Movement::Indent => {
+            let siblings = sibling_ids(plan, issue);
+            let idx = siblings
+                .iter()
+                .position(|sibling| *sibling == id)
+                .ok_or_else(not_found)?;
+            let Some(parent_id) = idx.checked_sub(1).and_then(|idx| siblings.get(idx)) else {
+                return Err(cannot_move("there is no previous sibling issue to become a parent"));
+            };
+
+            // The issue is nested when it is indented at least to the content of the new parent
+            let parent_marker = marker_at(&file_lines, lines.get_issue(parent_id), dialect).ok_or_else(not_found)?;
+            let content_offset = parent_marker.content_column - parent_marker.column;
+            let indent = if dialect.tabs && content_offset <= TAB_STOP {
+                "\t".to_string()
+            } else {
+                " ".repeat(dialect.indent.max(content_offset))
+            };
+            for line in &mut file_lines[subtree.clone()] {
+                if !line.trim().is_empty() {
+                    line.insert_str(0, &indent);
                 }
+            }
+            subtree.start
+        },
+        Movement::Outdent => {
+            let parent_id = issue
+                .parent_id
+                .as_ref()
+                .ok_or_else(|| cannot_move("top-level issue cannot be outdented"))?;
+            let parent_subtree = lines.issue_subtree(plan, parent_id).ok_or_else(not_found)?;
+            let parent_marker = marker_at(&file_lines, lines.get_issue(parent_id), dialect).ok_or_else(not_found)?;
+            let marker = marker_at(&file_lines, lines.get_issue(id), dialect).ok_or_else(not_found)?;
+            let outdent = marker.column.saturating_sub(parent_marker.column);
 
-                file.write_all(text.as_bytes())?;
-                file.write_all(b"\n")?;
-            },
-            Placement::CodeBlockInFile(path) => {
-                let mut manifest_content = fs::read_to_string(path)?;
-
-                if let Some(start_range) = get_newline_mark_range(&manifest_content, MD_BLOCK_START, 0) {
-                    if let Some(end_range) = get_newline_mark_range(&manifest_content, MD_BLOCK_END, start_range.1 + 1)
-                    {
-                        manifest_content.insert(end_range.0, '\n');
-                        manifest_content.insert_str(end_range.0, &text);
-                    } else {
-                        if !manifest_content.ends_with('\n') {
-                            manifest_content.push('\n');
-                        }
-                        manifest_content.push_str(&text);
-                        manifest_content.push('\n');
-                    }
-                } else {
-                    if !manifest_content.is_empty() && !manifest_content.ends_with('\n') {
-                        manifest_content.push('\n');
-                    }
-                    manifest_content.push_str(MD_BLOCK_START);
-                    manifest_content.push('\n');
-                    manifest_content.push_str(&text);
-                    manifest_content.push('\n');
-                    manifest_content.push_str(MD_BLOCK_END);
-                    manifest_content.push('\n');
+            let mut moved: Vec<_> = file_lines.drain(subtree.clone()).collect();
+            for line in &mut moved {
+                let stripped = line.len() - strip_indent(line, outdent).len();
+                line.drain(..stripped);
+            }
+            let insert_idx = parent_subtree.end - subtree.len();
+            file_lines.splice(insert_idx..insert_idx, moved);
+            insert_idx
+        },
+        Movement::Before(ref target_id) | Movement::After(ref target_id) => {
+            let target_subtree = lines
+                .issue_subtree(plan, target_id)
+                .ok_or_else(|| cannot_move("target issue is not found in the file of the issue"))?;
+            if subtree.contains(&target_subtree.start) {
+                return Err(cannot_move("issue cannot be moved next to itself or its subissue"));
+            }
+
+            // The issue takes the indentation of the target to become its sibling
+            let target_header = lines
+                .get_issue(target_id)
+                .and_then(|location| file_lines.get(location.lines.start))
+                .ok_or_else(not_found)?;
+            let target_indent = target_header[..target_header.len() - target_header.trim_start().len()].to_string();
+            let marker = marker_at(&file_lines, lines.get_issue(id), dialect).ok_or_else(not_found)?;
+
+            let mut moved: Vec<_> = file_lines.drain(subtree.clone()).collect();
+            for line in &mut moved {
+                if !line.trim().is_empty() {
+                    *line = format!("{target_indent}{}", strip_indent(line, marker.column));
+                }
+            }
+            let mut insert_idx = if matches!(movement, Movement::Before(_)) {
+                target_subtree.start
+            } else {
+                target_subtree.end
+            };
+            if insert_idx >= subtree.end {
+                insert_idx -= subtree.len();
+            }
+            file_lines.splice(insert_idx..insert_idx, moved);
+            insert_idx
+        },
+    };
+
+    doc.set_lines(file_lines);
+    doc.save(path)?;
+    Ok(moved_start)
+}
+
+/// Issues of the same parent or, for top-level issues, of the same milestone group.
+fn sibling_ids<'a, ID: HashedId + Clone>(plan: &'a Plan<ID>, issue: &Issue<ID>) -> Vec<&'a ID> {
+    if let Some(parent) = issue.parent_id.as_ref().and_then(|parent_id| plan.get_issue(parent_id)) {
+        return parent.subissues.iter().collect();
+    }
+
+    let mut group: Vec<&ID> = Vec::new();
+    for step in plan.steps() {
+        match step {
+            Step::Milestone(_) if group.iter().any(|id| **id == issue.id) => break,
+            Step::Milestone(_) => group.clear(),
+            Step::Issue(id) => {
+                if let Some(issue) = plan.get_issue(id)
+                    && issue.parent_id.is_none()
+                {
+                    group.push(&issue.id);
                 }
-                fs::write(path, manifest_content)?;
             },
         }
-        Ok(())
     }
+    group
 }
 
-fn get_newline_mark_range(haystack: impl AsRef<str>, mark: impl AsRef<str>, from_idx: usize) -> Option<(usize, usize)> {
-    let haystack = haystack.as_ref();
-    let mark = mark.as_ref();
+/// Header line of the issue with the list marker of the old header, returned with the byte index of the content.
+fn issue_header<ID>(header: &str, issue: &Issue<ID>, dialect: &ListDialect) -> io::Result<(String, usize)> {
+    let not_issue = || io::Error::new(io::ErrorKind::InvalidData, format!("`{header}` is not an issue line"));
+    let content_start = dialect.list_marker(header).ok_or_else(not_issue)?.content_start;
+    let captures = <Issue<u64> as ParseLine<&IntIdGenerator>>::regex()
+        .captures(&header[content_start..])
+        .ok_or_else(not_issue)?;
 
-    for (start_idx, _) in haystack
-        .match_indices(mark)
-        .skip_while(|(start_idx, _)| *start_idx < from_idx)
-    {
-        if start_idx == 0 || haystack.as_bytes()[start_idx - 1] == b'\n' {
-            let end_idx = start_idx + mark.len();
-            if end_idx == haystack.len()
-                || haystack[end_idx..]
-                    .chars()
-                    .next()
-                    .map(|ch| ch.is_whitespace())
-                    .unwrap_or(true)
-            {
-                return Some((start_idx, end_idx));
-            }
-        }
+    let mut text = header[..content_start].to_string();
+    match (issue.status, captures.name("status").is_some()) {
+        (IssueStatus::Completed, _) => text.push_str("[x] "),
+        (IssueStatus::Open, true) => text.push_str("[ ] "),
+        (IssueStatus::Open, false) => {},
+    }
+    if let Some(id) = captures.name("id") {
+        text.push_str(id.as_str().trim());
+        text.push(' ');
+    }
+    text.push_str(&issue.name);
+
+    Ok((text, content_start))
+}
+
+fn marker_at(file_lines: &[String], location: Option<&ItemLines>, dialect: &ListDialect) -> Option<ListMarker> {
+    dialect.list_marker(file_lines.get(location?.lines.start)?)
+}
+
+/// The ```` ```md todo ```` block of the section or the first block if the section is not given.
+fn find_code_block(doc: &Document, section: Option<&str>) -> Option<CodeBlock> {
+    let mut blocks = doc.code_blocks(MD_BLOCK_START).into_iter();
+    match section {
+        Some(section) => blocks.find(|block| block.name == section),
+        None => blocks.next(),
+    }
+}
+
+/// Appends the code block with the text to the document, returns the index of the line after the text.
+pub(crate) fn append_code_block(doc: &mut Document, section: Option<&str>, text: &str) -> usize {
+    let start = match section {
+        Some(section) => format!("{MD_BLOCK_START} {section}"),
+        None => MD_BLOCK_START.to_string(),
+    };
+    let lines = std::iter::once(start.as_str())
+        .chain(text.lines())
+        .chain(std::iter::once(MD_BLOCK_END));
+    let text_end = doc.lines_count() + 1 + text.lines().count();
+    doc.insert_lines(doc.lines_count(), lines);
+    text_end
+}
+
+fn check_no_section(section: Option<&str>) -> io::Result<()> {
+    match section {
+        Some(section) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("section `{section}` can be placed only in a manifest file"),
+        )),
+        None => Ok(()),
     }
-    None
 }
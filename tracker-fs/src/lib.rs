@@ -1,11 +1,22 @@
 pub use crate::placement::Placement;
 pub use crate::tracker::FsTracker;
 
+pub mod archive;
+pub mod cache;
+pub mod check;
 pub mod config;
+pub mod diagnostic;
+pub mod dialect;
+pub mod discovery;
+pub mod document;
 pub mod file;
+pub mod format;
 pub mod generator;
 pub mod issue;
+pub mod milestone;
 pub mod placement;
 pub mod plan;
 pub mod project;
+pub mod recurrence;
+pub mod time_log;
 pub mod tracker;
@@ -0,0 +1,96 @@
+use std::io;
+use std::path::Path;
+
+use todo_lib::issue::Milestone;
+
+use crate::document::Document;
+use crate::generator::IntIdGenerator;
+use crate::issue::{append_code_block, MD_BLOCK_START};
+use crate::plan::parse::ParseLine;
+use crate::plan::ItemLines;
+use crate::Placement;
+
+/// Inserts the milestone before the given line of the destination, or after the plan lines if the line is not given.
+/// The plan lines of the manifest are the lines of the ```` ```md todo ```` block containing the given line or of its
+/// first block. The milestone is separated from the neighbouring plan lines by empty lines, like in the formatted plan.
+pub fn insert_milestone(
+    destination: &Placement<impl AsRef<Path>>,
+    line_idx: Option<usize>,
+    name: &str,
+) -> io::Result<()> {
+    let path = destination.as_ref();
+    let mut doc = Document::load(path)?;
+    let text = format!("# {}", name.trim());
+
+    let plan_lines = match destination {
+        Placement::WholeFile(_) => 0..doc.lines_count(),
+        Placement::CodeBlockInFile(_) => {
+            let mut blocks = doc.code_blocks(MD_BLOCK_START).into_iter();
+            let block = match line_idx {
+                Some(idx) => blocks.find(|block| block.content.start <= idx && idx <= block.content.end),
+                None => blocks.next(),
+            };
+            match block {
+                Some(block) => block.content,
+                None if line_idx.is_some() => 0..doc.lines_count(),
+                None => {
+                    append_code_block(&mut doc, None, &text);
+                    return doc.save(path);
+                },
+            }
+        },
+    };
+
+    let idx = line_idx.unwrap_or(plan_lines.end);
+    let is_empty_line = |idx: usize| doc.line(idx).is_some_and(|line| line.trim().is_empty());
+    let mut lines = Vec::new();
+    if idx > plan_lines.start && !is_empty_line(idx - 1) {
+        lines.push(String::new());
+    }
+    lines.push(text);
+    if idx < plan_lines.end && !is_empty_line(idx) {
+        lines.push(String::new());
+    }
+
+    doc.insert_lines(idx, lines);
+    doc.save(path)
+}
+
+/// Replaces the name of the milestone, keeping its explicit id.
+pub fn rename_milestone(path: impl AsRef<Path>, location: &ItemLines, name: &str) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut doc = Document::load(path)?;
+    let line = doc
+        .line(location.lines.start)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "milestone line is out of file"))?;
+
+    let explicit_id = <Milestone<u64> as ParseLine<&IntIdGenerator>>::regex()
+        .captures(line)
+        .and_then(|captures| captures.get(1))
+        .map(|id| id.as_str().trim().to_string());
+    let text = match explicit_id {
+        Some(id) => format!("# {id} {}", name.trim()),
+        None => format!("# {}", name.trim()),
+    };
+
+    doc.replace_lines(location.lines.clone(), [text]);
+    doc.save(path)
+}
+
+/// Removes the milestone line, so its issues become needed by the next milestone of the same list. The empty line
+/// following the milestone is removed too, unless it is the only one separating the preceding and following lines.
+pub fn remove_milestone(path: impl AsRef<Path>, location: &ItemLines) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut doc = Document::load(path)?;
+
+    let start = location.lines.start;
+    let is_empty_line = |idx: usize| doc.line(idx).is_some_and(|line| line.trim().is_empty());
+    let end = if is_empty_line(start + 1) && (start == 0 || is_empty_line(start - 1)) {
+        start + 2
+    } else {
+        start + 1
+    };
+
+    doc.remove_lines(start..end);
+    doc.save(path)
+}
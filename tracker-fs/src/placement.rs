@@ -1,6 +1,8 @@
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum Placement<P> {
     WholeFile(P),
     CodeBlockInFile(P),
@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use std::io::{self, BufRead};
+use std::ops::Range;
 use std::path::Path;
 use std::str::FromStr;
 
@@ -6,12 +8,15 @@ use either::Either;
 use fs_err as fs;
 use indexmap::IndexMap;
 use todo_lib::id::HashedId;
-use todo_lib::issue::Issue;
-use todo_lib::plan::Plan;
+use todo_lib::issue::{Issue, SourceLocation};
+use todo_lib::plan::{Plan, Step};
 
-use self::parse::Item;
-use crate::generator::IdGenerator;
-use crate::issue::{MD_BLOCK_END, MD_BLOCK_START};
+use self::parse::{Item, LineKind, LineParser};
+use crate::diagnostic::Diagnostic;
+use crate::dialect::{leading_whitespace, strip_indent, ListDialect};
+use crate::document::{fence_name, Fence, Fences};
+use crate::generator::{IdGenerator, IntIdGenerator};
+use crate::issue::MD_BLOCK_START;
 use crate::Placement;
 
 pub mod parse;
@@ -19,7 +24,24 @@ pub mod parse;
 pub trait LoadProjectPlan<GEN> {
     type Id;
 
-    fn load(source: &Placement<impl AsRef<Path>>, id_generator: GEN) -> io::Result<Option<Plan<Self::Id>>>;
+    fn load(
+        source: &Placement<impl AsRef<Path>>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<Option<Plan<Self::Id>>>;
+
+    fn load_located(
+        source: &Placement<impl AsRef<Path>>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<Option<LocatedPlan<Self::Id>>>;
+
+    /// Loads the plan skipping over the broken lines, the found problems are returned as the diagnostics.
+    fn load_diagnosed(
+        source: &Placement<impl AsRef<Path>>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<Option<DiagnosedPlan<Self::Id>>>;
 
     fn load_to_lines(
         source: &Placement<impl AsRef<Path>>,
@@ -28,7 +50,119 @@ pub trait LoadProjectPlan<GEN> {
     fn load_from_lines(
         lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
         id_generator: GEN,
+        dialect: &ListDialect,
     ) -> io::Result<Plan<Self::Id>>;
+
+    fn load_located_from_lines(
+        lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<LocatedPlan<Self::Id>>;
+
+    fn load_diagnosed_from_lines(
+        lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<DiagnosedPlan<Self::Id>>;
+
+    /// Loads the plan from the lines of the ```` ```md todo ```` code blocks given with their opening fences,
+    /// as returned by `code_block_lines`. Each named block makes a plan section.
+    fn load_diagnosed_from_code_blocks(
+        lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<DiagnosedPlan<Self::Id>>;
+}
+
+/// Plan together with the file positions of its items.
+pub type LocatedPlan<ID> = (Plan<ID>, PlanLines<ID>);
+
+/// Located plan together with the problems found while loading it.
+pub type DiagnosedPlan<ID> = (Plan<ID>, PlanLines<ID>, Vec<Diagnostic>);
+
+/// Position of a parsed issue or milestone in its source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemLines {
+    /// Nesting level of the item (0 for top-level issues and milestones).
+    pub level: usize,
+
+    /// Zero-based file line indices of the item itself, including the issue description.
+    pub lines: Range<usize>,
+}
+
+/// File positions of all items of a plan loaded from a single source.
+#[derive(Debug, Clone)]
+pub struct PlanLines<ID> {
+    items: HashMap<Step<ID>, ItemLines>,
+}
+
+impl<ID> Default for PlanLines<ID> {
+    fn default() -> Self {
+        Self { items: HashMap::new() }
+    }
+}
+
+impl<ID: HashedId + Clone> PlanLines<ID> {
+    pub fn get_issue(&self, id: &ID) -> Option<&ItemLines> {
+        self.items.get(&Step::Issue(id.clone()))
+    }
+
+    pub fn get_milestone(&self, id: &ID) -> Option<&ItemLines> {
+        self.items.get(&Step::Milestone(id.clone()))
+    }
+
+    /// Lines of the issue together with all of its subissues.
+    pub fn issue_subtree(&self, plan: &Plan<ID>, id: &ID) -> Option<Range<usize>> {
+        let ItemLines { lines, .. } = self.get_issue(id)?;
+        let mut end = lines.end;
+
+        let mut subissues: Vec<_> = plan.get_issue(id)?.subissues.iter().collect();
+        while let Some(subissue_id) = subissues.pop() {
+            if let Some(subissue_lines) = self.get_issue(subissue_id) {
+                end = end.max(subissue_lines.lines.end);
+            }
+            if let Some(subissue) = plan.get_issue(subissue_id) {
+                subissues.extend(subissue.subissues.iter());
+            }
+        }
+
+        Some(lines.start..end)
+    }
+
+    /// Sets the source locations of the plan items loaded from the file.
+    pub fn set_locations(&self, plan: &mut Plan<ID>, path: &Path) {
+        for (step, ItemLines { lines, .. }) in &self.items {
+            let location = Some(SourceLocation {
+                path: path.to_path_buf(),
+                lines: lines.clone(),
+            });
+            match step {
+                Step::Issue(id) => {
+                    if let Some(issue) = plan.get_issue_mut(id) {
+                        issue.location = location;
+                    }
+                },
+                Step::Milestone(id) => {
+                    if let Some(milestone) = plan.get_milestone_mut(id) {
+                        milestone.location = location;
+                    }
+                },
+            }
+        }
+    }
+
+    fn insert(&mut self, step: Step<ID>, level: usize, line_idx: usize) {
+        self.items.insert(step, ItemLines {
+            level,
+            lines: line_idx..line_idx + 1,
+        });
+    }
+
+    fn extend_issue(&mut self, id: &ID, line_idx: usize) {
+        if let Some(item) = self.items.get_mut(&Step::Issue(id.clone())) {
+            item.lines.end = line_idx + 1;
+        }
+    }
 }
 
 impl<ID, GEN> LoadProjectPlan<GEN> for Plan<ID>
@@ -38,12 +172,43 @@ where
 {
     type Id = ID;
 
-    fn load(source: &Placement<impl AsRef<Path>>, id_generator: GEN) -> io::Result<Option<Plan<Self::Id>>> {
-        if source.as_ref().as_ref().exists() {
-            let lines = <Self as LoadProjectPlan<GEN>>::load_to_lines(source)?;
-            let plan = Self::load_from_lines(lines, id_generator)?;
+    fn load(
+        source: &Placement<impl AsRef<Path>>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<Option<Plan<Self::Id>>> {
+        <Self as LoadProjectPlan<GEN>>::load_located(source, id_generator, dialect)
+            .map(|located| located.map(|(plan, _)| plan))
+    }
 
-            Ok(Some(plan))
+    fn load_located(
+        source: &Placement<impl AsRef<Path>>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<Option<LocatedPlan<Self::Id>>> {
+        <Self as LoadProjectPlan<GEN>>::load_diagnosed(source, id_generator, dialect)?
+            .map(fail_on_error)
+            .transpose()
+    }
+
+    fn load_diagnosed(
+        source: &Placement<impl AsRef<Path>>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<Option<DiagnosedPlan<Self::Id>>> {
+        let path = source.as_ref().as_ref();
+        if path.exists() {
+            let lines = <Self as LoadProjectPlan<GEN>>::load_to_lines(source)?;
+            let (mut plan, lines, diagnostics) = match source {
+                Placement::WholeFile(_) => Self::load_diagnosed_from_lines(lines, id_generator, dialect)?,
+                Placement::CodeBlockInFile(_) => Self::load_diagnosed_from_code_blocks(lines, id_generator, dialect)?,
+            };
+            lines.set_locations(&mut plan, path);
+            let diagnostics = diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.with_path(path))
+                .collect();
+            Ok(Some((plan, lines, diagnostics)))
         } else {
             Ok(None)
         }
@@ -59,35 +224,7 @@ where
             },
             Placement::CodeBlockInFile(path) => {
                 let file = fs::File::open(path.as_ref())?;
-
-                let mut in_block = false;
-                let mut inner_blocks: usize = 0;
-                Either::Right(io::BufReader::new(file).lines().enumerate().filter(move |(_, line)| {
-                    let Ok(line) = line else {
-                        return false;
-                    };
-
-                    if !in_block {
-                        if let Some(start) = line.get(..line.len().min(MD_BLOCK_START.len() + 1)) {
-                            if start.trim().to_lowercase() == MD_BLOCK_START {
-                                in_block = true;
-                            }
-                        }
-                        false
-                    } else {
-                        let line = line.trim_end();
-                        if line.starts_with(MD_BLOCK_END) {
-                            if line.chars().nth(3).map(|ch| !ch.is_whitespace()).unwrap_or(false) {
-                                inner_blocks += 1;
-                            } else if inner_blocks == 0 {
-                                in_block = false;
-                            } else {
-                                inner_blocks -= 1;
-                            }
-                        }
-                        in_block
-                    }
-                }))
+                Either::Right(code_block_lines(io::BufReader::new(file).lines().enumerate()))
             },
         };
         Ok(lines)
@@ -96,108 +233,284 @@ where
     fn load_from_lines(
         lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
         id_generator: GEN,
+        dialect: &ListDialect,
     ) -> io::Result<Plan<Self::Id>> {
-        let mut planned = Plan::<ID>::new();
-        let mut last = Last::<ID>::new();
+        Self::load_located_from_lines(lines, id_generator, dialect).map(|(plan, _)| plan)
+    }
 
-        for (line_idx, line) in lines {
-            let line = line?;
+    fn load_located_from_lines(
+        lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<LocatedPlan<Self::Id>> {
+        fail_on_error(Self::load_diagnosed_from_lines(lines, id_generator, dialect)?)
+    }
+
+    fn load_diagnosed_from_lines(
+        lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<DiagnosedPlan<Self::Id>> {
+        load_diagnosed_lines(lines, id_generator, dialect, false)
+    }
+
+    fn load_diagnosed_from_code_blocks(
+        lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
+        id_generator: GEN,
+        dialect: &ListDialect,
+    ) -> io::Result<DiagnosedPlan<Self::Id>> {
+        load_diagnosed_lines(lines, id_generator, dialect, true)
+    }
+}
+
+/// Loads the plan skipping over the broken lines. The fences of the code blocks are recognized only if the lines
+/// come from the code blocks, in the whole issues file they are the plain text.
+fn load_diagnosed_lines<ID, GEN>(
+    lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
+    id_generator: GEN,
+    dialect: &ListDialect,
+    code_blocks: bool,
+) -> io::Result<DiagnosedPlan<ID>>
+where
+    ID: HashedId + Clone + FromStr,
+    GEN: IdGenerator<Id = ID> + Copy,
+{
+    let mut diagnostics = Vec::new();
+    let mut planned = Plan::<ID>::new();
+    let mut located = PlanLines::default();
+    let mut last = Last::<ID>::new();
+    let mut parser = LineParser::new(dialect);
+    let mut section = None;
+
+    for (line_idx, line) in lines {
+        let line = line?;
 
-            match Item::parse(line, id_generator) {
-                (Item::Empty, _) => {
-                    if let Line::Separator = last.line {
-                        planned.add_issues(last.extract_issues());
+        if code_blocks && let Some(name) = fence_name(&line, MD_BLOCK_START) {
+            // Each code block is a separate list, the named one makes a plan section
+            planned.add_issues(last.extract_issues());
+            close_section(&mut planned, section.take());
+            section = (!name.is_empty()).then(|| (name.to_string(), planned.steps().len()));
+            last = Last::new();
+            parser = LineParser::new(dialect);
+            continue;
+        }
+
+        let (_, column) = leading_whitespace(&line);
+        match parser.parse(line, id_generator) {
+            (Item::Empty, _) => {
+                if let Line::Separator = last.line {
+                    planned.add_issues(last.extract_issues());
+                }
+                last.line = Line::Empty;
+            },
+            (Item::Separator, _) => {
+                if let Line::Empty = last.line {
+                    last.line = Line::Separator;
+                } else {
+                    last.line = Line::Other;
+                }
+            },
+            (Item::Issue(mut issue), mut issue_level) => {
+                // The issue indented by a tab stop past its parent content is not a list item of the parent,
+                // the deeper levels are reported below
+                if issue_level > 0
+                    && issue_level <= last.issue_level + 1
+                    && !last.parsed_issues.is_empty()
+                    && parser.open_item(issue_level).is_none()
+                {
+                    diagnostics.push(Diagnostic::error(
+                        line_idx,
+                        column,
+                        "issue is indented by a tab stop or more past the content of its parent",
+                    ));
+                }
+
+                if issue_level == last.issue_level {
+                    if let (Line::Issue | Line::Description | Line::Empty, Some(id)) =
+                        (last.line, last.issue_parent_id.clone())
+                    {
+                        last.parsed_issues
+                            .get_mut(&id)
+                            .expect("issue for previous parent must parsed")
+                            .subissues
+                            .insert(issue.id.clone());
+                        issue.parent_id = Some(id);
                     }
-                    last.line = Line::Empty;
-                },
-                (Item::Separator, _) => {
-                    if let Line::Empty = last.line {
-                        last.line = Line::Separator;
-                    } else {
-                        last.line = Line::Other;
+                } else if issue_level != 0 {
+                    if issue_level > last.issue_level + 1 {
+                        // Recover by nesting the issue into the previous one
+                        diagnostics.push(Diagnostic::error(
+                            line_idx,
+                            column,
+                            format!(
+                                "issue level = {issue_level} is greater than previous issue level + 1 = {}",
+                                last.issue_level + 1
+                            ),
+                        ));
+                        issue_level = last.issue_level + 1;
                     }
-                },
-                (Item::Issue(mut issue), issue_level) => {
-                    if issue_level == last.issue_level {
-                        if let (Line::Issue | Line::Description | Line::Empty, Some(id)) =
-                            (last.line, last.issue_parent_id.clone())
-                        {
-                            last.parsed_issues
-                                .get_mut(&id)
-                                .expect("issue for previous parent must parsed")
-                                .subissues
-                                .insert(issue.id.clone());
-                            issue.parent_id = Some(id);
-                        }
-                    } else if issue_level != 0 {
-                        let parent_issue = if issue_level == last.issue_level + 1 {
-                            last.parsed_issues.last_mut().map(|(_, last_issue)| last_issue)
-                        } else if issue_level < last.issue_level {
-                            last.find_parent(issue_level)
-                        } else {
-                            return Err(io::Error::new(
-                                io::ErrorKind::InvalidData,
-                                format!(
-                                    "in line {line_idx}: issue level = {issue_level} is greater than previous issue level + 1 = {}",
-                                    last.issue_level + 1
-                                ),
-                            ));
-                        }.ok_or_else(|| {
-                            io::Error::new(
-                                io::ErrorKind::InvalidData,
-                                format!(
-                                    "in line {line_idx}: parent not found for issue level = {issue_level}"
-                                ),
-                            )
-                        })?;
 
+                    let parent_issue = if issue_level == last.issue_level + 1 {
+                        last.parsed_issues.last_mut().map(|(_, last_issue)| last_issue)
+                    } else {
+                        last.find_parent(issue_level)
+                    };
+                    if let Some(parent_issue) = parent_issue {
                         parent_issue.subissues.insert(issue.id.clone());
                         issue.parent_id = Some(parent_issue.id.clone());
+                    } else {
+                        // Recover by making the issue a top-level one
+                        diagnostics.push(Diagnostic::error(
+                            line_idx,
+                            column,
+                            format!("parent not found for issue level = {issue_level}"),
+                        ));
+                        issue_level = 0;
                     }
+                }
 
-                    last.insert_issue(issue, issue_level);
-                    last.line = Line::Issue;
-                },
-                (Item::Milestone(mut milestone), _milestone_level) => {
-                    milestone.needed_issues.extend(last.parsed_issues.keys().cloned());
-                    planned.add_issues(last.extract_issues());
-                    planned.add_milestone(milestone);
-                    last.line = Line::Milestone;
+                if let Some(first) = located.get_issue(&issue.id) {
+                    diagnostics.push(Diagnostic::warning(
+                        line_idx,
+                        column,
+                        format!("duplicate issue id, first used in line {}", first.lines.start + 1),
+                    ));
+                }
+                located.insert(Step::Issue(issue.id.clone()), issue_level, line_idx);
+                last.insert_issue(issue, issue_level);
+                last.line = Line::Issue;
+            },
+            (Item::Milestone(mut milestone), _milestone_level) => {
+                milestone.needed_issues.extend(last.parsed_issues.keys().cloned());
+                planned.add_issues(last.extract_issues());
+                located.insert(Step::Milestone(milestone.id.clone()), 0, line_idx);
+                planned.add_milestone(milestone);
+                last.line = Line::Milestone;
+            },
+            (Item::Text(text), text_level) => match last.line {
+                Line::Issue | Line::Description | Line::Empty if text_level > last.issue_level => {
+                    // The description is indented to the issue content or by the dialect indentation
+                    let indent = parser
+                        .open_item(last.issue_level)
+                        .map_or(0, |item| item.content_column.max(item.column + dialect.indent_width()));
+                    let description_line = strip_indent(&text, indent);
+
+                    let target_issue = last
+                        .parsed_issues
+                        .last_mut()
+                        .expect("issue for description must exist")
+                        .1;
+                    if !target_issue.content.is_empty() {
+                        target_issue.content.push('\n');
+                    }
+                    target_issue.content.push_str(description_line);
+                    located.extend_issue(&target_issue.id, line_idx);
+
+                    last.line = Line::Description;
                 },
-                (Item::Text(text), text_level) => match last.line {
-                    Line::Issue | Line::Description | Line::Empty if text_level > last.issue_level => {
-                        let mut padding = (last.issue_level + 1) * 2;
-                        let description_line = text.trim_start_matches(|ch| {
-                            if padding > 0 && ch == ' ' {
-                                true
-                            } else {
-                                padding -= 1;
-                                false
-                            }
-                        });
-
-                        let target_issue = last
-                            .parsed_issues
-                            .last_mut()
-                            .expect("issue for description must exist")
-                            .1;
-                        if !target_issue.content.is_empty() {
-                            target_issue.content.push('\n');
-                        }
-                        target_issue.content.push_str(description_line);
-
-                        last.line = Line::Description;
-                    },
-                    _ => last.line = Line::Other,
+                _ => last.line = Line::Other,
+            },
+        }
+    }
+    planned.add_issues(last.extract_issues());
+    close_section(&mut planned, section);
+
+    Ok((planned, located, diagnostics))
+}
+
+/// Fails with the first diagnosed error, the plan with the broken lines is not suitable for editing.
+fn fail_on_error<ID>((plan, lines, diagnostics): DiagnosedPlan<ID>) -> io::Result<LocatedPlan<ID>> {
+    match diagnostics.into_iter().find(Diagnostic::is_error) {
+        Some(diagnostic) => Err(diagnostic.into()),
+        None => Ok((plan, lines)),
+    }
+}
+
+/// Adds the steps starting from the given index to the section.
+fn close_section<ID: HashedId + Clone>(plan: &mut Plan<ID>, section: Option<(String, usize)>) {
+    if let Some((name, start)) = section {
+        let steps: Vec<_> = plan.steps().iter().skip(start).cloned().collect();
+        plan.add_to_section(name, steps);
+    }
+}
+
+/// Numbers of the plan steps of the different kinds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StepsCount {
+    pub milestones: usize,
+    pub issues: usize,
+    pub subissues: usize,
+}
+
+impl StepsCount {
+    pub fn of_plan<ID: HashedId + Clone>(plan: &Plan<ID>) -> Self {
+        let mut count = Self::default();
+        for step in plan.steps() {
+            match step {
+                Step::Milestone(_) => count.milestones += 1,
+                Step::Issue(id) => match plan.get_issue(id) {
+                    Some(issue) if issue.parent_id.is_some() => count.subissues += 1,
+                    _ => count.issues += 1,
                 },
             }
         }
-        planned.add_issues(last.extract_issues());
+        count
+    }
+
+    /// Counts the steps of the plan source scanning its lines without parsing the items. Unlike the plan loading,
+    /// the nesting of the issues is not validated: any indented issue is counted as a subissue.
+    pub fn of_source(source: &Placement<impl AsRef<Path>>, dialect: &ListDialect) -> io::Result<Self> {
+        let mut count = Self::default();
+        if !source.as_ref().as_ref().exists() {
+            return Ok(count);
+        }
 
-        Ok(planned)
+        let mut parser = LineParser::new(dialect);
+        for (_, line) in <Plan<u64> as LoadProjectPlan<&IntIdGenerator>>::load_to_lines(source)? {
+            match parser.detect(&line?) {
+                (LineKind::Issue, 0) => count.issues += 1,
+                (LineKind::Issue, _) => count.subissues += 1,
+                (LineKind::Milestone, _) => count.milestones += 1,
+                _ => {},
+            }
+        }
+        Ok(count)
+    }
+
+    pub fn total(&self, with_subissues: bool) -> usize {
+        let count = self.milestones + self.issues;
+        if with_subissues {
+            count + self.subissues
+        } else {
+            count
+        }
     }
 }
 
+impl std::ops::Add for StepsCount {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            milestones: self.milestones + other.milestones,
+            issues: self.issues + other.issues,
+            subissues: self.subissues + other.subissues,
+        }
+    }
+}
+
+/// Keeps only the lines of the ```` ```md todo ```` code blocks, preserving their file line indices. The opening
+/// fences are kept to separate the blocks.
+pub fn code_block_lines(
+    lines: impl IntoIterator<Item = (usize, io::Result<String>)>,
+) -> impl Iterator<Item = (usize, io::Result<String>)> {
+    let mut fences = Fences::new(MD_BLOCK_START);
+    lines.into_iter().filter(move |(_, line)| match line {
+        Ok(line) => matches!(fences.next(line), Fence::Open | Fence::Inside),
+        Err(_) => false,
+    })
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 enum Line {
     #[default]
@@ -240,6 +553,9 @@ impl<ID: HashedId + PartialEq + Clone> Last<ID> {
     }
 
     fn extract_issues(&mut self) -> impl IntoIterator<Item = Issue<ID>> + '_ {
+        // The next issues can't be nested into the extracted ones
+        self.issue_level = 0;
+        self.issue_parent_id = None;
         self.parsed_issues.drain(..).map(|(_, issue)| issue)
     }
 
@@ -2,9 +2,10 @@ use std::str::FromStr;
 
 use once_cell::sync::Lazy;
 use regex::Regex;
-use todo_lib::issue::{Issue, Milestone};
+use todo_lib::issue::{Issue, IssueStatus, Milestone};
 
-use crate::generator::IdGenerator;
+use crate::dialect::{leading_whitespace, ListDialect, ListMarker, TAB_STOP};
+use crate::generator::{IdGenerator, IntIdGenerator};
 
 pub enum Item<ID> {
     Empty,
@@ -14,27 +15,110 @@ pub enum Item<ID> {
     Text(String),
 }
 
-impl<ID: FromStr> Item<ID> {
-    pub fn parse<GEN: IdGenerator<Id = ID>>(line: impl Into<String>, id_generator: GEN) -> (Self, usize) {
+/// Kind of the plan line, detected without parsing the item itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Empty,
+    Separator,
+    Issue,
+    Milestone,
+    Text,
+}
+
+/// Parser of the plan lines, which follows the list nesting by the CommonMark rules: the line is nested into
+/// the list item if it is indented at least to the item content column.
+#[derive(Debug, Clone)]
+pub struct LineParser<'a> {
+    dialect: &'a ListDialect,
+
+    /// Markers of the list items containing the current line.
+    open_items: Vec<ListMarker>,
+}
+
+impl<'a> LineParser<'a> {
+    pub fn new(dialect: &'a ListDialect) -> Self {
+        Self {
+            dialect,
+            open_items: Vec::new(),
+        }
+    }
+
+    /// Detects the kind of the line together with its nesting level. The issue indented by a tab stop or more
+    /// deeper than the content of its parent gets the level greater than the parent level + 1.
+    pub fn detect(&mut self, line: &str) -> (LineKind, usize) {
         static SEPARATOR_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^-{3,}\s?.*").expect("regex must be correct"));
 
-        let line = line.into();
-        let line_trimmed = line.trim_start_matches(' ');
-        let line_level = (line.len() - line_trimmed.len()) / 2;
-
-        let item = if line.is_empty() {
-            Item::Empty
-        } else if <Issue<ID> as ParseLine<GEN>>::regex().is_match(&line) {
-            Item::Issue(Issue::parse_line(line_trimmed, id_generator))
-        } else if SEPARATOR_REGEX.is_match(&line) {
-            Item::Separator
-        } else if <Milestone<ID> as ParseLine<GEN>>::regex().is_match(&line) {
-            Item::Milestone(Milestone::parse_line(line_trimmed, id_generator))
+        if line.is_empty() {
+            return (LineKind::Empty, 0);
+        }
+
+        let (indent_len, column) = leading_whitespace(line);
+        if indent_len == line.len() {
+            // Blank lines do not close the list items
+            let level = self
+                .open_items
+                .iter()
+                .filter(|item| item.content_column <= column)
+                .count();
+            return (LineKind::Text, level);
+        }
+
+        if let Some(marker) = self.dialect.list_marker(line) {
+            let level = self.close_items(column);
+            let parent_content_column = self.open_items.last().map_or(0, |item| item.content_column);
+            if column >= parent_content_column + TAB_STOP {
+                return (LineKind::Issue, level + 1);
+            }
+            self.open_items.push(marker);
+            (LineKind::Issue, level)
+        } else if SEPARATOR_REGEX.is_match(line) {
+            self.open_items.clear();
+            (LineKind::Separator, 0)
+        } else if <Milestone<u64> as ParseLine<&IntIdGenerator>>::regex().is_match(line) {
+            self.open_items.clear();
+            (LineKind::Milestone, 0)
         } else {
-            Item::Text(line)
+            (LineKind::Text, self.close_items(column))
+        }
+    }
+
+    /// Parses the line into the plan item together with its nesting level.
+    pub fn parse<ID: FromStr, GEN: IdGenerator<Id = ID>>(
+        &mut self,
+        line: impl Into<String>,
+        id_generator: GEN,
+    ) -> (Item<ID>, usize) {
+        let line = line.into();
+        let (kind, level) = self.detect(&line);
+
+        let item = match kind {
+            LineKind::Empty => Item::Empty,
+            LineKind::Separator => Item::Separator,
+            LineKind::Issue => {
+                let content_start = self
+                    .dialect
+                    .list_marker(&line)
+                    .map_or(line.len(), |marker| marker.content_start);
+                Item::Issue(Issue::parse_line(&line[content_start..], id_generator))
+            },
+            LineKind::Milestone => Item::Milestone(Milestone::parse_line(&line, id_generator)),
+            LineKind::Text => Item::Text(line),
         };
 
-        (item, line_level)
+        (item, level)
+    }
+
+    /// Marker of the open list item with the given level.
+    pub fn open_item(&self, level: usize) -> Option<&ListMarker> {
+        self.open_items.get(level)
+    }
+
+    /// Closes the list items which do not contain the column and returns the number of the remaining ones.
+    fn close_items(&mut self, column: usize) -> usize {
+        while self.open_items.last().is_some_and(|item| column < item.content_column) {
+            self.open_items.pop();
+        }
+        self.open_items.len()
     }
 }
 
@@ -48,24 +132,31 @@ where
     ID: FromStr,
     GEN: IdGenerator<Id = ID>,
 {
+    /// Matches the issue content following the list item marker.
     fn regex() -> &'static Regex {
-        static ISSUE_REGEX: Lazy<Regex> =
-            Lazy::new(|| Regex::new(r"^\s*[-+]\s+([0-9]+\s)?\s*(.*)").expect("regex must be correct"));
+        static ISSUE_REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"^(?:\[(?<status>[ xX])\]\s+)?(?<id>[0-9]+\s)?\s*(?<name>.*)").expect("regex must be correct")
+        });
         &ISSUE_REGEX
     }
 
     fn parse_line(line: &str, id_generator: GEN) -> Self {
         let captures = <Self as ParseLine<GEN>>::regex().captures(line);
 
+        let status = match captures.as_ref().and_then(|caps| caps.name("status")) {
+            Some(status) if status.as_str() != " " => IssueStatus::Completed,
+            _ => IssueStatus::Open,
+        };
+
         let id = captures
             .as_ref()
-            .and_then(|caps| caps.get(1))
+            .and_then(|caps| caps.name("id"))
             .and_then(|value| value.as_str().trim().parse().ok())
             .unwrap_or_else(|| id_generator.next());
 
         let name = captures
             .as_ref()
-            .and_then(|caps| caps.get(2))
+            .and_then(|caps| caps.name("name"))
             .map(|mat| mat.as_str().trim().to_string())
             .unwrap_or_default();
 
@@ -74,8 +165,10 @@ where
             name,
             parent_id: None,
             content: Default::default(),
+            status,
             subissues: Default::default(),
             relations: Default::default(),
+            location: None,
         }
     }
 }
@@ -110,6 +203,7 @@ where
             id,
             name,
             needed_issues: Default::default(),
+            location: None,
         }
     }
 }
@@ -117,23 +211,32 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::generator::IntIdGenerator;
 
     #[test]
     fn parse_issue() {
         let id_generator = IntIdGenerator::new(1);
 
-        let issue = <Issue<u64> as ParseLine<&IntIdGenerator>>::parse_line("- task without id", &id_generator);
+        let issue = <Issue<u64> as ParseLine<&IntIdGenerator>>::parse_line("task without id", &id_generator);
         assert_eq!(issue.id, 1);
         assert_eq!(issue.name, "task without id");
 
-        let issue = <Issue<u64> as ParseLine<&IntIdGenerator>>::parse_line("- 25 task with id", &id_generator);
+        let issue = <Issue<u64> as ParseLine<&IntIdGenerator>>::parse_line("25 task with id", &id_generator);
         assert_eq!(issue.id, 25);
         assert_eq!(issue.name, "task with id");
 
-        let issue = <Issue<u64> as ParseLine<&IntIdGenerator>>::parse_line("- 25task without id", &id_generator);
+        let issue = <Issue<u64> as ParseLine<&IntIdGenerator>>::parse_line("25task without id", &id_generator);
         assert_eq!(issue.id, 2);
         assert_eq!(issue.name, "25task without id");
+
+        let issue = <Issue<u64> as ParseLine<&IntIdGenerator>>::parse_line("[x] 26 completed task", &id_generator);
+        assert_eq!(issue.id, 26);
+        assert_eq!(issue.name, "completed task");
+        assert!(issue.is_completed());
+
+        let issue = <Issue<u64> as ParseLine<&IntIdGenerator>>::parse_line("[ ] open task", &id_generator);
+        assert_eq!(issue.id, 3);
+        assert_eq!(issue.name, "open task");
+        assert!(!issue.is_completed());
     }
 
     #[test]
@@ -172,8 +275,10 @@ mod tests {
             ("# Milestone", Item::Milestone(Milestone::new(3, "Milestone"))),
         ];
 
+        let dialect = ListDialect::default();
+        let mut parser = LineParser::new(&dialect);
         for (line, item) in pairs {
-            let parsed_item = Item::parse(line, &id_generator).0;
+            let parsed_item = parser.parse(line, &id_generator).0;
             match (item, parsed_item) {
                 (Item::Empty, Item::Empty) => {},
                 (Item::Separator, Item::Separator) => {},
@@ -188,8 +293,36 @@ mod tests {
                 (Item::Text(text), Item::Text(parsed_text)) => {
                     assert_eq!(text, parsed_text);
                 },
-                _ => assert!(false),
+                _ => panic!("parsed item does not match the expected one"),
             }
         }
     }
+
+    #[test]
+    fn detect_commonmark_levels() {
+        let dialect = ListDialect {
+            bullets: vec!['*', '-'],
+            ordered: true,
+            ..Default::default()
+        };
+        let mut parser = LineParser::new(&dialect);
+        let lines = [
+            ("1. task", (LineKind::Issue, 0)),
+            ("   * subtask", (LineKind::Issue, 1)),
+            ("     description", (LineKind::Text, 2)),
+            ("", (LineKind::Empty, 0)),
+            ("\t\t- subsubtask", (LineKind::Issue, 2)),
+            ("    * subtask", (LineKind::Issue, 1)),
+            ("           * too deep", (LineKind::Issue, 3)),
+            ("2) task", (LineKind::Issue, 0)),
+            ("+ text", (LineKind::Text, 0)),
+            ("  * task", (LineKind::Issue, 0)),
+            ("# Milestone", (LineKind::Milestone, 0)),
+            ("    * too deep", (LineKind::Issue, 1)),
+        ];
+
+        for (line, expected) in lines {
+            assert_eq!(parser.detect(line), expected, "{line:?}");
+        }
+    }
 }
@@ -0,0 +1,246 @@
+use std::collections::HashSet;
+use std::io;
+
+use jiff::civil::{Date, Weekday};
+use jiff::Span;
+use todo_lib::issue::Issue;
+
+use crate::dialect::ListDialect;
+use crate::document::Document;
+use crate::generator::IntIdGenerator;
+use crate::plan::parse::ParseLine;
+use crate::tracker::PlanSource;
+
+pub const RECURRENCE_PREFIX: &str = "every:";
+pub const DUE_DATE_PREFIX: &str = "due:";
+
+/// Repetition rule of the issue, written in its name as `every:<count><unit>` with the `d`, `w`, `m` or `y` unit,
+/// like `every:1w`, or as `every:<weekday>`, like `every:monday` or `every:mon`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Days(u16),
+    Weeks(u16),
+    Months(u16),
+    Years(u16),
+    Weekday(Weekday),
+}
+
+impl Recurrence {
+    /// Parses the rule without the `every:` prefix, the zero count is not a rule.
+    pub fn parse(rule: &str) -> Option<Self> {
+        let rule = rule.to_lowercase();
+        if let Some(weekday) = parse_weekday(&rule) {
+            return Some(Self::Weekday(weekday));
+        }
+
+        let unit_start = rule.find(|ch: char| !ch.is_ascii_digit())?;
+        let count = rule[..unit_start].parse().ok().filter(|count| *count > 0)?;
+        match &rule[unit_start..] {
+            "d" => Some(Self::Days(count)),
+            "w" => Some(Self::Weeks(count)),
+            "m" => Some(Self::Months(count)),
+            "y" => Some(Self::Years(count)),
+            _ => None,
+        }
+    }
+
+    /// The date of the `n`-th occurrence after the anchor date, `None` if it is out of the supported dates. The date
+    /// is counted from the anchor, so the day clamped at the end of a shorter month is not carried to the next ones.
+    pub fn nth_after(&self, anchor: Date, n: i32) -> Option<Date> {
+        let times = |count: u16| i64::from(count) * i64::from(n);
+        let span = match *self {
+            Self::Days(count) => Span::new().try_days(times(count)),
+            Self::Weeks(count) => Span::new().try_weeks(times(count)),
+            Self::Months(count) => Span::new().try_months(times(count)),
+            Self::Years(count) => Span::new().try_years(times(count)),
+            Self::Weekday(weekday) => return anchor.nth_weekday(n, weekday).ok(),
+        };
+        anchor.checked_add(span.ok()?).ok()
+    }
+}
+
+/// Recurrence and due date of the issue, written in its name as the `every:<rule>` and `due:<YYYY-MM-DD>` words.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    pub recurrence: Option<Recurrence>,
+    pub due: Option<Date>,
+}
+
+impl Schedule {
+    pub fn parse(name: &str) -> Self {
+        let mut schedule = Self::default();
+        for word in name.split_whitespace() {
+            if let Some(rule) = word.strip_prefix(RECURRENCE_PREFIX) {
+                schedule.recurrence = schedule.recurrence.or(Recurrence::parse(rule));
+            } else if let Some(date) = word.strip_prefix(DUE_DATE_PREFIX) {
+                schedule.due = schedule.due.or(date.parse().ok());
+            }
+        }
+        schedule
+    }
+}
+
+/// Name of the next occurrence of the recurring issue completed at the given date, `None` if the issue does not
+/// recur. The next due date follows the current due date or the completion date if the issue has no due date,
+/// and it is moved forward by the rule until it comes after the completion date.
+pub fn next_occurrence_name(name: &str, completed_at: Date) -> Option<String> {
+    let Schedule { recurrence, due } = Schedule::parse(name);
+    let recurrence = recurrence?;
+
+    let anchor = due.unwrap_or(completed_at);
+    let mut n = 1;
+    let mut next_due = recurrence.nth_after(anchor, n)?;
+    while next_due <= completed_at {
+        n = n.checked_add(1)?;
+        next_due = recurrence.nth_after(anchor, n)?;
+    }
+
+    let due_word = format!("{DUE_DATE_PREFIX}{next_due}");
+    let mut is_replaced = false;
+    let mut words: Vec<&str> = name.split(' ').collect();
+    for word in &mut words {
+        let is_due_word = word
+            .strip_prefix(DUE_DATE_PREFIX)
+            .is_some_and(|date| date.parse::<Date>().is_ok());
+        if !is_replaced && is_due_word {
+            *word = &due_word;
+            is_replaced = true;
+        }
+    }
+
+    if is_replaced {
+        Some(words.join(" "))
+    } else {
+        Some(format!("{name} {due_word}"))
+    }
+}
+
+/// Inserts the next occurrence of the issue right before it: the copy of the issue lines with its subissues and
+/// descriptions, where the issues are reopened, their explicit ids are dropped and the issue gets the given name.
+pub fn insert_next_occurrence(source: &PlanSource, id: u64, name: &str, dialect: &ListDialect) -> io::Result<()> {
+    let PlanSource { placement, plan, lines } = source;
+    let not_found = || io::Error::new(io::ErrorKind::NotFound, format!("issue `{id}` not found"));
+    let subtree_lines = lines.issue_subtree(plan, &id).ok_or_else(not_found)?;
+
+    let mut headers = HashSet::new();
+    let mut pending = vec![id];
+    while let Some(id) = pending.pop() {
+        let issue = plan.get_issue(&id).ok_or_else(not_found)?;
+        if let Some(location) = lines.get_issue(&id) {
+            headers.insert(location.lines.start);
+        }
+        pending.extend(issue.subissues.iter().copied());
+    }
+
+    let path = placement.as_ref();
+    let mut doc = Document::load(path)?;
+    let occurrence: Vec<String> = subtree_lines
+        .clone()
+        .filter_map(|idx| {
+            let line = doc.line(idx)?;
+            Some(if headers.contains(&idx) {
+                let name = (idx == subtree_lines.start).then_some(name);
+                reopened_header(line, name, dialect)
+            } else {
+                line.to_string()
+            })
+        })
+        .collect();
+
+    doc.insert_lines(subtree_lines.start, occurrence);
+    doc.save(path)
+}
+
+/// Issue line with the open status box if the line has any, without the explicit id and with the new name if given.
+fn reopened_header(line: &str, name: Option<&str>, dialect: &ListDialect) -> String {
+    let content_start = dialect.list_marker(line).map_or(0, |marker| marker.content_start);
+    let Some(captures) = <Issue<u64> as ParseLine<&IntIdGenerator>>::regex().captures(&line[content_start..]) else {
+        return line.to_string();
+    };
+
+    let mut header = line[..content_start].to_string();
+    if captures.name("status").is_some() {
+        header.push_str("[ ] ");
+    }
+    match name {
+        Some(name) => header.push_str(name),
+        None => header.push_str(captures.name("name").map_or("", |name| name.as_str().trim())),
+    }
+    header
+}
+
+fn parse_weekday(name: &str) -> Option<Weekday> {
+    let weekday = match name {
+        "monday" | "mon" => Weekday::Monday,
+        "tuesday" | "tue" => Weekday::Tuesday,
+        "wednesday" | "wed" => Weekday::Wednesday,
+        "thursday" | "thu" => Weekday::Thursday,
+        "friday" | "fri" => Weekday::Friday,
+        "saturday" | "sat" => Weekday::Saturday,
+        "sunday" | "sun" => Weekday::Sunday,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil::date;
+
+    use super::*;
+
+    #[test]
+    fn parse_recurrence() {
+        assert_eq!(Recurrence::parse("1w"), Some(Recurrence::Weeks(1)));
+        assert_eq!(Recurrence::parse("10d"), Some(Recurrence::Days(10)));
+        assert_eq!(Recurrence::parse("3M"), Some(Recurrence::Months(3)));
+        assert_eq!(Recurrence::parse("1y"), Some(Recurrence::Years(1)));
+        assert_eq!(Recurrence::parse("Monday"), Some(Recurrence::Weekday(Weekday::Monday)));
+        assert_eq!(Recurrence::parse("fri"), Some(Recurrence::Weekday(Weekday::Friday)));
+        assert_eq!(Recurrence::parse("0d"), None);
+        assert_eq!(Recurrence::parse("w"), None);
+        assert_eq!(Recurrence::parse("2x"), None);
+        assert_eq!(Recurrence::parse("someday"), None);
+
+        assert_eq!(Schedule::parse("clean the house every:1w due:2026-10-19"), Schedule {
+            recurrence: Some(Recurrence::Weeks(1)),
+            due: Some(date(2026, 10, 19)),
+        });
+        assert_eq!(Schedule::parse("clean the house due:tomorrow"), Schedule::default());
+    }
+
+    #[test]
+    fn next_occurrence() {
+        // 2026-10-19 is Monday
+        let completed_at = date(2026, 10, 19);
+        let next = |name| next_occurrence_name(name, completed_at);
+
+        assert_eq!(next("chore"), None);
+        assert_eq!(next("chore every:1w"), Some("chore every:1w due:2026-10-26".into()));
+        assert_eq!(
+            next("chore every:monday"),
+            Some("chore every:monday due:2026-10-26".into())
+        );
+        assert_eq!(next("chore every:wed"), Some("chore every:wed due:2026-10-21".into()));
+        assert_eq!(
+            next("chore due:2026-10-18 every:1d"),
+            Some("chore due:2026-10-20 every:1d".into())
+        );
+        assert_eq!(
+            next("chore every:1m due:2026-08-31"),
+            Some("chore every:1m due:2026-10-31".into())
+        );
+        assert_eq!(
+            next("chore every:1m due:2026-01-31"),
+            Some("chore every:1m due:2026-10-31".into())
+        );
+        assert_eq!(
+            next("chore every:monday due:2026-09-28"),
+            Some("chore every:monday due:2026-10-26".into())
+        );
+        assert_eq!(
+            next("chore every:1y due:2027-01-01"),
+            Some("chore every:1y due:2028-01-01".into())
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use jiff::{SignedDuration, Timestamp};
+
+use crate::document::Document;
+
+/// Time spent on the issue, written in the time log as the `<issue id> <start> [<stop>]` line with the RFC 3339
+/// timestamps. The entry without the stop time is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeEntry {
+    pub issue_id: u64,
+    pub start: Timestamp,
+    pub stop: Option<Timestamp>,
+}
+
+impl TimeEntry {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let issue_id = words.next()?.parse().ok()?;
+        let start = words.next()?.parse().ok()?;
+        let stop = match words.next() {
+            Some(stop) => Some(stop.parse().ok()?),
+            None => None,
+        };
+
+        words.next().is_none().then_some(Self { issue_id, start, stop })
+    }
+
+    pub fn to_line(&self) -> String {
+        match self.stop {
+            Some(stop) => format!("{} {} {stop}", self.issue_id, self.start),
+            None => format!("{} {}", self.issue_id, self.start),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.stop.is_none()
+    }
+
+    /// Duration of the entry, the running entry lasts until `now`.
+    pub fn duration(&self, now: Timestamp) -> SignedDuration {
+        self.stop.unwrap_or(now).duration_since(self.start)
+    }
+}
+
+/// Reads the entries of the time log, the missing log has no entries. The empty lines are skipped.
+pub fn load_time_log(path: impl AsRef<Path>) -> io::Result<Vec<TimeEntry>> {
+    let path = path.as_ref();
+    let doc = Document::load(path)?;
+
+    doc.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            TimeEntry::parse(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("broken time entry in line {} of `{}`", idx + 1, path.display()),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Starts the entry of the issue at the given time. The running entry is stopped first and returned.
+pub fn start_entry(path: impl AsRef<Path>, issue_id: u64, at: Timestamp) -> io::Result<Option<TimeEntry>> {
+    let path = path.as_ref();
+    let stopped = stop_entry(path, at)?;
+
+    let mut doc = Document::load(path)?;
+    let entry = TimeEntry {
+        issue_id,
+        start: at,
+        stop: None,
+    };
+    doc.insert_lines(doc.lines_count(), [entry.to_line()]);
+    doc.save(path)?;
+
+    Ok(stopped)
+}
+
+/// Stops the running entry at the given time, the stopped entry is returned.
+pub fn stop_entry(path: impl AsRef<Path>, at: Timestamp) -> io::Result<Option<TimeEntry>> {
+    let path = path.as_ref();
+    let mut doc = Document::load(path)?;
+
+    let running = doc
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| TimeEntry::parse(line).map(|entry| (idx, entry)))
+        .filter(|(_, entry)| entry.is_running())
+        .last();
+    let Some((idx, mut entry)) = running else {
+        return Ok(None);
+    };
+
+    entry.stop = Some(at);
+    doc.replace_lines(idx..idx + 1, [entry.to_line()]);
+    doc.save(path)?;
+
+    Ok(Some(entry))
+}
+
+/// Replaces the issue ids of the entries by the new ones, the entries of the ids missing in the map are kept.
+pub fn remap_entries(path: impl AsRef<Path>, ids: &HashMap<u64, u64>) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut doc = Document::load(path)?;
+
+    let remapped: Vec<_> = doc
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let mut entry = TimeEntry::parse(line)?;
+            entry.issue_id = *ids.get(&entry.issue_id)?;
+            Some((idx, entry))
+        })
+        .collect();
+    if remapped.is_empty() {
+        return Ok(());
+    }
+
+    for (idx, entry) in remapped {
+        doc.replace_lines(idx..idx + 1, [entry.to_line()]);
+    }
+    doc.save(path)
+}
@@ -1,37 +1,60 @@
 use std::collections::HashMap;
 use std::io;
-use std::path::{Path, PathBuf};
+use std::path::{self, Path, PathBuf};
+use std::sync::OnceLock;
 
 use indexmap::{IndexMap, IndexSet};
+use rayon::prelude::*;
 use regex::Regex;
 use todo_lib::id::HashedId;
 use todo_lib::plan::Plan;
 use todo_lib::project::Project;
 
-use crate::config::FsProjectConfig;
-use crate::file::find_by_regex;
+use crate::config::{ConfigFormat, FsProjectConfig, SourceOverrides};
+use crate::diagnostic::Diagnostic;
+use crate::dialect::ListDialect;
+use crate::discovery::DiscoveryRules;
+use crate::file::{find_by_globs, find_by_regex};
 use crate::generator::IntIdGenerator;
-use crate::plan::LoadProjectPlan;
+use crate::plan::{LoadProjectPlan, PlanLines, StepsCount};
 use crate::project::LoadProject;
 use crate::Placement;
 
-pub struct FsTracker<PID = String, ID = u64> {
+/// Directories skipped while searching for the issues files of the project.
+const ISSUES_FILES_EXCLUDE: [&str; 3] = [".git", "node_modules", "target"];
+
+/// Plan of the project loaded skipping over the broken lines, which are diagnosed.
+#[derive(Debug)]
+struct LoadedPlan<ID> {
+    plan: Option<Plan<ID>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+pub struct FsTracker<PID: HashedId = String, ID = u64> {
     projects: IndexMap<PID, Project<PID>>,
     project_root_dirs: HashMap<PID, PathBuf>,
     parents: IndexMap<PID, PID>,
-    planes: HashMap<PID, Plan<ID>>,
+    planes: HashMap<PID, OnceLock<LoadedPlan<ID>>>,
+    configs: HashMap<PID, FsProjectConfig<PID>>,
+    manifest_filename_regex: Regex,
+    todo_filename_regex: Regex,
+    dialect: ListDialect,
+    archive_file: Option<PathBuf>,
 }
 
 impl<PID: HashedId + Clone> FsTracker<PID> {
+    /// Creates the tracker of the projects. The plans are not read here, they are loaded on the first access.
     pub fn new(
         project_configs: IndexMap<PID, FsProjectConfig<PID>>,
         manifest_filename_regex: &Regex,
         todo_filename_regex: &Regex,
-    ) -> io::Result<Self> {
+        dialect: &ListDialect,
+    ) -> Self {
         let mut projects = IndexMap::new();
         let mut project_root_dirs = HashMap::new();
         let mut parents = IndexMap::new();
         let mut planes = HashMap::new();
+        let mut configs = HashMap::new();
 
         for (parent_id, config) in &project_configs {
             parents.extend(config.subprojects.iter().cloned().map(|id| (id, parent_id.clone())));
@@ -39,21 +62,32 @@ impl<PID: HashedId + Clone> FsTracker<PID> {
 
         for (id, config) in project_configs {
             if let Some(project_root) = config.root_dir.clone() {
-                if let Some(plan) = load_project_plan(&config, manifest_filename_regex, todo_filename_regex)? {
-                    planes.insert(id.clone(), plan);
-                }
+                planes.insert(id.clone(), OnceLock::new());
                 project_root_dirs.insert(id.clone(), project_root);
+                configs.insert(id.clone(), config.clone());
             }
             let project = Project::load(id.clone(), parents.get(&id).cloned(), config);
             projects.insert(id, project);
         }
 
-        Ok(Self {
+        Self {
             projects,
             project_root_dirs,
             parents,
             planes,
-        })
+            configs,
+            manifest_filename_regex: manifest_filename_regex.clone(),
+            todo_filename_regex: todo_filename_regex.clone(),
+            dialect: dialect.clone(),
+            archive_file: None,
+        }
+    }
+
+    /// Makes the tracker read the plans of the projects from their archive files instead of the project files,
+    /// the path of the archive file is relative to the project root.
+    pub fn with_archive(mut self, archive_file: impl Into<PathBuf>) -> Self {
+        self.archive_file = Some(archive_file.into());
+        self
     }
 
     pub fn projects(&self) -> &IndexMap<PID, Project<PID>> {
@@ -77,50 +111,343 @@ impl<PID: HashedId + Clone> FsTracker<PID> {
         subprojects
     }
 
-    pub fn project_plan(&self, id: &PID) -> Option<&Plan<u64>> {
-        self.planes.get(id)
+    /// Returns the plan of the project, loading it from the project files on the first call. The broken lines
+    /// of the files are skipped and reported by `project_diagnostics`. The failed reading of the files is not
+    /// cached, so the next call reads the files again.
+    pub fn project_plan(&self, id: &PID) -> io::Result<Option<&Plan<u64>>> {
+        Ok(self.loaded_plan(id)?.and_then(|loaded| loaded.plan.as_ref()))
+    }
+
+    /// Problems found in the project files while loading the plan, which is loaded if it is not yet.
+    pub fn project_diagnostics(&self, id: &PID) -> io::Result<&[Diagnostic]> {
+        Ok(self
+            .loaded_plan(id)?
+            .map(|loaded| loaded.diagnostics.as_slice())
+            .unwrap_or_default())
+    }
+
+    /// Problems found in the project files if the plan is already loaded, the plan is not loaded by the call.
+    pub fn loaded_project_diagnostics(&self, id: &PID) -> &[Diagnostic] {
+        self.planes
+            .get(id)
+            .and_then(OnceLock::get)
+            .map(|loaded| loaded.diagnostics.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn loaded_plan(&self, id: &PID) -> io::Result<Option<&LoadedPlan<u64>>> {
+        let (Some(plan), Some(config)) = (self.planes.get(id), self.configs.get(id)) else {
+            return Ok(None);
+        };
+
+        if let Some(loaded) = plan.get() {
+            return Ok(Some(loaded));
+        }
+        let (loaded, diagnostics) = match &self.archive_file {
+            Some(archive_file) => load_project_archive_diagnosed(config, archive_file, &self.dialect)?,
+            None => load_project_plan_diagnosed(
+                config,
+                &self.manifest_filename_regex,
+                &self.todo_filename_regex,
+                &self.dialect,
+            )?,
+        };
+        Ok(Some(plan.get_or_init(|| LoadedPlan {
+            plan: loaded,
+            diagnostics,
+        })))
+    }
+
+    /// Loads the plans of all projects in parallel, the plans already loaded are kept.
+    pub fn load_plans(&self) -> io::Result<()>
+    where
+        PID: Sync,
+    {
+        let results: Vec<_> = self
+            .projects
+            .keys()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|id| self.project_plan(id).map(|_| ()))
+            .collect();
+
+        // Results are collected in the order of the projects, so the first error is reported the same way
+        // as by the sequential loading
+        results.into_iter().collect()
+    }
+
+    /// Counts the steps of the project. The already loaded plan is used if any, otherwise the project files
+    /// are scanned without building the plan.
+    pub fn project_steps_count(&self, id: &PID) -> io::Result<StepsCount> {
+        if let Some(loaded) = self.planes.get(id).and_then(OnceLock::get) {
+            return Ok(loaded.plan.as_ref().map(StepsCount::of_plan).unwrap_or_default());
+        }
+        let Some(project_root) = self.project_root_dir(id) else {
+            return Ok(StepsCount::default());
+        };
+
+        let default_source = SourceOverrides::default();
+        let source = self.configs.get(id).map_or(&default_source, |config| &config.source);
+        let dialect = source.list(&self.dialect);
+        if let Some(archive_file) = &self.archive_file {
+            return StepsCount::of_source(&Placement::WholeFile(project_root.join(archive_file)), dialect);
+        }
+
+        plan_placements(
+            project_root,
+            source,
+            &self.manifest_filename_regex,
+            &self.todo_filename_regex,
+        )?
+        .into_iter()
+        .try_fold(StepsCount::default(), |count, (_, placement)| {
+            Ok(count + StepsCount::of_source(&placement, dialect)?)
+        })
+    }
+
+    /// Files the plan of the project is loaded from, they are searched again on each call.
+    pub fn project_source_paths(&self, id: &PID) -> io::Result<Vec<PathBuf>> {
+        let Some(project_root) = self.project_root_dir(id) else {
+            return Ok(Vec::new());
+        };
+        if let Some(archive_file) = &self.archive_file {
+            return Ok(vec![project_root.join(archive_file)]);
+        }
+
+        let default_source = SourceOverrides::default();
+        let source = self.configs.get(id).map_or(&default_source, |config| &config.source);
+        Ok(plan_placements(
+            project_root,
+            source,
+            &self.manifest_filename_regex,
+            &self.todo_filename_regex,
+        )?
+        .into_iter()
+        .map(|(_, placement)| placement.as_ref().clone())
+        .collect())
+    }
+
+    /// Config of the project with the root directory.
+    pub fn project_config(&self, id: &PID) -> Option<&FsProjectConfig<PID>> {
+        self.configs.get(id)
     }
 
     pub fn project_root_dir(&self, id: &PID) -> Option<&Path> {
         self.project_root_dirs.get(id).map(|path| path.as_path())
     }
+
+    /// Root directories of the projects, which contain the project config, manifest and issues files.
+    pub fn project_root_dirs(&self) -> impl Iterator<Item = (&PID, &Path)> {
+        self.project_root_dirs.iter().map(|(id, path)| (id, path.as_path()))
+    }
+
+    /// Finds the project with the deepest root directory containing the path.
+    pub fn find_project_by_path(&self, path: impl AsRef<Path>) -> Option<&PID> {
+        let path = path::absolute(path).ok()?;
+        self.project_root_dirs
+            .iter()
+            .filter_map(|(id, root_dir)| Some((id, path::absolute(root_dir).ok()?)))
+            .filter(|(_, root_dir)| path.starts_with(root_dir))
+            .max_by_key(|(_, root_dir)| root_dir.components().count())
+            .map(|(id, _)| id)
+    }
+
+    /// Loads the plan of the project from its files again, keeping the rest of the projects untouched.
+    /// On error the plan is dropped and will be loaded again on the next access.
+    pub fn reload_project_plan(&mut self, id: &PID) -> io::Result<()> {
+        let Some(plan) = self.planes.get_mut(id) else {
+            return Ok(());
+        };
+
+        plan.take();
+        self.project_plan(id).map(|_| ())
+    }
+}
+
+/// Plan loaded from a single project source together with the file positions of its items.
+#[derive(Debug)]
+pub struct PlanSource<ID = u64> {
+    pub placement: Placement<PathBuf>,
+    pub plan: Plan<ID>,
+    pub lines: PlanLines<ID>,
 }
 
 pub fn load_project_plan<PID>(
     project_config: &FsProjectConfig<PID>,
     manifest_filename_regex: &Regex,
     issues_filename_regex: &Regex,
+    dialect: &ListDialect,
 ) -> io::Result<Option<Plan<u64>>>
+where
+    PID: HashedId,
+{
+    let sources = load_project_plan_sources(project_config, manifest_filename_regex, issues_filename_regex, dialect)?;
+    Ok(merge_sources(sources))
+}
+
+/// Same as `load_project_plan`, but the broken lines of the project files are skipped and returned
+/// as the diagnostics instead of failing.
+pub fn load_project_plan_diagnosed<PID>(
+    project_config: &FsProjectConfig<PID>,
+    manifest_filename_regex: &Regex,
+    issues_filename_regex: &Regex,
+    dialect: &ListDialect,
+) -> io::Result<(Option<Plan<u64>>, Vec<Diagnostic>)>
+where
+    PID: HashedId,
+{
+    let (sources, diagnostics) =
+        load_project_plan_sources_diagnosed(project_config, manifest_filename_regex, issues_filename_regex, dialect)?;
+    Ok((merge_sources(sources), diagnostics))
+}
+
+/// Loads the plan of the project archive file the same way as the plans of the project files, `None` is returned
+/// if the project has nothing archived yet.
+pub fn load_project_archive_diagnosed<PID>(
+    project_config: &FsProjectConfig<PID>,
+    archive_file: impl AsRef<Path>,
+    dialect: &ListDialect,
+) -> io::Result<(Option<Plan<u64>>, Vec<Diagnostic>)>
+where
+    PID: HashedId,
+{
+    let Some(project_root) = &project_config.root_dir else {
+        return Ok((None, Vec::new()));
+    };
+    let id_generator = IntIdGenerator::new(project_config.start_id.unwrap_or(1));
+    let dialect = project_config.source.list(dialect);
+    let placement = Placement::WholeFile(project_root.join(archive_file));
+
+    match Plan::load_diagnosed(&placement, &id_generator, dialect)? {
+        Some((plan, _, diagnostics)) => Ok((Some(plan), diagnostics)),
+        None => Ok((None, Vec::new())),
+    }
+}
+
+/// Loads the plans of the project files, the source overrides of the project config take precedence over
+/// the given file name regexes and list dialect. The plan of each issues file found by the glob patterns makes
+/// the section named by the file path relative to the project root.
+pub fn load_project_plan_sources<PID>(
+    project_config: &FsProjectConfig<PID>,
+    manifest_filename_regex: &Regex,
+    issues_filename_regex: &Regex,
+    dialect: &ListDialect,
+) -> io::Result<Vec<PlanSource>>
+where
+    PID: HashedId,
+{
+    let (sources, diagnostics) =
+        load_project_plan_sources_diagnosed(project_config, manifest_filename_regex, issues_filename_regex, dialect)?;
+    match diagnostics.into_iter().find(Diagnostic::is_error) {
+        Some(diagnostic) => Err(diagnostic.into()),
+        None => Ok(sources),
+    }
+}
+
+/// Same as `load_project_plan_sources`, but the broken lines of the project files are skipped and returned
+/// as the diagnostics instead of failing.
+pub fn load_project_plan_sources_diagnosed<PID>(
+    project_config: &FsProjectConfig<PID>,
+    manifest_filename_regex: &Regex,
+    issues_filename_regex: &Regex,
+    dialect: &ListDialect,
+) -> io::Result<(Vec<PlanSource>, Vec<Diagnostic>)>
 where
     PID: HashedId,
 {
     let Some(project_root) = project_config.root_dir.clone() else {
-        return Ok(None);
+        return Ok((Vec::new(), Vec::new()));
     };
     let id_generator = IntIdGenerator::new(project_config.start_id.unwrap_or(1));
-    let mut plan = Plan::new();
-    let mut plan_exists = false;
+    let dialect = project_config.source.list(dialect);
+    let placements = plan_placements(
+        &project_root,
+        &project_config.source,
+        manifest_filename_regex,
+        issues_filename_regex,
+    )?;
+    let mut sources = Vec::new();
+    let mut diagnostics = Vec::new();
 
-    if let Some(manifest_source) = find_by_regex(&project_root, manifest_filename_regex).map(Placement::CodeBlockInFile)
-    {
-        plan = match Plan::load(&manifest_source, &id_generator)? {
-            Some(plan) => plan,
-            None => return Ok(None),
+    for (section, placement) in placements {
+        let Some((mut plan, lines, plan_diagnostics)) = Plan::load_diagnosed(&placement, &id_generator, dialect)?
+        else {
+            return Ok((Vec::new(), diagnostics));
         };
-        plan_exists = true;
+        add_file_section(&mut plan, section);
+        diagnostics.extend(plan_diagnostics);
+        sources.push(PlanSource { placement, plan, lines });
     }
 
-    if let Some(issues_source) = find_by_regex(&project_root, issues_filename_regex).map(Placement::WholeFile) {
-        plan = plan.merge(match Plan::load(&issues_source, &id_generator)? {
-            Some(plan) => plan,
-            None => return Ok(None),
-        });
-        plan_exists = true;
+    Ok((sources, diagnostics))
+}
+
+fn merge_sources(sources: Vec<PlanSource>) -> Option<Plan<u64>> {
+    sources
+        .into_iter()
+        .map(|source| source.plan)
+        .reduce(|plan, other| plan.merge(other))
+}
+
+/// Makes the whole plan of the issues file the section named by the file.
+fn add_file_section(plan: &mut Plan<u64>, section: Option<String>) {
+    if let Some(section) = section {
+        let steps = plan.steps().clone();
+        plan.add_to_section(section, steps);
     }
+}
+
+/// Files of the project plan with the section names of the issues files found by the glob patterns.
+fn plan_placements(
+    project_root: &Path,
+    source: &SourceOverrides,
+    manifest_filename_regex: &Regex,
+    issues_filename_regex: &Regex,
+) -> io::Result<Vec<(Option<String>, Placement<PathBuf>)>> {
+    let manifest_file = find_by_regex(project_root, source.manifest_filename_regex(manifest_filename_regex));
+    let mut placements: Vec<_> = manifest_file
+        .iter()
+        .map(|path| (None, Placement::CodeBlockInFile(path.clone())))
+        .collect();
 
-    if plan_exists {
-        Ok(Some(plan))
+    if source.issues_files.is_empty() {
+        if let Some(path) = find_by_regex(project_root, source.issues_filename_regex(issues_filename_regex)) {
+            placements.push((None, Placement::WholeFile(path)));
+        }
     } else {
-        Ok(None)
+        let files = find_issues_files(
+            project_root,
+            &source.issues_files,
+            source.manifest_filename_regex(manifest_filename_regex),
+        )?;
+        for path in files.into_iter().filter(|path| Some(path) != manifest_file.as_ref()) {
+            let section = path.strip_prefix(project_root).unwrap_or(&path).display().to_string();
+            placements.push((Some(section), Placement::WholeFile(path)));
+        }
     }
+
+    Ok(placements)
+}
+
+/// Finds the issues files of the project by the glob patterns relative to the project root. The `.git`,
+/// `node_modules` and `target` directories are skipped, as well as the subprojects, which root holds a manifest
+/// or a `Project` config file.
+pub fn find_issues_files(
+    project_root: &Path,
+    patterns: &[String],
+    manifest_filename_regex: &Regex,
+) -> io::Result<Vec<PathBuf>> {
+    let invalid_input = |err| io::Error::new(io::ErrorKind::InvalidInput, err);
+    let rules = DiscoveryRules::new()
+        .with_exclude(ISSUES_FILES_EXCLUDE)
+        .map_err(invalid_input)?;
+    let is_subproject_root = |dir: &Path| {
+        find_by_regex(dir, manifest_filename_regex).is_some()
+            || ConfigFormat::ALL
+                .iter()
+                .flat_map(ConfigFormat::extensions)
+                .any(|extension| dir.join("Project").with_extension(extension).is_file())
+    };
+
+    find_by_globs(project_root, patterns, &rules, is_subproject_root).map_err(invalid_input)
 }
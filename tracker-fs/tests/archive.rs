@@ -0,0 +1,85 @@
+use std::fs;
+
+use indexmap::{IndexMap, IndexSet};
+use regex::Regex;
+use temp_testdir::TempDir;
+use todo_lib::plan::{Plan, Step};
+use todo_tracker_fs::archive::{append_to_archive, finished_steps, remove_steps, steps_lines};
+use todo_tracker_fs::config::FsProjectConfig;
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::generator::IntIdGenerator;
+use todo_tracker_fs::plan::LoadProjectPlan;
+use todo_tracker_fs::tracker::PlanSource;
+use todo_tracker_fs::{FsTracker, Placement};
+
+static TASK_LIST_TEXT: &str = "- [x] task A
+  - [x] task AA
+- [x] task B
+  - [ ] task BA
+
+# Mile 1
+
+- [x] task C
+- [ ] task D
+
+# Mile 2
+
+- [x] task E
+";
+
+#[test]
+fn find_finished_steps() {
+    let plan = Plan::load_from_lines(
+        TASK_LIST_TEXT.lines().map(|line| Ok(line.to_string())).enumerate(),
+        &IntIdGenerator::new(1),
+        &ListDialect::default(),
+    )
+    .unwrap();
+
+    assert_eq!(finished_steps(&plan), [Step::Issue(1), Step::Issue(6), Step::Issue(9)]);
+}
+
+#[test]
+fn archive_finished_steps() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    let archive_path = temp_dir.join("old").join("ARCHIVE.md");
+    fs::write(
+        &path,
+        "- [x] task A\n  - [x] task AA\n\n# Mile 1\n\n- [ ] task B\n- [x] task C\n",
+    )
+    .unwrap();
+
+    let placement = Placement::WholeFile(path.clone());
+    let (plan, lines) = Plan::load_located(&placement, &IntIdGenerator::new(1), &ListDialect::default())
+        .unwrap()
+        .unwrap();
+    let steps = finished_steps(&plan);
+    assert_eq!(steps, [Step::Issue(1), Step::Milestone(3), Step::Issue(5)]);
+
+    let source = PlanSource { placement, plan, lines };
+    let archived = steps_lines(&source, &steps).unwrap();
+    remove_steps(&source, &steps).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "- [ ] task B\n");
+
+    append_to_archive(&archive_path, "2026-10-01", &archived).unwrap();
+    append_to_archive(&archive_path, "2026-10-02", &["- [x] task D".to_string()]).unwrap();
+    assert_eq!(
+        fs::read_to_string(&archive_path).unwrap(),
+        "## 2026-10-01\n\n- [x] task A\n  - [x] task AA\n\n# Mile 1\n\n- [x] task C\n\n## 2026-10-02\n\n- [x] task D\n"
+    );
+
+    let manifest_regex = Regex::new(r"\.manifest\.md$").unwrap();
+    let issues_regex = Regex::new(r"^TODO\.md$").unwrap();
+    let configs = IndexMap::from([("project".to_string(), FsProjectConfig {
+        id: "project".to_string(),
+        root_dir: Some(temp_dir.to_path_buf()),
+        ..Default::default()
+    })]);
+    let tracker =
+        FsTracker::new(configs, &manifest_regex, &issues_regex, &ListDialect::default()).with_archive("old/ARCHIVE.md");
+    let plan = tracker.project_plan(&"project".to_string()).unwrap().unwrap();
+    assert_eq!(plan.steps().len(), 5);
+    assert_eq!(plan.get_milestone(&3).unwrap().needed_issues, IndexSet::from([1, 2]));
+    assert!(tracker.project_diagnostics(&"project".to_string()).unwrap().is_empty());
+}
@@ -0,0 +1,64 @@
+use std::cell::Cell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use temp_testdir::TempDir;
+use todo_tracker_fs::cache::DiscoveryCache;
+use todo_tracker_fs::config::find_projects_cached;
+use todo_tracker_fs::discovery::DiscoveryRules;
+use todo_tracker_fs::Placement;
+
+fn create_project(dir: &Path, id: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("Project.toml"), format!("id = \"{id}\"\n")).unwrap();
+}
+
+#[test]
+fn refresh_lists_only_changed_dirs() {
+    let temp_dir = TempDir::default();
+    let root = temp_dir.join("projects");
+    create_project(&root.join("a"), "a");
+    create_project(&root.join("a").join("b"), "b");
+    fs::create_dir_all(root.join("other").join("deep")).unwrap();
+
+    let reads = Cell::new(0);
+    let get_placement = |path: &Path| -> Option<Placement<PathBuf>> {
+        reads.set(reads.get() + 1);
+        let config = path.join("Project.toml");
+        config.exists().then_some(Placement::WholeFile(config))
+    };
+
+    let rules = DiscoveryRules::new();
+    let mut cache = DiscoveryCache::default();
+    let projects = find_projects_cached::<String>([&root], &mut cache, &rules, get_placement);
+    assert_eq!(projects.keys().collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(projects["a"].subprojects.iter().collect::<Vec<_>>(), ["b"]);
+    assert_eq!(reads.get(), 5);
+
+    let cache_file = temp_dir.join("cache").join("projects.json");
+    cache.save(&cache_file).unwrap();
+    let mut cache = DiscoveryCache::load(&cache_file).unwrap();
+    assert_eq!(cache.len(), 5);
+
+    // The listing of the unchanged directory is taken from the cache
+    let other = root.join("other");
+    let other_modified = fs::metadata(&other).unwrap().modified().unwrap();
+    create_project(&other.join("hidden"), "hidden");
+    fs::File::open(&other).unwrap().set_modified(other_modified).unwrap();
+    let projects = find_projects_cached::<String>([&root], &mut cache, &rules, get_placement);
+    assert_eq!(projects.keys().collect::<Vec<_>>(), ["a", "b"]);
+
+    // The project configs are looked up again, so the changed source settings are applied
+    let projects = find_projects_cached::<String>([&root], &mut cache, &rules, |_: &Path| None);
+    assert!(projects.is_empty());
+
+    create_project(&other.join("c"), "c");
+    fs::remove_dir_all(other.join("deep")).unwrap();
+    let projects = find_projects_cached::<String>([&root], &mut cache, &rules, get_placement);
+    assert_eq!(projects.keys().collect::<Vec<_>>(), ["a", "b", "c", "hidden"]);
+    assert_eq!(cache.len(), 6);
+
+    DiscoveryCache::clear(&cache_file).unwrap();
+    assert!(!cache_file.exists());
+    assert!(DiscoveryCache::load(&cache_file).unwrap().is_empty());
+}
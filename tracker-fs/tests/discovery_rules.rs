@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use temp_testdir::TempDir;
+use todo_tracker_fs::config::find_projects;
+use todo_tracker_fs::discovery::DiscoveryRules;
+use todo_tracker_fs::Placement;
+
+fn create_project(dir: &Path, id: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("Project.toml"), format!("id = \"{id}\"\n")).unwrap();
+}
+
+fn get_placement(path: &Path) -> Option<Placement<PathBuf>> {
+    let config = path.join("Project.toml");
+    config.exists().then_some(Placement::WholeFile(config))
+}
+
+fn found_ids(root: &Path, rules: &DiscoveryRules) -> Vec<String> {
+    find_projects::<String>([root], rules, get_placement)
+        .into_keys()
+        .collect()
+}
+
+#[test]
+fn exclude_and_max_depth() {
+    let temp_dir = TempDir::default();
+    let root = temp_dir.join("projects");
+    create_project(&root.join("a"), "a");
+    create_project(&root.join("a").join("target").join("b"), "b");
+    create_project(&root.join("c").join("node_modules").join("d"), "d");
+    create_project(&root.join("c").join("deep").join("e"), "e");
+
+    assert_eq!(found_ids(&root, &DiscoveryRules::new()), ["a", "b", "e", "d"]);
+
+    let rules = DiscoveryRules::new().with_exclude(["target", "c/deep"]).unwrap();
+    assert_eq!(found_ids(&root, &rules), ["a", "d"]);
+
+    let rules = DiscoveryRules::new().with_max_depth(Some(2));
+    assert_eq!(found_ids(&root, &rules), ["a"]);
+
+    let rules = DiscoveryRules::new().with_max_depth(Some(3));
+    assert_eq!(found_ids(&root, &rules), ["a", "b", "e", "d"]);
+
+    assert!(DiscoveryRules::new().with_exclude(["a{"]).is_err());
+}
+
+#[test]
+fn ignore_files() {
+    let temp_dir = TempDir::default();
+    let root = temp_dir.join("projects");
+    create_project(&root.join("a"), "a");
+    create_project(&root.join("a").join("build").join("b"), "b");
+    create_project(&root.join("a").join("build").join("keep").join("c"), "c");
+    create_project(&root.join("d"), "d");
+    fs::write(root.join(".gitignore"), "build/\n").unwrap();
+    fs::write(root.join("a").join(".todoignore"), "/d\n").unwrap();
+    fs::write(root.join(".todoignore"), "/d\n").unwrap();
+
+    assert_eq!(found_ids(&root, &DiscoveryRules::new()), ["a", "b", "c", "d"]);
+
+    let rules = DiscoveryRules::new().with_ignore_files(true);
+    assert_eq!(found_ids(&root, &rules), ["a"]);
+
+    fs::write(root.join("a").join(".gitignore"), "!build/\nbuild/keep/\n").unwrap();
+    assert_eq!(found_ids(&root, &rules), ["a", "b"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_cycles() {
+    let temp_dir = TempDir::default();
+    let root = temp_dir.join("projects");
+    create_project(&root.join("a"), "a");
+    create_project(&temp_dir.join("outside").join("b"), "b");
+    std::os::unix::fs::symlink(&root, root.join("a").join("loop")).unwrap();
+    std::os::unix::fs::symlink(temp_dir.join("outside"), root.join("a").join("link")).unwrap();
+
+    assert_eq!(found_ids(&root, &DiscoveryRules::new()), ["a", "b"]);
+
+    let root = temp_dir.join("mutual");
+    create_project(&root.join("x"), "x");
+    create_project(&root.join("y"), "y");
+    std::os::unix::fs::symlink(root.join("y"), root.join("x").join("to_y")).unwrap();
+    std::os::unix::fs::symlink(root.join("x"), root.join("y").join("to_x")).unwrap();
+
+    assert_eq!(found_ids(&root, &DiscoveryRules::new()), ["x", "y"]);
+}
@@ -0,0 +1,321 @@
+use std::fs;
+
+use proptest::prelude::*;
+use temp_testdir::TempDir;
+use todo_lib::issue::Issue;
+use todo_lib::plan::{Plan, Step};
+use todo_tracker_fs::config::FsProjectConfig;
+use todo_tracker_fs::diagnostic::Diagnostic;
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::document::Document;
+use todo_tracker_fs::format::format_document;
+use todo_tracker_fs::generator::IntIdGenerator;
+use todo_tracker_fs::issue::SaveIssue;
+use todo_tracker_fs::plan::parse::LineParser;
+use todo_tracker_fs::plan::LoadProjectPlan;
+use todo_tracker_fs::Placement;
+
+/// Lines looking like the plan items, the prose and the code block fences.
+fn plan_line() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(String::new()),
+        Just("---".to_string()),
+        Just("```md todo".to_string()),
+        Just("```".to_string()),
+        "[ ]{0,6}[-+] (\\[[ xX]\\] )?([0-9]{1,3} )?[a-z ]{0,12}",
+        "# [a-z0-9 ]{0,12}",
+        "[ ]{0,6}[a-zA-Z<>`*#]{1,3}[a-z ]{0,12}",
+    ]
+}
+
+fn plan_text() -> impl Strategy<Value = String> {
+    (
+        prop::collection::vec(plan_line(), 0..24),
+        prop_oneof![Just("\n"), Just("\r\n")],
+        any::<bool>(),
+    )
+        .prop_map(|(lines, newline, terminated)| {
+            let mut text = lines.join(newline);
+            if terminated && !text.is_empty() {
+                text.push_str(newline);
+            }
+            text
+        })
+}
+
+proptest! {
+    #[test]
+    fn print_parsed_text_as_is(text in any::<String>()) {
+        prop_assert_eq!(Document::parse(text.clone()).to_string(), text);
+    }
+
+    #[test]
+    fn print_parsed_plan_as_is(text in plan_text()) {
+        let doc = Document::parse(text.clone());
+        prop_assert_eq!(doc.as_str(), text.as_str());
+
+        let mut unchanged = doc.clone();
+        unchanged.set_lines(doc.lines().map(ToString::to_string).collect::<Vec<_>>());
+        prop_assert_eq!(unchanged.as_str(), text.as_str());
+    }
+
+    #[test]
+    fn format_idempotently(text in plan_text()) {
+        let doc = Document::parse(text);
+        for placement in [Placement::WholeFile(()), Placement::CodeBlockInFile(())] {
+            let dialect = ListDialect::default();
+            let formatted = format_document(&doc, &placement, &dialect);
+            prop_assert_eq!(format_document(&formatted, &placement, &dialect), formatted);
+        }
+    }
+
+    #[test]
+    fn keep_parsed_plan_on_format(text in plan_text()) {
+        let dialect = ListDialect::default();
+        let doc = Document::parse(text);
+        let (steps, diagnostics) = parsed_steps(&doc, &dialect);
+        prop_assume!(diagnostics.iter().all(|diagnostic| !diagnostic.is_error()));
+
+        let formatted = format_document(&doc, &Placement::WholeFile(()), &dialect);
+        prop_assert_eq!(parsed_steps(&formatted, &dialect).0, steps);
+    }
+
+    #[test]
+    fn keep_item_spans(text in plan_text()) {
+        let doc = Document::parse(text);
+        for placement in [Placement::WholeFile(()), Placement::CodeBlockInFile(())] {
+            let dialect = ListDialect::default();
+            let mut parser = LineParser::new(&dialect);
+            for item in doc.plan_items(&placement, &dialect) {
+                let line = doc.line(item.line).unwrap();
+                prop_assert_eq!(&doc.as_str()[item.span.clone()], line);
+                prop_assert_eq!(parser.detect(line), (item.kind, item.level));
+            }
+        }
+    }
+
+    #[test]
+    fn keep_untouched_bytes_on_replace(
+        text in plan_text(),
+        start in 0..32_usize,
+        len in 0..4_usize,
+        new_lines in prop::collection::vec(plan_line(), 0..4),
+    ) {
+        let mut doc = Document::parse(text.clone());
+        let start = start.min(doc.lines_count());
+        let end = (start + len).min(doc.lines_count());
+        let prefix = &text[..line_offset(&text, start)];
+        let suffix = &text[line_offset(&text, end)..];
+
+        doc.replace_lines(start..end, &new_lines);
+
+        let new_text = doc.to_string();
+        prop_assert!(new_text.starts_with(prefix));
+        prop_assert!(new_text.ends_with(suffix));
+        let replaced: Vec<_> = doc.lines().skip(start).take(new_lines.len()).collect();
+        prop_assert_eq!(replaced, new_lines.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn restore_text_after_insert_and_remove(
+        text in plan_text(),
+        idx in 0..32_usize,
+        new_lines in prop::collection::vec(plan_line(), 1..4),
+    ) {
+        let mut doc = Document::parse(text.clone());
+        let idx = idx.min(doc.lines_count());
+        if idx == doc.lines_count() && !text.is_empty() && !text.ends_with('\n') {
+            // Appending to the not terminated last line adds its terminator
+            return Ok(());
+        }
+
+        doc.insert_lines(idx, &new_lines);
+        doc.remove_lines(idx..idx + new_lines.len());
+        prop_assert_eq!(doc.to_string(), text);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ParsedStep {
+    Issue(Issue<u64>),
+    Milestone(String),
+}
+
+/// Steps of the plan parsed from all lines of the document with the diagnostics of the parsing.
+fn parsed_steps(doc: &Document, dialect: &ListDialect) -> (Vec<ParsedStep>, Vec<Diagnostic>) {
+    let lines = doc.lines().enumerate().map(|(idx, line)| (idx, Ok(line.to_string())));
+    let (plan, _, diagnostics) = Plan::load_diagnosed_from_lines(lines, &IntIdGenerator::new(1), dialect).unwrap();
+    let steps = plan
+        .steps()
+        .iter()
+        .filter_map(|step| match step {
+            Step::Issue(id) => plan.get_issue(id).cloned().map(ParsedStep::Issue),
+            Step::Milestone(id) => plan
+                .get_milestone(id)
+                .map(|milestone| ParsedStep::Milestone(milestone.name.clone())),
+        })
+        .collect();
+    (steps, diagnostics)
+}
+
+/// Byte offset of the line with the given index.
+fn line_offset(text: &str, idx: usize) -> usize {
+    text.split_inclusive('\n').take(idx).map(str::len).sum()
+}
+
+#[test]
+fn add_issue_keeping_manifest_text() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("project.manifest.md");
+    let manifest = "# Project\r\n\r\nSome *prose*.\r\n\r\n```md todo\r\n- task 1\r\n```\r\n\r\nTrailing text";
+    fs::write(&path, manifest).unwrap();
+
+    let placement = Placement::CodeBlockInFile(path.clone());
+    Issue::new(2, "task 2")
+        .add_last(&placement, None, &ListDialect::default())
+        .unwrap();
+    Issue::new(3, "task 0")
+        .add_first(&placement, None, &ListDialect::default())
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Project\r\n\r\nSome *prose*.\r\n\r\n```md todo\r\n- task 0\r\n- task 1\r\n- task 2\r\n```\r\n\r\nTrailing text"
+    );
+}
+
+#[test]
+fn add_issue_to_section() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("project.manifest.md");
+    fs::write(
+        &path,
+        "# Project\n\n```md todo\n- task 1\n```\n\n```md todo sprint\n- task 2\n```\n",
+    )
+    .unwrap();
+
+    let dialect = ListDialect::default();
+    let placement = Placement::CodeBlockInFile(path.clone());
+    let added = Issue::new(3, "task 3")
+        .add_first(&placement, Some("sprint"), &dialect)
+        .unwrap();
+    assert_eq!(added, 7..8);
+    let added = Issue::new(4, "task 4")
+        .add_last(&placement, Some("backlog"), &dialect)
+        .unwrap();
+    assert_eq!(added, 11..12);
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Project\n\n```md todo\n- task 1\n```\n\n```md todo sprint\n- task 3\n- task 2\n```\n```md todo backlog\n- task 4\n```\n"
+    );
+
+    let err = Issue::new(5, "task 5")
+        .add_last(
+            &Placement::WholeFile(temp_dir.join("TODO.md")),
+            Some("sprint"),
+            &dialect,
+        )
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn save_config_keeping_manifest_text() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("project.manifest.md");
+    let manifest = r#"# Project
+
+Description of the project.
+
+```toml project
+# Project identity
+id = "project"
+name = "Project" # shown in the lists
+tags = ["a", "b"]
+```
+
+```md todo
+- task
+```
+"#;
+    fs::write(&path, manifest).unwrap();
+
+    let mut config = FsProjectConfig::<String>::load(&Placement::CodeBlockInFile(&path)).unwrap();
+    assert_eq!(config.name.as_deref(), Some("Project"));
+    config.name = Some("Renamed".into());
+    config.start_id = Some(10);
+    config.save(Placement::CodeBlockInFile(&path)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        r#"# Project
+
+Description of the project.
+
+```toml project
+# Project identity
+id = "project"
+name = "Renamed" # shown in the lists
+tags = ["a", "b"]
+start_id = 10
+```
+
+```md todo
+- task
+```
+"#
+    );
+}
+
+#[test]
+fn save_config_file_keeping_comments() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("Project.toml");
+    fs::write(
+        &path,
+        "# Project identity\nid = \"project\"\n\n# Unused\nname = \"Project\"\n",
+    )
+    .unwrap();
+
+    let mut config = FsProjectConfig::<String>::load(&Placement::WholeFile(&path)).unwrap();
+    config.name = None;
+    config.save(Placement::WholeFile(&path)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Project identity\nid = \"project\"\n"
+    );
+}
+
+#[test]
+fn save_config_in_its_format() {
+    let temp_dir = TempDir::default();
+
+    let path = temp_dir.join("Project.yaml");
+    fs::write(&path, "id: project\nname: Project\n").unwrap();
+    let mut config = FsProjectConfig::<String>::load(&Placement::WholeFile(&path)).unwrap();
+    config.tags = vec!["work".into()];
+    config.save(Placement::WholeFile(&path)).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "id: project\nname: Project\ntags:\n- work\n"
+    );
+
+    let path = temp_dir.join("project.manifest.md");
+    fs::write(
+        &path,
+        "# Project\n\n```json project\n{\"id\": \"project\"}\n```\n\n```md todo\n- task\n```\n",
+    )
+    .unwrap();
+    let mut config = FsProjectConfig::<String>::load(&Placement::CodeBlockInFile(&path)).unwrap();
+    assert_eq!(config.id, "project");
+    config.start_id = Some(10);
+    config.save(Placement::CodeBlockInFile(&path)).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Project\n\n```json project\n{\n  \"id\": \"project\",\n  \"start_id\": 10\n}\n```\n\n```md todo\n- task\n```\n"
+    );
+    let config = FsProjectConfig::<String>::load(&Placement::CodeBlockInFile(&path)).unwrap();
+    assert_eq!(config.start_id, Some(10));
+}
@@ -0,0 +1,287 @@
+use std::fs;
+use std::path::Path;
+
+use jiff::civil::date;
+use temp_testdir::TempDir;
+use todo_lib::issue::IssueStatus;
+use todo_lib::plan::Plan;
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::generator::IntIdGenerator;
+use todo_tracker_fs::issue::{self, Movement};
+use todo_tracker_fs::plan::LoadProjectPlan;
+use todo_tracker_fs::tracker::PlanSource;
+use todo_tracker_fs::{recurrence, Placement};
+
+static TASK_LIST_TEXT: &str = "- task A
+  - task AA
+  - task AB
+    description
+- task B
+
+- task C
+# Mile 1
+- task D
+";
+
+fn load_source(path: &Path) -> PlanSource {
+    let placement = Placement::WholeFile(path.to_path_buf());
+    let (plan, lines) = Plan::load_located(&placement, &IntIdGenerator::new(1), &ListDialect::default())
+        .unwrap()
+        .unwrap();
+    PlanSource { placement, plan, lines }
+}
+
+#[track_caller]
+fn assert_moved(id: u64, movement: Movement, expected: &str) {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, TASK_LIST_TEXT).unwrap();
+
+    let source = load_source(&path);
+    let name = source.plan.get_issue(&id).unwrap().name.clone();
+    let moved_line = issue::move_issue(&source, &id, movement, &ListDialect::default()).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+    assert!(expected.lines().nth(moved_line).unwrap().ends_with(&name));
+}
+
+#[test]
+fn move_up_and_down() {
+    assert_moved(
+        4,
+        Movement::Up,
+        "- task B\n- task A\n  - task AA\n  - task AB\n    description\n\n- task C\n# Mile 1\n- task D\n",
+    );
+    assert_moved(
+        2,
+        Movement::Down,
+        "- task A\n  - task AB\n    description\n  - task AA\n- task B\n\n- task C\n# Mile 1\n- task D\n",
+    );
+    assert_moved(
+        4,
+        Movement::Down,
+        "- task A\n  - task AA\n  - task AB\n    description\n- task C\n\n- task B\n# Mile 1\n- task D\n",
+    );
+}
+
+#[test]
+fn indent_and_outdent() {
+    assert_moved(
+        4,
+        Movement::Indent,
+        "- task A\n  - task AA\n  - task AB\n    description\n  - task B\n\n- task C\n# Mile 1\n- task D\n",
+    );
+    assert_moved(
+        2,
+        Movement::Outdent,
+        "- task A\n  - task AB\n    description\n- task AA\n- task B\n\n- task C\n# Mile 1\n- task D\n",
+    );
+}
+
+#[test]
+fn move_before_and_after() {
+    assert_moved(
+        1,
+        Movement::After(4),
+        "- task B\n- task A\n  - task AA\n  - task AB\n    description\n\n- task C\n# Mile 1\n- task D\n",
+    );
+    assert_moved(
+        4,
+        Movement::Before(3),
+        "- task A\n  - task AA\n  - task B\n  - task AB\n    description\n\n- task C\n# Mile 1\n- task D\n",
+    );
+    assert_moved(
+        3,
+        Movement::After(7),
+        "- task A\n  - task AA\n- task B\n\n- task C\n# Mile 1\n- task D\n- task AB\n  description\n",
+    );
+}
+
+#[test]
+fn reload_moved_relations() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, TASK_LIST_TEXT).unwrap();
+
+    let parent_name = |name: &str| {
+        let source = load_source(&path);
+        let issue = source.plan.find_issue(name).unwrap();
+        let parent_id = issue.parent_id?;
+        Some(source.plan.get_issue(&parent_id).unwrap().name.clone())
+    };
+    let id_of = |name: &str| load_source(&path).plan.find_issue(name).unwrap().id;
+
+    issue::move_issue(
+        &load_source(&path),
+        &id_of("task B"),
+        Movement::After(id_of("task AA")),
+        &ListDialect::default(),
+    )
+    .unwrap();
+    assert_eq!(parent_name("task B").as_deref(), Some("task A"));
+    let source = load_source(&path);
+    let task_a = source.plan.find_issue("task A").unwrap();
+    let subissues: Vec<_> = task_a
+        .subissues
+        .iter()
+        .map(|id| source.plan.get_issue(id).unwrap().name.as_str())
+        .collect();
+    assert_eq!(subissues, ["task AA", "task B", "task AB"]);
+
+    issue::move_issue(
+        &load_source(&path),
+        &id_of("task AB"),
+        Movement::Before(id_of("task D")),
+        &ListDialect::default(),
+    )
+    .unwrap();
+    assert_eq!(parent_name("task AB"), None);
+    assert_eq!(
+        load_source(&path).plan.find_issue("task AB").unwrap().content,
+        "description"
+    );
+}
+
+#[test]
+fn invalid_movements() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, TASK_LIST_TEXT).unwrap();
+    let source = load_source(&path);
+
+    for (id, movement) in [
+        (1, Movement::Up),
+        (1, Movement::Indent),
+        (1, Movement::Outdent),
+        (5, Movement::Down),
+        (2, Movement::Up),
+        (7, Movement::Up),
+        (1, Movement::Before(2)),
+        (1, Movement::After(1)),
+        (1, Movement::After(42)),
+    ] {
+        let err = issue::move_issue(&source, &id, movement, &ListDialect::default()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{id} {movement:?}");
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), TASK_LIST_TEXT);
+}
+
+#[test]
+fn complete_issue() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, "- task A\n  - 7 task AA\n    description\n").unwrap();
+
+    let source = load_source(&path);
+    let mut subissue = source.plan.get_issue(&7).unwrap().clone();
+    subissue.status = IssueStatus::Completed;
+    issue::replace_issue(
+        &path,
+        source.lines.get_issue(&7).unwrap(),
+        &subissue,
+        &ListDialect::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n  - [x] 7 task AA\n    description\n"
+    );
+
+    let source = load_source(&path);
+    let subissue = source.plan.get_issue(&7).unwrap();
+    assert!(subissue.is_completed());
+
+    let subissue = subissue.clone().with_status(IssueStatus::Open);
+    issue::replace_issue(
+        &path,
+        source.lines.get_issue(&7).unwrap(),
+        &subissue,
+        &ListDialect::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n  - [ ] 7 task AA\n    description\n"
+    );
+}
+
+#[test]
+fn write_explicit_id() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, "- task A\n  - [x] task AA\n  - 7 task AB\n").unwrap();
+
+    let source = load_source(&path);
+    let dialect = ListDialect::default();
+    assert!(issue::write_explicit_id(&path, source.lines.get_issue(&2).unwrap(), 9, &dialect).unwrap());
+    assert!(!issue::write_explicit_id(&path, source.lines.get_issue(&7).unwrap(), 10, &dialect).unwrap());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n  - [x] 9 task AA\n  - 7 task AB\n"
+    );
+}
+
+#[test]
+fn take_issue_subtree() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(
+        &path,
+        "- task A\n  - [x] 7 task AA\n  * 8 task AB\n    description\n- task B\n\n# Milestone\n\n# 9 Release\n",
+    )
+    .unwrap();
+    let dialect = ListDialect {
+        bullets: vec!['-', '*'],
+        ..Default::default()
+    };
+    let placement = Placement::WholeFile(path.clone());
+    let (plan, lines) = Plan::load_located(&placement, &IntIdGenerator::new(1), &dialect)
+        .unwrap()
+        .unwrap();
+    let source = PlanSource { placement, plan, lines };
+
+    let subtree = issue::issue_subtree(&source, 1, &dialect).unwrap();
+    let levels: Vec<_> = subtree
+        .iter()
+        .map(|subtree_issue| (subtree_issue.level, subtree_issue.explicit_id))
+        .collect();
+    assert_eq!(levels, [(0, None), (1, Some(7)), (1, Some(8))]);
+    assert_eq!(issue::generated_ids_count(&source, &dialect).unwrap(), 3);
+
+    let dialect = ListDialect {
+        bullets: vec!['+'],
+        indent: 4,
+        ..Default::default()
+    };
+    assert_eq!(
+        issue::subtree_to_text(&subtree, &dialect),
+        "+ task A\n    + [x] 7 task AA\n    + 8 task AB\n        description"
+    );
+}
+
+#[test]
+fn insert_next_occurrence_of_recurring_issue() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(
+        &path,
+        "- task A\n- [x] 7 clean every:1w due:2026-10-19\n  description\n  - [x] 8 kitchen\n  - bathroom\n- task B\n",
+    )
+    .unwrap();
+
+    let source = load_source(&path);
+    let issue = source.plan.get_issue(&7).unwrap();
+    let name = recurrence::next_occurrence_name(&issue.name, date(2026, 10, 20)).unwrap();
+    assert_eq!(name, "clean every:1w due:2026-10-26");
+
+    recurrence::insert_next_occurrence(&source, 7, &name, &ListDialect::default()).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n- [ ] clean every:1w due:2026-10-26\n  description\n  - [ ] kitchen\n  - bathroom\n- [x] 7 clean \
+         every:1w due:2026-10-19\n  description\n  - [x] 8 kitchen\n  - bathroom\n- task B\n"
+    );
+
+    let source = load_source(&path);
+    let occurrence = source.plan.find_issue(&name).unwrap();
+    assert!(!occurrence.is_completed());
+    assert_eq!(occurrence.subissues.len(), 2);
+}
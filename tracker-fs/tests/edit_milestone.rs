@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::Path;
+
+use indexmap::IndexSet;
+use temp_testdir::TempDir;
+use todo_lib::plan::Plan;
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::generator::IntIdGenerator;
+use todo_tracker_fs::plan::{LoadProjectPlan, PlanLines};
+use todo_tracker_fs::{milestone, Placement};
+
+static TASK_LIST_TEXT: &str = "- task A
+  - task AA
+- task B
+
+# 10 Mile 1
+
+- task C
+- task D
+";
+
+fn load_plan(placement: &Placement<&Path>) -> (Plan<u64>, PlanLines<u64>) {
+    Plan::load_located(placement, &IntIdGenerator::new(1), &ListDialect::default())
+        .unwrap()
+        .unwrap()
+}
+
+fn needed_issues(plan: &Plan<u64>, milestone_id: u64) -> IndexSet<u64> {
+    plan.get_milestone(&milestone_id).unwrap().needed_issues.clone()
+}
+
+#[test]
+fn insert_milestone_between_issues() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, TASK_LIST_TEXT).unwrap();
+    let placement = Placement::WholeFile(path.as_path());
+
+    // Before `task B`
+    let (plan, lines) = load_plan(&placement);
+    let line_idx = lines.issue_subtree(&plan, &3).unwrap().start;
+    milestone::insert_milestone(&placement, Some(line_idx), "Mile 0").unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n  - task AA\n\n# Mile 0\n\n- task B\n\n# 10 Mile 1\n\n- task C\n- task D\n"
+    );
+
+    let (plan, _) = load_plan(&placement);
+    assert_eq!(needed_issues(&plan, 3), IndexSet::from([1, 2]));
+    assert_eq!(needed_issues(&plan, 10), IndexSet::from([4]));
+
+    // After the last issues
+    milestone::insert_milestone(&placement, None, "Mile 2").unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n  - task AA\n\n# Mile 0\n\n- task B\n\n# 10 Mile 1\n\n- task C\n- task D\n\n# Mile 2\n"
+    );
+
+    let (plan, _) = load_plan(&placement);
+    assert_eq!(needed_issues(&plan, 7), IndexSet::from([5, 6]));
+}
+
+#[test]
+fn insert_milestone_into_manifest() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("Project.manifest.md");
+    fs::write(&path, "# Project\n\n```md todo\n- task A\n- task B\n```\n\nText\n").unwrap();
+    let placement = Placement::CodeBlockInFile(path.as_path());
+
+    let (plan, lines) = load_plan(&placement);
+    let line_idx = lines.issue_subtree(&plan, &1).unwrap().end;
+    milestone::insert_milestone(&placement, Some(line_idx), "Mile 1").unwrap();
+    milestone::insert_milestone(&placement, None, "Mile 2").unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Project\n\n```md todo\n- task A\n\n# Mile 1\n\n- task B\n\n# Mile 2\n```\n\nText\n"
+    );
+
+    let (plan, _) = load_plan(&placement);
+    assert_eq!(needed_issues(&plan, 2), IndexSet::from([1]));
+    assert_eq!(needed_issues(&plan, 4), IndexSet::from([3]));
+}
+
+#[test]
+fn rename_milestone_keeping_id() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, TASK_LIST_TEXT).unwrap();
+    let placement = Placement::WholeFile(path.as_path());
+
+    let (_, lines) = load_plan(&placement);
+    milestone::rename_milestone(&path, lines.get_milestone(&10).unwrap(), "First release").unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n  - task AA\n- task B\n\n# 10 First release\n\n- task C\n- task D\n"
+    );
+}
+
+#[test]
+fn remove_milestone_merging_issues() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, format!("{TASK_LIST_TEXT}\n# 20 Mile 2\n")).unwrap();
+    let placement = Placement::WholeFile(path.as_path());
+
+    let (_, lines) = load_plan(&placement);
+    milestone::remove_milestone(&path, lines.get_milestone(&10).unwrap()).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n  - task AA\n- task B\n\n- task C\n- task D\n\n# 20 Mile 2\n"
+    );
+
+    let (plan, _) = load_plan(&placement);
+    assert!(plan.get_milestone(&10).is_none());
+    assert_eq!(needed_issues(&plan, 20), IndexSet::from([1, 2, 3, 4, 5]));
+}
@@ -0,0 +1,65 @@
+use std::fs;
+
+use temp_testdir::TempDir;
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::document::Document;
+use todo_tracker_fs::format::{format_document, format_file};
+use todo_tracker_fs::Placement;
+
+/// Formats the `tests/format/<name>.md` file and compares the result with the `<name>.formatted.md` golden file,
+/// formatting the golden file must keep it unchanged.
+fn assert_formatted_as_golden(name: &str, placement: Placement<()>) {
+    let dialect = ListDialect::default();
+    let source = fs::read_to_string(format!("tests/format/{name}.md")).unwrap();
+    let expected = fs::read_to_string(format!("tests/format/{name}.formatted.md")).unwrap();
+
+    let formatted = format_document(&Document::parse(source), &placement, &dialect);
+    assert_eq!(formatted.as_str(), expected);
+
+    let reformatted = format_document(&formatted, &placement, &dialect);
+    assert_eq!(reformatted.as_str(), expected);
+}
+
+#[test]
+fn format_issues_file() {
+    assert_formatted_as_golden("issues", Placement::WholeFile(()));
+}
+
+#[test]
+fn format_manifest_blocks() {
+    assert_formatted_as_golden("manifest", Placement::CodeBlockInFile(()));
+}
+
+#[test]
+fn keep_fenced_code_blocks() {
+    assert_formatted_as_golden("fences", Placement::WholeFile(()));
+}
+
+#[test]
+fn format_with_dialect() {
+    let dialect = ListDialect {
+        bullets: vec!['*'],
+        tabs: true,
+        ..Default::default()
+    };
+    let doc = Document::parse("* task 1\n  * subtask 1\n    description\n* task 2\n");
+    let formatted = format_document(&doc, &Placement::WholeFile(()), &dialect);
+    assert_eq!(
+        formatted.as_str(),
+        "* task 1\n\t* subtask 1\n\t\tdescription\n* task 2\n"
+    );
+}
+
+#[test]
+fn format_file_only_if_changed() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    let placement = Placement::WholeFile(path.clone());
+
+    fs::write(&path, "- task 1\n- task 2\n").unwrap();
+    assert_eq!(format_file(&placement, &ListDialect::default()).unwrap(), None);
+
+    fs::write(&path, "+ task 1\n# milestone\n").unwrap();
+    let formatted = format_file(&placement, &ListDialect::default()).unwrap().unwrap();
+    assert_eq!(formatted.as_str(), "- task 1\n\n# milestone\n");
+}
@@ -0,0 +1,131 @@
+use std::fs;
+
+use temp_testdir::TempDir;
+use todo_lib::issue::{Issue, IssueStatus};
+use todo_lib::plan::Plan;
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::generator::IntIdGenerator;
+use todo_tracker_fs::issue::{self, Movement, SaveIssue};
+use todo_tracker_fs::plan::LoadProjectPlan;
+use todo_tracker_fs::tracker::PlanSource;
+use todo_tracker_fs::Placement;
+
+fn star_dialect() -> ListDialect {
+    ListDialect {
+        bullets: vec!['*'],
+        ordered: true,
+        indent: 4,
+        tabs: false,
+    }
+}
+
+fn load(text: &str, dialect: &ListDialect) -> Plan<u64> {
+    let lines = text.lines().map(|line| Ok(line.to_string())).enumerate();
+    Plan::load_located_from_lines(lines, &IntIdGenerator::new(1), dialect)
+        .unwrap()
+        .0
+}
+
+fn names<'a>(plan: &Plan<u64>, issues: impl IntoIterator<Item = &'a u64>) -> Vec<String> {
+    issues
+        .into_iter()
+        .map(|id| plan.get_issue(id).unwrap().name.clone())
+        .collect()
+}
+
+#[test]
+fn load_checklists_and_ordered_lists() {
+    let plan = load(
+        "* [x] task A\n    1. task AA\n    2) [ ] task AB\n       description\n* task B\n\t* task BA\n",
+        &star_dialect(),
+    );
+
+    let task_a = plan.get_issue(&1).unwrap();
+    assert_eq!(task_a.status, IssueStatus::Completed);
+    assert_eq!(names(&plan, &task_a.subissues), ["task AA", "task AB"]);
+    assert_eq!(plan.get_issue(&3).unwrap().content, "description");
+
+    let task_b = plan.get_issue(&4).unwrap();
+    assert_eq!(names(&plan, &task_b.subissues), ["task BA"]);
+}
+
+#[test]
+fn nest_by_content_column() {
+    // The subissue needs to be indented to the content of the parent only
+    let plan = load("-   task A\n    - task AA\n  - task B\n", &ListDialect::default());
+    assert_eq!(names(&plan, &plan.get_issue(&1).unwrap().subissues), ["task AA"]);
+    assert!(plan.get_issue(&3).unwrap().parent_id.is_none());
+
+    // Too deep indentation makes an indented code instead of a subissue
+    let lines = "- task A\n      - task AA\n"
+        .lines()
+        .map(|line| Ok(line.to_string()))
+        .enumerate();
+    assert!(Plan::load_located_from_lines(lines, &IntIdGenerator::new(1), &ListDialect::default()).is_err());
+
+    // Bullets of other dialects are the plain text
+    let plan = load("* task A\n+ task B\n", &star_dialect());
+    assert_eq!(plan.steps().len(), 1);
+}
+
+#[test]
+fn write_issues_in_dialect() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, "* task A\n").unwrap();
+    let dialect = star_dialect();
+
+    let placement = Placement::WholeFile(path.clone());
+    Issue::new(2, "task B")
+        .with_status(IssueStatus::Completed)
+        .with_content("description")
+        .add_last(&placement, None, &dialect)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "* task A\n* [x] task B\n    description\n"
+    );
+
+    let (plan, lines) = Plan::load_located(&placement, &IntIdGenerator::new(1), &dialect)
+        .unwrap()
+        .unwrap();
+    assert_eq!(plan.get_issue(&2).unwrap().content, "description");
+
+    let source = PlanSource { placement, plan, lines };
+    issue::move_issue(&source, &2, Movement::Indent, &dialect).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "* task A\n    * [x] task B\n        description\n"
+    );
+}
+
+#[test]
+fn write_issues_with_tabs() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TODO.md");
+    fs::write(&path, "- task A\n- task B\n").unwrap();
+    let dialect = ListDialect {
+        tabs: true,
+        ..Default::default()
+    };
+
+    let placement = Placement::WholeFile(path.clone());
+    let (plan, lines) = Plan::load_located(&placement, &IntIdGenerator::new(1), &dialect)
+        .unwrap()
+        .unwrap();
+    let source = PlanSource { placement, plan, lines };
+    issue::move_issue(&source, &2, Movement::Indent, &dialect).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "- task A\n\t- task B\n");
+
+    let (plan, lines) = Plan::load_located(&source.placement, &IntIdGenerator::new(1), &dialect)
+        .unwrap()
+        .unwrap();
+    assert_eq!(plan.get_issue(&2).unwrap().parent_id, Some(1));
+
+    let task_b = plan.get_issue(&2).unwrap().clone().with_content("description");
+    issue::replace_issue(&path, lines.get_issue(&2).unwrap(), &task_b, &dialect).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "- task A\n\t- task B\n\t  description\n"
+    );
+}
@@ -9,11 +9,12 @@ use tests::init_logger;
 use todo_lib::issue::{Issue, Milestone};
 use todo_lib::plan::Plan;
 use todo_lib::plan::Step::*;
+use todo_tracker_fs::dialect::ListDialect;
 use todo_tracker_fs::generator::IntIdGenerator;
 use todo_tracker_fs::plan::LoadProjectPlan;
 use todo_tracker_fs::Placement;
 
-static TASK_LIST_TEXT: &'static str = r"
+static TASK_LIST_TEXT: &str = r"
 - task A
   - task AA
 
@@ -167,7 +168,12 @@ fn tasks_from_todo_file() -> anyhow::Result<()> {
     File::create(todo_file_path.clone())?.write_all(TASK_LIST_TEXT.as_bytes())?;
 
     let id_generator = IntIdGenerator::new(1);
-    let plan = Plan::load(&Placement::WholeFile(todo_file_path), &id_generator)?.unwrap();
+    let plan = Plan::load(
+        &Placement::WholeFile(todo_file_path),
+        &id_generator,
+        &ListDialect::default(),
+    )?
+    .unwrap();
 
     assert_task_list_plan(&plan);
 
@@ -219,9 +225,107 @@ List 3:
     )?;
 
     let id_generator = IntIdGenerator::new(1);
-    let plan = Plan::load(&Placement::CodeBlockInFile(manifest_file_path), &id_generator)?.unwrap();
+    let plan = Plan::load(
+        &Placement::CodeBlockInFile(manifest_file_path),
+        &id_generator,
+        &ListDialect::default(),
+    )?
+    .unwrap();
 
     assert_task_list_plan(&plan);
 
     Ok(())
 }
+
+#[test]
+#[named]
+fn sections_from_manifest_file() -> anyhow::Result<()> {
+    init_logger();
+
+    let temp_dir = TempDir::default();
+    let project_root = create_temp_project_root_dir(&temp_dir, function_name!())?;
+    let manifest_file_path = project_root.join(format!("{}.manifest.md", function_name!()));
+
+    File::create(manifest_file_path.clone())?.write_all(
+        r"# Project header
+
+```md todo
+- task 1
+```
+
+```md todo backlog
+- task 2
+  - task 3
+```
+
+Some description.
+
+```md todo sprint
+  - task 4
+# Mile
+- task 5
+```
+"
+        .as_bytes(),
+    )?;
+
+    let id_generator = IntIdGenerator::new(1);
+    let plan = Plan::load(
+        &Placement::CodeBlockInFile(manifest_file_path),
+        &id_generator,
+        &ListDialect::default(),
+    )?
+    .unwrap();
+
+    assert!(plan
+        .steps()
+        .iter()
+        .eq(&[Issue(1), Issue(2), Issue(3), Issue(4), Milestone(5), Issue(6)]));
+    assert!(plan.get_issue(&4).unwrap().parent_id.is_none());
+    assert!(plan.get_milestone(&5).unwrap().needed_issues.iter().eq(&[4]));
+
+    assert!(plan.sections().keys().eq(["backlog", "sprint"]));
+    assert!(plan.section("backlog").unwrap().iter().eq(&[Issue(2), Issue(3)]));
+    assert!(plan
+        .section("sprint")
+        .unwrap()
+        .iter()
+        .eq(&[Issue(4), Milestone(5), Issue(6)]));
+    assert_eq!(plan.section_of(&Issue(1)), None);
+    assert_eq!(plan.section_of(&Issue(3)), Some("backlog"));
+
+    Ok(())
+}
+
+#[test]
+#[named]
+fn code_block_fences_in_issues_file_are_text() -> anyhow::Result<()> {
+    init_logger();
+
+    let temp_dir = TempDir::default();
+    let project_root = create_temp_project_root_dir(&temp_dir, function_name!())?;
+    let issues_file_path = project_root.join("TODO.md");
+
+    File::create(issues_file_path.clone())?.write_all(
+        r"- task 1
+
+```md todo backlog
+- task 2
+```
+"
+        .as_bytes(),
+    )?;
+
+    let id_generator = IntIdGenerator::new(1);
+    let plan = Plan::load(
+        &Placement::WholeFile(issues_file_path),
+        &id_generator,
+        &ListDialect::default(),
+    )?
+    .unwrap();
+
+    assert!(plan.steps().iter().eq(&[Issue(1), Issue(2)]));
+    assert!(plan.sections().is_empty());
+
+    Ok(())
+}
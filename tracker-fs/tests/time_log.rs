@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::{fs, io};
+
+use jiff::{SignedDuration, Timestamp};
+use temp_testdir::TempDir;
+use todo_tracker_fs::time_log::{load_time_log, remap_entries, start_entry, stop_entry, TimeEntry};
+
+fn timestamp(time: &str) -> Timestamp {
+    time.parse().unwrap()
+}
+
+#[test]
+fn start_and_stop_entries() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TIME.log");
+
+    assert_eq!(load_time_log(&path).unwrap(), []);
+    assert_eq!(stop_entry(&path, timestamp("2026-10-01T08:00:00Z")).unwrap(), None);
+
+    assert_eq!(start_entry(&path, 1, timestamp("2026-10-01T09:00:00Z")).unwrap(), None);
+    let stopped = start_entry(&path, 2, timestamp("2026-10-01T10:30:00Z")).unwrap();
+    assert_eq!(
+        stopped,
+        Some(TimeEntry {
+            issue_id: 1,
+            start: timestamp("2026-10-01T09:00:00Z"),
+            stop: Some(timestamp("2026-10-01T10:30:00Z")),
+        })
+    );
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "1 2026-10-01T09:00:00Z 2026-10-01T10:30:00Z\n2 2026-10-01T10:30:00Z\n"
+    );
+
+    let entries = load_time_log(&path).unwrap();
+    assert!(entries[1].is_running());
+    let now = timestamp("2026-10-01T11:00:00Z");
+    assert_eq!(entries[0].duration(now), SignedDuration::from_mins(90));
+    assert_eq!(entries[1].duration(now), SignedDuration::from_mins(30));
+
+    let stopped = stop_entry(&path, now).unwrap().unwrap();
+    assert_eq!(stopped.issue_id, 2);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "1 2026-10-01T09:00:00Z 2026-10-01T10:30:00Z\n2 2026-10-01T10:30:00Z 2026-10-01T11:00:00Z\n"
+    );
+}
+
+#[test]
+fn remap_entries_ids() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TIME.log");
+
+    remap_entries(&path, &HashMap::from([(1, 2)])).unwrap();
+    assert!(!path.exists());
+
+    fs::write(
+        &path,
+        "1 2026-10-01T09:00:00Z 2026-10-01T10:00:00Z\n\n2 2026-10-01T10:00:00Z 2026-10-01T11:00:00Z\n3 2026-10-01T11:00:00Z\n",
+    )
+    .unwrap();
+    remap_entries(&path, &HashMap::from([(1, 3), (3, 1)])).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "3 2026-10-01T09:00:00Z 2026-10-01T10:00:00Z\n\n2 2026-10-01T10:00:00Z 2026-10-01T11:00:00Z\n1 2026-10-01T11:00:00Z\n"
+    );
+}
+
+#[test]
+fn load_broken_time_log() {
+    let temp_dir = TempDir::default();
+    let path = temp_dir.join("TIME.log");
+    fs::write(&path, "1 2026-10-01T09:00:00Z\n\n2 yesterday\n").unwrap();
+
+    let err = load_time_log(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        format!("broken time entry in line 3 of `{}`", path.display())
+    );
+}
@@ -0,0 +1,307 @@
+use std::fs;
+
+use indexmap::IndexMap;
+use regex::Regex;
+use temp_testdir::TempDir;
+use todo_lib::plan::{Plan, Step};
+use todo_tracker_fs::check::check_plan_sources;
+use todo_tracker_fs::config::FsProjectConfig;
+use todo_tracker_fs::dialect::ListDialect;
+use todo_tracker_fs::generator::IntIdGenerator;
+use todo_tracker_fs::plan::LoadProjectPlan;
+use todo_tracker_fs::tracker::{find_issues_files, load_project_plan_sources, load_project_plan_sources_diagnosed};
+use todo_tracker_fs::{FsTracker, Placement};
+
+#[test]
+fn load_plans_in_configs_order() {
+    let temp_dir = TempDir::default();
+    let manifest_regex = Regex::new(r"\.manifest\.md$").unwrap();
+    let issues_regex = Regex::new(r"^TODO\.md$").unwrap();
+
+    let mut configs = IndexMap::new();
+    for idx in (0..64).rev() {
+        let project_dir = temp_dir.join(format!("project {idx}"));
+        fs::create_dir_all(&project_dir).unwrap();
+        let todo: String = (0..idx).map(|issue_idx| format!("- task {issue_idx}\n")).collect();
+        fs::write(project_dir.join("TODO.md"), todo).unwrap();
+
+        let id = format!("project {idx}");
+        configs.insert(id.clone(), FsProjectConfig {
+            id,
+            root_dir: Some(project_dir),
+            ..Default::default()
+        });
+    }
+
+    let tracker = FsTracker::new(configs.clone(), &manifest_regex, &issues_regex, &ListDialect::default());
+    tracker.load_plans().unwrap();
+    assert!(tracker.projects().keys().eq(configs.keys()));
+    for (idx, id) in (0..64).rev().zip(configs.keys()) {
+        let steps = tracker
+            .project_plan(id)
+            .unwrap()
+            .map(|plan| plan.steps().len())
+            .unwrap_or_default();
+        assert_eq!(steps, idx, "{id}");
+    }
+
+    for idx in [40, 10] {
+        fs::write(
+            temp_dir.join(format!("project {idx}")).join("TODO.md"),
+            "    - broken\n",
+        )
+        .unwrap();
+    }
+    let ids: Vec<_> = configs.keys().cloned().collect();
+    let tracker = FsTracker::new(configs, &manifest_regex, &issues_regex, &ListDialect::default());
+    tracker.load_plans().unwrap();
+    for (idx, id) in (0..64).rev().zip(&ids) {
+        let diagnostics = tracker.project_diagnostics(id).unwrap();
+        if [40, 10].contains(&idx) {
+            assert!(diagnostics[0].message.contains("level"), "{id}");
+        } else {
+            assert!(diagnostics.is_empty(), "{id}");
+        }
+    }
+}
+
+#[test]
+fn load_plan_on_demand() {
+    let temp_dir = TempDir::default();
+    let manifest_regex = Regex::new(r"\.manifest\.md$").unwrap();
+    let issues_regex = Regex::new(r"^TODO\.md$").unwrap();
+
+    let project_dir = temp_dir.join("project");
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::create_dir(project_dir.join("TODO.md")).unwrap();
+
+    let id = "project".to_string();
+    let configs = IndexMap::from([(id.clone(), FsProjectConfig {
+        id: id.clone(),
+        root_dir: Some(project_dir.clone()),
+        ..Default::default()
+    })]);
+
+    // Unreadable plan does not prevent the tracker creation and its failed loading is not cached
+    let tracker = FsTracker::new(configs, &manifest_regex, &issues_regex, &ListDialect::default());
+    assert!(tracker.project_plan(&id).is_err());
+
+    fs::remove_dir(project_dir.join("TODO.md")).unwrap();
+    fs::write(project_dir.join("TODO.md"), "- task\n").unwrap();
+    let plan = tracker.project_plan(&id).unwrap().unwrap();
+    assert_eq!(plan.steps().len(), 1);
+
+    // Loaded plan is cached until reloading
+    fs::write(project_dir.join("TODO.md"), "- task\n- other task\n").unwrap();
+    assert_eq!(tracker.project_plan(&id).unwrap().unwrap().steps().len(), 1);
+    assert_eq!(tracker.project_steps_count(&id).unwrap().total(true), 1);
+
+    let mut tracker = tracker;
+    tracker.reload_project_plan(&id).unwrap();
+    assert_eq!(tracker.project_plan(&id).unwrap().unwrap().steps().len(), 2);
+}
+
+#[test]
+fn count_steps_without_loading_plan() {
+    let temp_dir = TempDir::default();
+    let manifest_regex = Regex::new(r"\.manifest\.md$").unwrap();
+    let issues_regex = Regex::new(r"^TODO\.md$").unwrap();
+
+    let project_dir = temp_dir.join("project");
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::write(
+        project_dir.join("project.manifest.md"),
+        "# Project\n\n```md todo\n- manifest task\n  - manifest subtask\n```\n\n- not a task\n",
+    )
+    .unwrap();
+    fs::write(
+        project_dir.join("TODO.md"),
+        "- [x] task 1\n  description\n  - subtask\n    - subsubtask\n\n---\n\n# Milestone\n\n+ task 2\n",
+    )
+    .unwrap();
+
+    let id = "project".to_string();
+    let configs = IndexMap::from([(id.clone(), FsProjectConfig {
+        id: id.clone(),
+        root_dir: Some(project_dir),
+        ..Default::default()
+    })]);
+
+    let tracker = FsTracker::new(configs, &manifest_regex, &issues_regex, &ListDialect::default());
+    let scanned = tracker.project_steps_count(&id).unwrap();
+    assert_eq!(scanned.milestones, 1);
+    assert_eq!(scanned.issues, 3);
+    assert_eq!(scanned.subissues, 3);
+    assert_eq!(scanned.total(false), 4);
+
+    let plan = tracker.project_plan(&id).unwrap().unwrap();
+    assert_eq!(scanned.total(true), plan.steps().len());
+    assert_eq!(tracker.project_steps_count(&id).unwrap(), scanned);
+}
+
+#[test]
+fn load_plan_from_issues_files_globs() {
+    let temp_dir = TempDir::default();
+    let manifest_regex = Regex::new(r"\.manifest\.md$").unwrap();
+    let issues_regex = Regex::new(r"^TODO\.md$").unwrap();
+
+    let project_dir = temp_dir.join("project");
+    fs::create_dir_all(project_dir.join("docs")).unwrap();
+    fs::create_dir_all(project_dir.join("todo").join("old")).unwrap();
+    fs::write(project_dir.join("TODO.md"), "- task 1\n").unwrap();
+    fs::write(project_dir.join("docs").join("TODO.md"), "- task 2\n- task 3\n").unwrap();
+    fs::write(project_dir.join("todo").join("b.md"), "- task 5\n").unwrap();
+    fs::write(project_dir.join("todo").join("a.md"), "# Mile\n- task 4\n").unwrap();
+    fs::write(project_dir.join("todo").join("old").join("c.md"), "- task 6\n").unwrap();
+
+    let mut config = FsProjectConfig::new("project".to_string()).with_root_dir(project_dir.clone());
+    config.source.issues_files = ["TODO.md", "docs/TODO.md", "todo/*.md"].map(String::from).to_vec();
+
+    let sources = load_project_plan_sources(&config, &manifest_regex, &issues_regex, &ListDialect::default()).unwrap();
+    let located: Vec<_> = sources
+        .iter()
+        .flat_map(|source| {
+            source.plan.steps().iter().filter_map(|step| {
+                let Step::Issue(id) = step else {
+                    return None;
+                };
+                let name = source.plan.get_issue(id).unwrap().name.as_str();
+                let line = source.lines.get_issue(id).unwrap().lines.start;
+                Some((name, source.placement.as_ref().clone(), line))
+            })
+        })
+        .collect();
+    assert_eq!(located, [
+        ("task 1", project_dir.join("TODO.md"), 0),
+        ("task 2", project_dir.join("docs").join("TODO.md"), 0),
+        ("task 3", project_dir.join("docs").join("TODO.md"), 1),
+        ("task 4", project_dir.join("todo").join("a.md"), 1),
+        ("task 5", project_dir.join("todo").join("b.md"), 0),
+    ]);
+
+    let tracker = FsTracker::new(
+        IndexMap::from([(config.id.clone(), config.clone())]),
+        &manifest_regex,
+        &issues_regex,
+        &ListDialect::default(),
+    );
+    assert_eq!(tracker.project_steps_count(&config.id).unwrap().total(true), 6);
+    assert_eq!(tracker.project_source_paths(&config.id).unwrap(), [
+        project_dir.join("TODO.md"),
+        project_dir.join("docs").join("TODO.md"),
+        project_dir.join("todo").join("a.md"),
+        project_dir.join("todo").join("b.md"),
+    ]);
+
+    let plan = tracker.project_plan(&config.id).unwrap().unwrap();
+    assert_eq!(plan.sections().keys().collect::<Vec<_>>(), [
+        "TODO.md",
+        "docs/TODO.md",
+        "todo/a.md",
+        "todo/b.md"
+    ]);
+    assert_eq!(plan.section("docs/TODO.md").unwrap().len(), 2);
+    assert_eq!(plan.section("todo/a.md").unwrap().len(), 2);
+
+    fs::create_dir_all(project_dir.join("target").join("doc")).unwrap();
+    fs::write(project_dir.join("target").join("doc").join("TODO.md"), "- built\n").unwrap();
+    fs::create_dir_all(project_dir.join("sub")).unwrap();
+    fs::write(project_dir.join("sub").join("Project.toml"), "id = \"sub\"\n").unwrap();
+    fs::write(project_dir.join("sub").join("TODO.md"), "- subproject task\n").unwrap();
+    let files = find_issues_files(&project_dir, &["**/TODO.md".to_string()], &manifest_regex).unwrap();
+    assert_eq!(files, [
+        project_dir.join("TODO.md"),
+        project_dir.join("docs").join("TODO.md")
+    ]);
+}
+
+#[test]
+fn load_broken_plan_with_diagnostics() {
+    let temp_dir = TempDir::default();
+    let manifest_regex = Regex::new(r"\.manifest\.md$").unwrap();
+    let issues_regex = Regex::new(r"^TODO\.md$").unwrap();
+
+    let project_dir = temp_dir.join("project");
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::write(project_dir.join("TODO.md"), "- task 1\n      - task 2\n- task 3\n").unwrap();
+
+    let config = FsProjectConfig::new("project".to_string()).with_root_dir(project_dir.clone());
+    let tracker = FsTracker::new(
+        IndexMap::from([(config.id.clone(), config.clone())]),
+        &manifest_regex,
+        &issues_regex,
+        &ListDialect::default(),
+    );
+    assert!(tracker.loaded_project_diagnostics(&config.id).is_empty());
+
+    let plan = tracker.project_plan(&config.id).unwrap().unwrap();
+    assert_eq!(plan.steps().len(), 3);
+    assert_eq!(plan.get_issue(&2).unwrap().parent_id, Some(1));
+
+    let diagnostics = tracker.project_diagnostics(&config.id).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (1, 6));
+    assert_eq!(tracker.loaded_project_diagnostics(&config.id), diagnostics);
+    assert_eq!(
+        diagnostics[0].to_string(),
+        format!(
+            "{}:2:7: error: issue level = 2 is greater than previous issue level + 1 = 1",
+            project_dir.join("TODO.md").display()
+        )
+    );
+
+    let placement = Placement::WholeFile(project_dir.join("TODO.md"));
+    assert!(Plan::load_located(&placement, &IntIdGenerator::new(1), &ListDialect::default()).is_err());
+}
+
+#[test]
+fn check_plan_sources_of_project_files() {
+    let temp_dir = TempDir::default();
+    let manifest_regex = Regex::new(r"\.manifest\.md$").unwrap();
+    let issues_regex = Regex::new(r"^TODO\.md$").unwrap();
+
+    let project_dir = temp_dir.join("project");
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::write(
+        project_dir.join("project.manifest.md"),
+        "# project\n\n```md todo\n- 5 task 1\n- task 2\n```\n",
+    )
+    .unwrap();
+    fs::write(
+        project_dir.join("TODO.md"),
+        "- 5 task 3\n- task 2\n- 7 task 4\n- 7 task 5\n",
+    )
+    .unwrap();
+
+    let config = FsProjectConfig::new("project".to_string()).with_root_dir(project_dir.clone());
+    let (sources, diagnostics) =
+        load_project_plan_sources_diagnosed(&config, &manifest_regex, &issues_regex, &ListDialect::default()).unwrap();
+    assert_eq!(sources.len(), 2);
+    assert_eq!(diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>(), [
+        format!(
+            "{}:4:1: warning: duplicate issue id, first used in line 3",
+            project_dir.join("TODO.md").display()
+        )
+    ]);
+
+    let manifest_file = project_dir.join("project.manifest.md");
+    let issues_file = project_dir.join("TODO.md");
+    assert_eq!(
+        check_plan_sources(&sources)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        [
+            format!(
+                "{}:1:1: error: issue id `5` collides with `{}:4`",
+                issues_file.display(),
+                manifest_file.display()
+            ),
+            format!(
+                "{}:2:1: warning: duplicate issue name `task 2`, first used in `{}:5`",
+                issues_file.display(),
+                manifest_file.display()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,370 @@
+use std::io;
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use todo_app::config::{Config, SourceConfig};
+use todo_app::issue::{self, IssueUpdate};
+use todo_app::locate_project_configs;
+use todo_app::project::{FsProjectMetadata, ProjectData};
+use todo_lib::issue::{IssueStatus, SourceLocation};
+use todo_lib::plan::{Plan, Step};
+use todo_tracker_fs::config::FsProjectConfig;
+use todo_tracker_fs::issue::Movement;
+use todo_tracker_fs::FsTracker;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Projects,
+    Plan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+
+    /// Editing the new name of the selected issue
+    Rename(String),
+
+    /// Waiting for the confirmation of the selected issue removal
+    ConfirmRemove,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectRow {
+    pub id: String,
+    pub name: String,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanRow {
+    Issue {
+        id: u64,
+        name: String,
+        level: usize,
+        completed: bool,
+    },
+    Milestone {
+        id: u64,
+        name: String,
+    },
+}
+
+/// State of the terminal UI: the loaded tracker, the selection and the current input mode.
+pub struct App {
+    config: Config,
+    project_configs: IndexMap<String, FsProjectConfig>,
+    tracker: FsTracker,
+    projects: Vec<ProjectRow>,
+    plan: Vec<PlanRow>,
+    selected_project: usize,
+    selected_step: usize,
+    pane: Pane,
+    mode: Mode,
+    message: Option<String>,
+    should_quit: bool,
+}
+
+impl App {
+    pub fn new(config: Config, search_roots: Vec<PathBuf>) -> anyhow::Result<Self> {
+        let project_configs = locate_project_configs::<String>(None, &search_roots, &config)?;
+        let tracker = load_tracker(&project_configs, &config)?;
+
+        let mut app = Self {
+            config,
+            project_configs,
+            tracker,
+            projects: Vec::new(),
+            plan: Vec::new(),
+            selected_project: 0,
+            selected_step: 0,
+            pane: Pane::Projects,
+            mode: Mode::Normal,
+            message: None,
+            should_quit: false,
+        };
+        app.projects = project_rows(&app.tracker);
+        app.plan = app.selected_plan().map(plan_rows).unwrap_or_default();
+        Ok(app)
+    }
+
+    pub fn projects(&self) -> &[ProjectRow] {
+        &self.projects
+    }
+
+    pub fn plan(&self) -> &[PlanRow] {
+        &self.plan
+    }
+
+    pub fn selected_project(&self) -> Option<&ProjectRow> {
+        self.projects.get(self.selected_project)
+    }
+
+    pub fn selected_project_idx(&self) -> usize {
+        self.selected_project
+    }
+
+    pub fn selected_step_idx(&self) -> usize {
+        self.selected_step
+    }
+
+    pub fn pane(&self) -> Pane {
+        self.pane
+    }
+
+    pub fn mode(&self) -> &Mode {
+        &self.mode
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match &mut self.mode {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Rename(name) => match key.code {
+                KeyCode::Enter => {
+                    let name = name.trim().to_string();
+                    self.mode = Mode::Normal;
+                    if !name.is_empty() {
+                        self.rename_selected(name);
+                    }
+                },
+                KeyCode::Esc => self.mode = Mode::Normal,
+                KeyCode::Backspace => {
+                    name.pop();
+                },
+                KeyCode::Char(ch) => name.push(ch),
+                _ => {},
+            },
+            Mode::ConfirmRemove => {
+                self.mode = Mode::Normal;
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    self.remove_selected();
+                }
+            },
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) {
+        self.message = None;
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+
+        match (self.pane, key.code) {
+            (_, KeyCode::Char('q') | KeyCode::Esc) => self.should_quit = true,
+            (_, KeyCode::Tab) => {
+                self.pane = match self.pane {
+                    Pane::Projects => Pane::Plan,
+                    Pane::Plan => Pane::Projects,
+                }
+            },
+            (Pane::Projects, KeyCode::Down | KeyCode::Char('j')) => self.select_project(self.selected_project + 1),
+            (Pane::Projects, KeyCode::Up | KeyCode::Char('k')) => {
+                self.select_project(self.selected_project.saturating_sub(1))
+            },
+            (Pane::Projects, KeyCode::Enter | KeyCode::Right) => self.pane = Pane::Plan,
+            (Pane::Plan, KeyCode::Up) if shift => self.move_selected(Movement::Up),
+            (Pane::Plan, KeyCode::Down) if shift => self.move_selected(Movement::Down),
+            (Pane::Plan, KeyCode::Char('K')) => self.move_selected(Movement::Up),
+            (Pane::Plan, KeyCode::Char('J')) => self.move_selected(Movement::Down),
+            (Pane::Plan, KeyCode::Char('>')) => self.move_selected(Movement::Indent),
+            (Pane::Plan, KeyCode::Char('<')) => self.move_selected(Movement::Outdent),
+            (Pane::Plan, KeyCode::Down | KeyCode::Char('j')) => {
+                self.selected_step = (self.selected_step + 1).min(self.plan.len().saturating_sub(1))
+            },
+            (Pane::Plan, KeyCode::Up | KeyCode::Char('k')) => self.selected_step = self.selected_step.saturating_sub(1),
+            (Pane::Plan, KeyCode::Left) => self.pane = Pane::Projects,
+            (Pane::Plan, KeyCode::Char(' ') | KeyCode::Char('x')) => self.toggle_selected(),
+            (Pane::Plan, KeyCode::Char('r')) => {
+                if let Some(PlanRow::Issue { name, .. }) = self.plan.get(self.selected_step) {
+                    self.mode = Mode::Rename(name.clone());
+                }
+            },
+            (Pane::Plan, KeyCode::Char('d') | KeyCode::Delete) => {
+                if let Some(PlanRow::Issue { .. }) = self.plan.get(self.selected_step) {
+                    self.mode = Mode::ConfirmRemove;
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn select_project(&mut self, idx: usize) {
+        if idx < self.projects.len() && idx != self.selected_project {
+            self.selected_project = idx;
+            self.selected_step = 0;
+            self.plan = self.selected_plan().map(plan_rows).unwrap_or_default();
+        }
+    }
+
+    fn selected_plan(&self) -> Option<&Plan<u64>> {
+        let project = self.projects.get(self.selected_project)?;
+        self.tracker.project_plan(&project.id).ok().flatten()
+    }
+
+    fn selected_issue_id(&self) -> Option<u64> {
+        match self.plan.get(self.selected_step)? {
+            PlanRow::Issue { id, .. } => Some(*id),
+            PlanRow::Milestone { .. } => None,
+        }
+    }
+
+    fn move_selected(&mut self, movement: Movement) {
+        if let Some(id) = self.selected_issue_id() {
+            self.edit(|project_data, config| issue::shift(project_data, config, id, movement).map(Some));
+        }
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(PlanRow::Issue { id, completed, .. }) = self.plan.get(self.selected_step).cloned() else {
+            return;
+        };
+        let status = if completed {
+            IssueStatus::Open
+        } else {
+            IssueStatus::Completed
+        };
+        let update = IssueUpdate {
+            status: Some(status),
+            ..Default::default()
+        };
+        self.edit(|project_data, config| issue::update(project_data, config, id, update).map(|_| None));
+    }
+
+    fn rename_selected(&mut self, new_name: String) {
+        if let Some(id) = self.selected_issue_id() {
+            let update = IssueUpdate {
+                name: Some(new_name.clone()),
+                ..Default::default()
+            };
+            self.edit(|project_data, config| issue::update(project_data, config, id, update).map(|_| None));
+        }
+    }
+
+    fn remove_selected(&mut self) {
+        if let Some(id) = self.selected_issue_id() {
+            self.edit(|project_data, config| issue::remove(project_data, config, id).map(|_| None));
+        }
+    }
+
+    /// Applies the edit to the selected project files and reloads the plan. The edit returns the new location of
+    /// the issue if it is moved, otherwise the selection keeps its row, since the issue ids may change with the
+    /// positions of the issues and the names may repeat.
+    fn edit(&mut self, edit: impl FnOnce(ProjectData, &SourceConfig) -> io::Result<Option<SourceLocation>>) {
+        let Some(project_config) = self
+            .selected_project()
+            .and_then(|project| self.project_configs.get(&project.id))
+            .cloned()
+        else {
+            return;
+        };
+
+        let project_data = ProjectData::Fs(FsProjectMetadata::default().with_config(project_config));
+        let moved_to = match edit(project_data, &self.config.source) {
+            Ok(moved_to) => moved_to,
+            Err(err) => {
+                self.message = Some(err.to_string());
+                return;
+            },
+        };
+
+        if let Err(err) = self.reload(moved_to.as_ref()) {
+            self.message = Some(err.to_string());
+        }
+    }
+
+    fn reload(&mut self, moved_to: Option<&SourceLocation>) -> io::Result<()> {
+        self.tracker = load_tracker(&self.project_configs, &self.config)?;
+        self.plan = self.selected_plan().map(plan_rows).unwrap_or_default();
+
+        let moved_id = moved_to.and_then(|moved_to| {
+            let plan = self.selected_plan()?;
+            plan.steps().iter().find_map(|step| match step {
+                Step::Issue(id) => plan
+                    .get_issue(id)
+                    .filter(|issue| {
+                        issue.location.as_ref().is_some_and(|location| {
+                            location.path == moved_to.path && location.lines.start == moved_to.lines.start
+                        })
+                    })
+                    .map(|issue| issue.id),
+                Step::Milestone(_) => None,
+            })
+        });
+        let selected = moved_id.and_then(|moved_id| {
+            self.plan
+                .iter()
+                .position(|row| matches!(row, PlanRow::Issue { id, .. } if *id == moved_id))
+        });
+        self.selected_step = selected.unwrap_or(self.selected_step.min(self.plan.len().saturating_sub(1)));
+        Ok(())
+    }
+}
+
+fn load_tracker(project_configs: &IndexMap<String, FsProjectConfig>, config: &Config) -> io::Result<FsTracker> {
+    let tracker = FsTracker::new(
+        project_configs.clone(),
+        &config.source.manifest_filename_regex,
+        &config.source.issues_filename_regex,
+        &config.source.list,
+    );
+    tracker.load_plans()?;
+    Ok(tracker)
+}
+
+/// Projects in the depth-first order of the project tree.
+fn project_rows(tracker: &FsTracker) -> Vec<ProjectRow> {
+    fn push_rows(tracker: &FsTracker, id: &String, depth: usize, rows: &mut Vec<ProjectRow>) {
+        if let Some(project) = tracker.projects().get(id) {
+            rows.push(ProjectRow {
+                id: id.clone(),
+                name: project.name().to_string(),
+                depth,
+            });
+        }
+        for child in tracker.subprojects().get(id).into_iter().flatten() {
+            push_rows(tracker, child, depth + 1, rows);
+        }
+    }
+
+    let mut rows = Vec::new();
+    for id in tracker.projects().keys() {
+        if !tracker.project_parents().contains_key(id) {
+            push_rows(tracker, id, 0, &mut rows);
+        }
+    }
+    rows
+}
+
+fn plan_rows(plan: &Plan<u64>) -> Vec<PlanRow> {
+    plan.steps()
+        .iter()
+        .filter_map(|step| match step {
+            Step::Issue(id) => plan.get_issue(id).map(|issue| {
+                let mut level = 0;
+                let mut parent_id = issue.parent_id;
+                while let Some(parent) = parent_id.and_then(|id| plan.get_issue(&id)) {
+                    level += 1;
+                    parent_id = parent.parent_id;
+                }
+
+                PlanRow::Issue {
+                    id: *id,
+                    name: issue.name.clone(),
+                    level,
+                    completed: issue.is_completed(),
+                }
+            }),
+            Step::Milestone(id) => plan.get_milestone(id).map(|milestone| PlanRow::Milestone {
+                id: *id,
+                name: milestone.name.clone(),
+            }),
+        })
+        .collect()
+}
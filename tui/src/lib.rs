@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use ratatui::backend::Backend;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::Terminal;
+use todo_app::config::Config;
+
+pub use crate::app::App;
+
+pub mod app;
+pub mod ui;
+
+/// Runs the terminal UI in the alternate screen until the user quits.
+pub fn run(config: Config, search_roots: Vec<PathBuf>) -> anyhow::Result<()> {
+    let mut app = App::new(config, search_roots)?;
+
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, &mut app);
+    ratatui::restore();
+
+    result
+}
+
+fn run_app(terminal: &mut Terminal<impl Backend>, app: &mut App) -> anyhow::Result<()> {
+    while !app.should_quit() {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key);
+        }
+    }
+
+    Ok(())
+}
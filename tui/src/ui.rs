@@ -0,0 +1,80 @@
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::app::{App, Mode, Pane, PlanRow};
+
+const HELP: &str = "Tab pane  j/k select  J/K move  >/< indent  x complete  r rename  d delete  q quit";
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main_area, status_area] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    let [projects_area, plan_area] =
+        Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(main_area);
+
+    let projects: Vec<_> = app
+        .projects()
+        .iter()
+        .map(|project| ListItem::new(format!("{:indent$}{}", "", project.name, indent = project.depth * 2)))
+        .collect();
+    let mut projects_state = ListState::default().with_selected(Some(app.selected_project_idx()));
+    frame.render_stateful_widget(
+        List::new(projects)
+            .block(pane_block("Projects", app.pane() == Pane::Projects))
+            .highlight_style(highlight_style(app.pane() == Pane::Projects)),
+        projects_area,
+        &mut projects_state,
+    );
+
+    let steps: Vec<_> = app
+        .plan()
+        .iter()
+        .map(|row| match row {
+            PlanRow::Issue {
+                name, level, completed, ..
+            } => {
+                let checkbox = if *completed { "[x]" } else { "[ ]" };
+                let line = Line::from(format!("{:indent$}{checkbox} {name}", "", indent = level * 2));
+                ListItem::new(if *completed { line.dim() } else { line })
+            },
+            PlanRow::Milestone { name, .. } => ListItem::new(Line::from(format!("# {name}")).bold()),
+        })
+        .collect();
+    let title = app
+        .selected_project()
+        .map(|project| format!("Plan: {}", project.name))
+        .unwrap_or_else(|| "Plan".into());
+    let mut plan_state = ListState::default().with_selected((!steps.is_empty()).then_some(app.selected_step_idx()));
+    frame.render_stateful_widget(
+        List::new(steps)
+            .block(pane_block(title, app.pane() == Pane::Plan))
+            .highlight_style(highlight_style(app.pane() == Pane::Plan)),
+        plan_area,
+        &mut plan_state,
+    );
+
+    let status = match app.mode() {
+        Mode::Rename(name) => format!("Rename: {name}_"),
+        Mode::ConfirmRemove => "Delete the issue with its subissues? (y/n)".into(),
+        Mode::Normal => app.message().unwrap_or(HELP).into(),
+    };
+    frame.render_widget(Paragraph::new(status), status_area);
+}
+
+fn pane_block<'a>(title: impl Into<Line<'a>>, is_active: bool) -> Block<'a> {
+    let block = Block::bordered().title(title);
+    if is_active {
+        block.border_style(Style::new().bold())
+    } else {
+        block
+    }
+}
+
+fn highlight_style(is_active: bool) -> Style {
+    if is_active {
+        Style::new().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::new().add_modifier(Modifier::UNDERLINED)
+    }
+}
@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::Path;
+
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+use temp_testdir::TempDir;
+use todo_app::config::Config;
+use todo_tui::app::{Mode, Pane, PlanRow};
+use todo_tui::{ui, App};
+
+fn create_project(root: &Path, id: &str, todo: &str) {
+    let project_dir = root.join(id);
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::write(
+        project_dir.join("Project.toml"),
+        format!("id = \"{id}\"\nname = \"{id}\"\n"),
+    )
+    .unwrap();
+    fs::write(project_dir.join("TODO.md"), todo).unwrap();
+}
+
+fn press(app: &mut App, keys: &str) {
+    for ch in keys.chars() {
+        let code = match ch {
+            '\t' => KeyCode::Tab,
+            '\n' => KeyCode::Enter,
+            '\x08' => KeyCode::Backspace,
+            ch => KeyCode::Char(ch),
+        };
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+}
+
+fn render(app: &App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+    terminal.draw(|frame| ui::draw(frame, app)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let mut text = String::new();
+    for y in 0..buffer.area.height {
+        for x in 0..buffer.area.width {
+            text.push_str(buffer[(x, y)].symbol());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn issue_names(app: &App) -> Vec<(usize, &str)> {
+    app.plan()
+        .iter()
+        .filter_map(|row| match row {
+            PlanRow::Issue { name, level, .. } => Some((*level, name.as_str())),
+            PlanRow::Milestone { .. } => None,
+        })
+        .collect()
+}
+
+#[test]
+fn render_projects_and_plan() {
+    let temp_dir = TempDir::default();
+    create_project(&temp_dir, "alpha", "- task A\n  - [x] task AA\n\n# Mile\n");
+    create_project(&temp_dir.join("alpha"), "beta", "- task B\n");
+
+    let mut app = App::new(Config::default(), vec![temp_dir.to_path_buf()]).unwrap();
+    assert_eq!(app.projects().len(), 2);
+    assert_eq!(app.projects()[1].depth, 1);
+
+    let screen = render(&app);
+    assert!(screen.contains("alpha"), "{screen}");
+    assert!(screen.contains("  beta"), "{screen}");
+    assert!(screen.contains("[ ] task A"), "{screen}");
+    assert!(screen.contains("  [x] task AA"), "{screen}");
+    assert!(screen.contains("# Mile"), "{screen}");
+
+    press(&mut app, "j");
+    assert_eq!(app.selected_project().unwrap().id, "beta");
+    assert!(render(&app).contains("[ ] task B"));
+
+    press(&mut app, "\t");
+    assert_eq!(app.pane(), Pane::Plan);
+    press(&mut app, "q");
+    assert!(app.should_quit());
+}
+
+#[test]
+fn edit_issues() {
+    let temp_dir = TempDir::default();
+    create_project(&temp_dir, "alpha", "- task A\n- task B\n- task C\n");
+    let todo_file = temp_dir.join("alpha").join("TODO.md");
+
+    let mut app = App::new(Config::default(), vec![temp_dir.to_path_buf()]).unwrap();
+    press(&mut app, "\tjJ");
+    assert_eq!(
+        fs::read_to_string(&todo_file).unwrap(),
+        "- task A\n- task C\n- task B\n"
+    );
+    assert_eq!(app.selected_step_idx(), 2);
+
+    press(&mut app, ">");
+    assert_eq!(issue_names(&app), vec![(0, "task A"), (0, "task C"), (1, "task B")]);
+    press(&mut app, "<K");
+    assert_eq!(
+        fs::read_to_string(&todo_file).unwrap(),
+        "- task A\n- task B\n- task C\n"
+    );
+
+    press(&mut app, "x");
+    assert_eq!(
+        fs::read_to_string(&todo_file).unwrap(),
+        "- task A\n- [x] task B\n- task C\n"
+    );
+    press(&mut app, " ");
+    assert_eq!(
+        fs::read_to_string(&todo_file).unwrap(),
+        "- task A\n- [ ] task B\n- task C\n"
+    );
+
+    press(&mut app, "r\x08two");
+    assert_eq!(app.mode(), &Mode::Rename("task two".into()));
+    press(&mut app, "\n");
+    assert_eq!(
+        fs::read_to_string(&todo_file).unwrap(),
+        "- task A\n- [ ] task two\n- task C\n"
+    );
+
+    press(&mut app, "dn");
+    assert_eq!(issue_names(&app).len(), 3);
+    press(&mut app, "dy");
+    assert_eq!(fs::read_to_string(&todo_file).unwrap(), "- task A\n- task C\n");
+
+    press(&mut app, "k<");
+    assert_eq!(app.message(), Some("top-level issue cannot be outdented"));
+    assert!(render(&app).contains("top-level issue cannot be outdented"));
+}
+
+#[test]
+fn keep_selection_of_issues_with_same_names() {
+    let temp_dir = TempDir::default();
+    create_project(&temp_dir, "alpha", "- task\n- task\n- other\n- task\n");
+    let todo_file = temp_dir.join("alpha").join("TODO.md");
+
+    let mut app = App::new(Config::default(), vec![temp_dir.to_path_buf()]).unwrap();
+    press(&mut app, "\tjJ");
+    assert_eq!(
+        fs::read_to_string(&todo_file).unwrap(),
+        "- task\n- other\n- task\n- task\n"
+    );
+    assert_eq!(app.selected_step_idx(), 2);
+
+    press(&mut app, "jx");
+    assert_eq!(
+        fs::read_to_string(&todo_file).unwrap(),
+        "- task\n- other\n- task\n- [x] task\n"
+    );
+    assert_eq!(app.selected_step_idx(), 3);
+}